@@ -8,10 +8,38 @@
 mod state;
 
 use linera_sdk::{
-    base::WithContractAbi,
+    base::{
+        Account, AccountOwner, Amount, ApplicationPermissions, ChainId, StreamName,
+        WithContractAbi,
+    },
     Contract, ContractRuntime,
 };
-use game_station::{GameType, Message, Operation};
+use game_station::{
+    cosmetics,
+    games::{
+        blackjack::{self, BlackjackResult},
+        chess::Color,
+        commitment, daily_word,
+        minesweeper::{self, MinesweeperResult},
+        snake, sudoku, tetris,
+        trivia::{self, TriviaQuestion},
+        twenty_forty_eight, GameOutcome, MoveError,
+    },
+    nft::{NftAbi, NftOperation, NftResponse},
+    quests::QuestEvent,
+    tournament::{self, Pairing, TournamentStatus},
+    BattleshipState, BlackjackAction, ChatMessage, CheckersState, ChessState, ClanRole,
+    ConnectFourState, CreditReason, DotsAndBoxesState, Encounter, GameEvent, GameSession, GameState,
+    GameStationConfig, GameStationError, GameStationParameters, GameType, GomokuState,
+    LeaderboardKind, LudoState, MatchRecord, MatchResult, MemoryState, Message, MoveRecord,
+    Operation, PlayerMark, PlayerReport, PokerState, RecentGame, Response, ReversiState,
+    RockPaperScissorsState, RoomStatus, RoomVisibility, SnakeLaddersState, TicTacToeState,
+    TipAmount, TipRecord, TournamentFormat, TriviaState, UnoState, YahtzeeState,
+    CREDITS_PER_PRESTIGE, CREDITS_PER_WIN, MAX_BLOCK_LIST, MAX_CHAT_MESSAGE_LEN, MAX_CLAN_NAME_LEN,
+    MAX_CLAN_SIZE, MAX_CLAN_TAG_LEN, MAX_RAKE_BPS, MAX_REPORT_REASON_LEN, MAX_TIP_MEMO_LEN,
+    MICROS_PER_DAY, MIN_CLAN_TAG_LEN, PASS_FREE_REWARD, PASS_PREMIUM_REWARD_PER_TIER,
+    ROOM_BOOST_COST, ROOM_BOOST_DURATION,
+};
 use state::GameStationState;
 
 pub struct GameStationContract {
@@ -27,128 +55,2660 @@ impl WithContractAbi for GameStationContract {
 
 impl Contract for GameStationContract {
     type Message = Message;
-    type Parameters = ();
-    type InstantiationArgument = ();
-
+    type Parameters = GameStationParameters;
+    type EventValue = GameEvent;
+    type InstantiationArgument = GameStationConfig;
+    
     async fn load(runtime: ContractRuntime<Self>) -> Self {
-        let state = GameStationState::load(runtime.root_view_storage_context())
+        let mut state = GameStationState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
+        state.migrate().await.expect("Failed to migrate state");
         GameStationContract { state, runtime }
     }
-
-    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
-        // Initialize the game station with default state
+    
+    async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
+        // Initialize the game station with the deployment configuration
+        self.state.config.set(argument);
+        self.state.start_seasons(self.runtime.system_time().micros());
         log::info!("Linera Game Station initialized!");
     }
-
+    
     async fn execute_operation(&mut self, operation: Operation) -> Self::Response {
-        let owner = self.runtime
-            .authenticated_signer()
-            .map(|s| format!("{:?}", s))
-            .unwrap_or_else(|| "anonymous".to_string());
+        self.handle_operation(operation)
+            .await
+            .unwrap_or_else(|error| panic!("Operation failed: {error}"))
+    }
+    
+    async fn execute_message(&mut self, message: Message) {
+        self.handle_message(message)
+            .await
+            .unwrap_or_else(|error| panic!("Message failed: {error}"))
+    }
+    
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl GameStationContract {
+    /// Apply an operation, failing without side effects if it is not allowed
+    async fn handle_operation(&mut self, operation: Operation) -> Result<Response, GameStationError> {
+        // Every operation changes state on someone's behalf, so it must be signed
+        let Some(owner) = self.runtime.authenticated_signer() else {
+            return Err(GameStationError::Unauthenticated);
+        };
+        
+        let is_admin = self.runtime.application_parameters().admin == Some(owner);
+        if *self.state.paused.get() && !is_admin {
+            return Err(GameStationError::Paused);
+        }
+        if !is_admin && self.state.banned.contains_key(&owner).await? {
+            return Err(GameStationError::Banned);
+        }
+        
+        let now = self.runtime.system_time().micros();
+        self.state.roll_season(now);
+        self.state.touch_user(&owner, now).await?;
         
         match operation {
             Operation::SubmitSnakeScore { score } => {
                 log::info!("Player {} submitting Snake score: {}", owner, score);
+                self.record_snake_score(owner, score).await
+            }
+            
+            Operation::SubmitVerifiedSnakeScore { score, seed, turns } => {
+                log::info!("Player {} submitting verified Snake score: {}", owner, score);
                 
-                // Ensure user profile exists
-                self.state.get_or_create_user(&owner).await;
+                let replayed = snake::replay(seed, &turns)?.score;
+                if replayed != score {
+                    return Err(GameStationError::ScoreMismatch { claimed: score, replayed });
+                }
+                self.record_snake_score(owner, score).await
+            }
+            
+            Operation::StartTwentyFortyEight => {
+                let now = self.runtime.system_time().micros();
+                let seed = self.session_seed(&owner, b"2048", now);
+                let session = GameSession { seed, started_at: now };
+                self.state.twenty_forty_eight_sessions.insert(&owner, session)?;
+                Ok(Response::SessionStarted { seed })
+            }
+            
+            Operation::SubmitTwentyFortyEightScore { score, moves } => {
+                log::info!("Player {} submitting 2048 score: {}", owner, score);
+                
+                let Some(session) = self.state.twenty_forty_eight_sessions.get(&owner).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                let replayed = twenty_forty_eight::replay(session.seed, &moves)?.score;
+                if replayed != score {
+                    return Err(GameStationError::ScoreMismatch { claimed: score, replayed });
+                }
+                self.state.twenty_forty_eight_sessions.remove(&owner)?;
+                self.record_session_score(GameType::TwentyFortyEight, owner, score).await
+            }
+            
+            Operation::StartTetris => {
+                let now = self.runtime.system_time().micros();
+                let seed = self.session_seed(&owner, b"tetris", now);
+                let session = GameSession { seed, started_at: now };
+                self.state.tetris_sessions.insert(&owner, session)?;
+                Ok(Response::SessionStarted { seed })
+            }
+            
+            Operation::SubmitTetrisScore { score, inputs } => {
+                log::info!("Player {} submitting Tetris score: {}", owner, score);
+                
+                let Some(session) = self.state.tetris_sessions.get(&owner).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                let replayed = tetris::replay(session.seed, &inputs)?.score;
+                if replayed != score {
+                    return Err(GameStationError::ScoreMismatch { claimed: score, replayed });
+                }
+                self.state.tetris_sessions.remove(&owner)?;
+                self.record_session_score(GameType::Tetris, owner, score).await
+            }
+            
+            Operation::StartMinesweeper => {
+                let pending = self.state.minesweeper_sessions.get(&owner).await?;
+                if pending.is_some_and(|session| session.submitted_at.is_some()) {
+                    return Err(GameStationError::SessionAlreadySubmitted);
+                }
+                let Some(board_commitment) = self.state.minesweeper_boards.front().await? else {
+                    return Err(GameStationError::NoBoardAvailable);
+                };
+                self.state.minesweeper_boards.delete_front();
+                
+                let session = state::MinesweeperSession {
+                    board_commitment,
+                    started_at: self.runtime.system_time().micros(),
+                    clicks: Vec::new(),
+                    submitted_at: None,
+                };
+                self.state.minesweeper_sessions.insert(&owner, session)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::SubmitMinesweeperClicks { clicks } => {
+                let Some(mut session) = self.state.minesweeper_sessions.get(&owner).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                if session.submitted_at.is_some() {
+                    return Err(GameStationError::SessionAlreadySubmitted);
+                }
+                // Clicks can't claim to be later than the submission itself
+                let now = self.runtime.system_time().micros();
+                minesweeper::validate_clicks(&clicks, (now - session.started_at) / 1_000)?;
+                
+                session.clicks = clicks;
+                session.submitted_at = Some(now);
+                self.state.minesweeper_sessions.insert(&owner, session)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::SubmitSudokuSolution { day, grid } => {
+                if day > now / MICROS_PER_DAY {
+                    return Err(GameStationError::PuzzleNotPublished);
+                }
+                let puzzle = sudoku::daily_puzzle(day);
+                let solved = sudoku::grid_from_digits(&grid)
+                    .is_some_and(|grid| sudoku::is_solution(&puzzle.givens, &grid));
+                if !solved {
+                    return Err(GameStationError::InvalidSudokuSolution);
+                }
+                
+                self.state.get_or_create_user(&owner).await?;
+                let solve_time = now - day * MICROS_PER_DAY;
+                if !self.state.record_sudoku_solve(&owner, day, solve_time, now).await? {
+                    return Err(GameStationError::AlreadySolved);
+                }
+                log::info!("Player {} solved the day {} Sudoku in {}us", owner, day, solve_time);
                 
-                // Update score and leaderboard
-                let is_new_high = self.state.update_snake_score(&owner, score).await;
+                let record = MatchRecord {
+                    game_type: GameType::Sudoku,
+                    opponent: None,
+                    result: MatchResult::Completed,
+                    score: solve_time,
+                    timestamp: now,
+                };
+                self.state.record_match(&owner, record).await?;
+                Ok(Response::SudokuSolved { solve_time })
+            }
+            
+            Operation::SubmitWordGuess { day, guess } => {
+                if !daily_word::is_valid_word(&guess) {
+                    return Err(GameStationError::InvalidWord);
+                }
+                // Guesses are taken on the word's own day, until it is revealed
+                let word = self.state.daily_words.get(&day).await?;
+                let unrevealed = word.is_some_and(|word| word.answer.is_none());
+                if day != now / MICROS_PER_DAY || !unrevealed {
+                    return Err(GameStationError::WordNotOpen);
+                }
+                self.state.get_or_create_user(&owner).await?;
+                if !self.state.record_word_guess(&owner, day, guess).await? {
+                    return Err(GameStationError::OutOfGuesses);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::StartBlackjack { bet, salt } => {
+                if bet == Amount::ZERO {
+                    return Err(GameStationError::InvalidWager);
+                }
+                if self.state.blackjack_hands.contains_key(&owner).await? {
+                    return Err(GameStationError::HandInProgress);
+                }
+                // A doubled win pays out the most, twice the bet on top of the stakes
+                let reserved = bet.saturating_mul(2);
+                let mut bankroll = *self.state.house_bankroll.get();
+                if reserved > bankroll {
+                    return Err(GameStationError::HouseBankrollTooLow);
+                }
+                let Some(chain_head) = self.state.blackjack_shoes.front().await? else {
+                    return Err(GameStationError::NoShoeAvailable);
+                };
+                self.state.blackjack_shoes.delete_front();
+                self.escrow_wager(owner, bet).await?;
+                bankroll.saturating_sub_assign(reserved);
+                self.state.house_bankroll.set(bankroll);
                 
-                if is_new_high {
-                    log::info!("New high score for player {}!", owner);
+                log::info!("Player {} betting {} on a blackjack hand", owner, bet);
+                let session = state::BlackjackSession {
+                    chain_head,
+                    salt,
+                    bet,
+                    reserved,
+                    hand: Default::default(),
+                    updated_at: now,
+                };
+                self.state.blackjack_hands.insert(&owner, session)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::PlayBlackjack { action } => {
+                let Some(mut session) = self.state.blackjack_hands.get(&owner).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                session.hand.act(action)?;
+                if action == BlackjackAction::DoubleDown {
+                    self.escrow_wager(owner, session.bet).await?;
+                }
+                session.updated_at = now;
+                match session.hand.result {
+                    Some(result) => self.settle_blackjack(owner, session, result).await,
+                    None => {
+                        self.state.blackjack_hands.insert(&owner, session)?;
+                        Ok(Response::Ok)
+                    }
+                }
+            }
+            
+            Operation::ClaimBlackjackTimeout => {
+                let Some(session) = self.state.blackjack_hands.get(&owner).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                let deadline = session.updated_at + self.state.config.get().turn_timeout;
+                if !session.hand.wants_card() || now < deadline {
+                    return Err(GameStationError::NoTimeoutToClaim);
                 }
+                self.settle_blackjack(owner, session, BlackjackResult::Win).await
             }
             
             Operation::SubmitTicTacToeResult { won, opponent } => {
                 log::info!("Player {} submitting TicTacToe result: won={}", owner, won);
                 
                 // Ensure user profile exists
-                self.state.get_or_create_user(&owner).await;
+                self.state.get_or_create_user(&owner).await?;
                 
                 // Update stats
-                self.state.update_tictactoe_result(&owner, won).await;
+                self.state.update_tictactoe_result(&owner, won).await?;
+                
+                let now = self.runtime.system_time().micros();
+                let record = MatchRecord {
+                    game_type: GameType::TicTacToe,
+                    opponent,
+                    result: if won { MatchResult::Win } else { MatchResult::Loss },
+                    score: 0,
+                    timestamp: now,
+                };
+                self.state.record_match(&owner, record).await?;
                 
                 // If there's an opponent, update their stats too
                 if let Some(opp) = opponent {
-                    self.state.get_or_create_user(&opp).await;
-                    self.state.update_tictactoe_result(&opp, !won).await;
+                    self.state.get_or_create_user(&opp).await?;
+                    self.state.update_tictactoe_result(&opp, !won).await?;
+                    
+                    let record = MatchRecord {
+                        game_type: GameType::TicTacToe,
+                        opponent: Some(owner),
+                        result: if won { MatchResult::Loss } else { MatchResult::Win },
+                        score: 0,
+                        timestamp: now,
+                    };
+                    self.state.record_match(&opp, record).await?;
                 }
+                Ok(Response::Ok)
             }
             
-            Operation::UpdateProfile { username, avatar_id } => {
+            Operation::UpdateProfile { username, avatar_id, nft_avatar } => {
                 log::info!("Player {} updating profile: {}", owner, username);
                 
-                let mut profile = self.state.get_or_create_user(&owner).await;
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                
+                // Ask the NFT application who owns the token
+                if let Some(nft) = &nft_avatar {
+                    let operation = NftOperation::OwnerOf { token_id: nft.token_id.clone() };
+                    let response = self
+                        .runtime
+                        .call_application(false, nft.application_id.with_abi::<NftAbi>(), &operation);
+                    if response != NftResponse::Owner(Some(owner)) {
+                        return Err(GameStationError::NftNotOwned);
+                    }
+                }
+                
+                // Names are unique regardless of case
+                let new_key = username.to_lowercase();
+                let old_key = profile.username.to_lowercase();
+                if new_key != old_key {
+                    if !new_key.is_empty() {
+                        if self.state.usernames.get(&new_key).await?.is_some() {
+                            return Err(GameStationError::UsernameTaken);
+                        }
+                        self.state.usernames.insert(&new_key, owner)?;
+                    }
+                    if !old_key.is_empty() {
+                        self.state.usernames.remove(&old_key)?;
+                    }
+                    self.state.reindex_username(&owner, &old_key, &new_key).await?;
+                }
                 profile.username = username;
                 profile.avatar_id = avatar_id;
-                let _ = self.state.users.insert(&owner, profile);
+                profile.nft_avatar = nft_avatar;
+                self.state.users.insert(&owner, profile)?;
+                Ok(Response::Ok)
             }
             
-            Operation::CreateRoom { game_type, max_players, entry_fee } => {
+            Operation::CreateRoom {
+                game_type,
+                max_players,
+                entry_fee,
+                series_target,
+                visibility,
+                invite_code_hash,
+                allowlist,
+                dedicated_chain,
+                wager,
+                board_size,
+                swap2_opening,
+            } => {
                 log::info!("Player {} creating {:?} room", owner, game_type);
                 
-                let room_id = format!("{:?}-{}", game_type, self.runtime.system_time().micros());
-                let room = state::GameRoom {
-                    room_id: room_id.clone(),
+                if max_players == 0 || max_players > self.state.config.get().max_room_size {
+                    return Err(GameStationError::InvalidRoomSize);
+                }
+                for invited in &allowlist {
+                    if self.state.is_blocked_between(invited, &owner).await? {
+                        return Err(GameStationError::Blocked);
+                    }
+                }
+                if wager > Amount::ZERO && (!game_type.allows_wagers() || max_players != 2) {
+                    return Err(GameStationError::InvalidWager);
+                }
+                if game_type.seats().is_some_and(|seats| !seats.contains(&max_players)) {
+                    return Err(GameStationError::InvalidRoomSize);
+                }
+                if board_size.is_some_and(|size| !game_type.allows_board_size(size)) {
+                    return Err(GameStationError::InvalidBoardSize);
+                }
+                if swap2_opening && game_type != GameType::Gomoku {
+                    return Err(GameStationError::InvalidOpeningRule);
+                }
+                if dedicated_chain && game_type == GameType::Trivia {
+                    return Err(GameStationError::TriviaOnDedicatedChain);
+                }
+                
+                let room_id = self.state.next_room_id(game_type, self.runtime.chain_id());
+                
+                // The creator pays in like everyone else
+                let deposit = self.escrow_entry_fee(owner, entry_fee).await?;
+                let stake = self.escrow_wager(owner, wager).await?;
+                
+                let mut room = state::GameRoom::new(
+                    room_id.clone(),
                     game_type,
-                    creator: owner.clone(),
-                    players: vec![owner],
+                    owner,
+                    self.runtime.chain_id(),
                     max_players,
-                    entry_fee,
-                    status: game_station::RoomStatus::Waiting,
-                    game_state: None,
-                    created_at: self.runtime.system_time().micros() as u64,
-                };
+                    self.runtime.system_time().micros(),
+                    self.state.config.get().waiting_room_ttl,
+                );
+                room.visibility = visibility;
+                room.invite_code_hash = invite_code_hash;
+                room.allowlist = allowlist;
+                room.entry_fee = entry_fee;
+                room.escrow = deposit.amount;
+                room.deposits = vec![deposit];
+                room.wager = wager;
+                room.wager_deposits = vec![stake];
+                room.rake_bps = self.state.config.get().rake_bps;
+                room.series_target = series_target.max(1);
+                room.dedicated_chain = dedicated_chain;
+                room.board_size = board_size;
+                room.swap2_opening = swap2_opening;
+                
+                // A solo room is full as soon as it exists, so there is no one to wait for
+                if max_players == 1 && game_type.seats().is_some_and(|seats| seats.contains(&1)) {
+                    room.start(room.created_at + self.state.config.get().turn_timeout);
+                    if room.dedicated_chain {
+                        self.host_on_new_chain(&mut room);
+                    }
+                }
                 
-                let _ = self.state.rooms.insert(&room_id, room);
+                self.emit_event(GameEvent::RoomCreated {
+                    room_id: room_id.clone(),
+                    game_type,
+                    creator: owner,
+                    timestamp: room.created_at,
+                });
+                self.state.save_room(room).await?;
+                Ok(Response::RoomCreated { room_id })
             }
             
-            Operation::JoinRoom { room_id } => {
+            Operation::JoinRoom { room_id, invite_proof, accept_wager } => {
                 log::info!("Player {} joining room {}", owner, room_id);
                 
-                if let Some(mut room) = self.state.rooms.get(&room_id).await.ok().flatten() {
-                    if room.players.len() < room.max_players as usize {
-                        room.players.push(owner.clone());
-                        
-                        // Start game if room is full
-                        if room.players.len() == room.max_players as usize {
-                            room.status = game_station::RoomStatus::InProgress;
-                        }
-                        
-                        let _ = self.state.rooms.insert(&room_id, room);
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if room.is_expired(self.runtime.system_time().micros()) {
+                    // Expired rooms are refunded by `CleanupExpiredRooms`
+                    return Err(GameStationError::RoomExpired);
+                }
+                
+                if !room.admits(&owner, invite_proof) {
+                    log::warn!("Player {} is not invited to room {}", owner, room_id);
+                    return Err(GameStationError::NotInvited);
+                }
+                
+                if room.players.len() >= room.max_players as usize
+                    || room.status != RoomStatus::Waiting
+                    || room.players.contains(&owner)
+                {
+                    return Err(GameStationError::RoomNotJoinable);
+                }
+                for player in &room.players {
+                    if self.state.is_blocked_between(player, &owner).await? {
+                        return Err(GameStationError::Blocked);
+                    }
+                }
+                
+                // Joining never stakes more than the player agreed to
+                if room.wager > Amount::ZERO && accept_wager != Some(room.wager) {
+                    return Err(GameStationError::WagerNotAccepted);
+                }
+                
+                let deposit = self.escrow_entry_fee(owner, room.entry_fee).await?;
+                room.escrow.saturating_add_assign(deposit.amount);
+                room.deposits.push(deposit);
+                let stake = self.escrow_wager(owner, room.wager).await?;
+                room.wager_deposits.push(stake);
+                room.player_chains.push((owner, self.runtime.chain_id()));
+                room.players.push(owner);
+                self.notify_room(&room, Message::PlayerJoined { room_id: room_id.clone(), player: owner });
+                
+                // Once the room is full, everyone confirms before play starts
+                let ready_check = room.players.len() == room.max_players as usize;
+                if ready_check {
+                    room.begin_ready_check(now + self.state.config.get().ready_check_timeout);
+                }
+                
+                self.state.save_room(room).await?;
+                Ok(Response::RoomJoined { room_id, ready_check })
+            }
+            
+            Operation::Ready { room_id } => {
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if room.status != RoomStatus::ReadyCheck || room.missed_ready_check(now) {
+                    return Err(GameStationError::NoReadyCheck);
+                }
+                if !room.players.contains(&owner) {
+                    return Err(GameStationError::NotInRoom);
+                }
+                if !room.ready.contains(&owner) {
+                    room.ready.push(owner);
+                }
+                
+                let started = room.ready.len() == room.players.len();
+                if started {
+                    log::info!("Everyone is ready in room {}", room_id);
+                    room.ready_deadline = None;
+                    room.start(now + self.state.config.get().turn_timeout);
+                    if room.dedicated_chain {
+                        self.host_on_new_chain(&mut room);
+                    }
+                }
+                
+                self.state.save_room(room).await?;
+                Ok(Response::ReadyConfirmed { started })
+            }
+            
+            Operation::LeaveRoom { room_id } => {
+                log::info!("Player {} leaving room {}", owner, room_id);
+                
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if room.status == RoomStatus::InProgress || !room.players.contains(&owner) {
+                    return Err(GameStationError::CannotLeaveRoom);
+                }
+                
+                for deposit in room.remove_player(&owner) {
+                    self.refund_deposit(deposit).await?;
+                }
+                
+                let room_closed = room.players.is_empty();
+                if room_closed {
+                    self.state.delete_room(&room_id).await?;
+                    self.state.room_chat.remove_entry(&room_id)?;
+                    self.state.move_logs.remove_entry(&room_id)?;
+                } else {
+                    self.state.save_room(room).await?;
+                }
+                Ok(Response::RoomLeft { room_closed })
+            }
+            
+            Operation::KickPlayer { room_id, player } => {
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if room.creator != owner {
+                    return Err(GameStationError::NotRoomCreator);
+                }
+                if !matches!(room.status, RoomStatus::Waiting | RoomStatus::ReadyCheck) {
+                    return Err(GameStationError::CannotLeaveRoom);
+                }
+                if player == owner || !room.players.contains(&player) {
+                    return Err(GameStationError::NotInRoom);
+                }
+                
+                log::info!("Host {} kicked {} from room {}", owner, player, room_id);
+                for deposit in room.remove_player(&player) {
+                    self.refund_deposit(deposit).await?;
+                }
+                room.allowlist.retain(|p| *p != player);
+                self.state.save_room(room).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::EnterMatchmaking { game_type } => {
+                log::info!("Player {} entering {:?} matchmaking", owner, game_type);
+                
+                if game_type.is_single_player() {
+                    return Err(GameStationError::SinglePlayerGame);
+                }
+                
+                let profile = self.state.get_or_create_user(&owner).await?;
+                let now = self.runtime.system_time().micros();
+                let ticket = state::MatchmakingTicket {
+                    player: owner,
+                    chain_id: self.runtime.chain_id(),
+                    rating: profile.rating,
+                    queued_at: now,
+                };
+                let rating_range = self.state.config.get().matchmaking_rating_range;
+                
+                let matched = self.state.match_or_enqueue(game_type, ticket, rating_range).await?;
+                let Some(opponent) = matched else {
+                    return Ok(Response::Queued);
+                };
+                
+                // The player who waited longest hosts; nobody else can take a seat
+                let room_id = self.state.next_room_id(game_type, self.runtime.chain_id());
+                let mut room = state::GameRoom::new(
+                    room_id.clone(),
+                    game_type,
+                    opponent.player,
+                    opponent.chain_id,
+                    2,
+                    now,
+                    self.state.config.get().waiting_room_ttl,
+                );
+                room.visibility = RoomVisibility::Private;
+                room.allowlist = vec![opponent.player, owner];
+                room.players.push(owner);
+                room.player_chains.push((owner, self.runtime.chain_id()));
+                // Both players confirm before play starts, as in a room that filled up
+                room.begin_ready_check(now + self.state.config.get().ready_check_timeout);
+                
+                // The host learns about the room from these, since it was created here
+                for player in [opponent.player, owner] {
+                    self.notify_room(&room, Message::PlayerJoined { room_id: room_id.clone(), player });
+                }
+                
+                log::info!("Matched {} with {} in room {}", opponent.player, owner, room_id);
+                self.emit_event(GameEvent::RoomCreated {
+                    room_id: room_id.clone(),
+                    game_type,
+                    creator: opponent.player,
+                    timestamp: now,
+                });
+                self.state.save_room(room).await?;
+                Ok(Response::Matched { room_id })
+            }
+            
+            Operation::LeaveMatchmaking { game_type } => {
+                self.state.leave_matchmaking(game_type, &owner).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::SendFriendRequest { address } => {
+                log::info!("Player {} sending friend request to {}", owner, address);
+                if self.state.is_blocked_between(&owner, &address).await? {
+                    return Err(GameStationError::Blocked);
+                }
+                if !self.state.send_friend_request(&owner, &address).await? {
+                    return Err(GameStationError::FriendRequestsFull);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::AcceptFriendRequest { address } => {
+                log::info!("Player {} accepting friend request from {}", owner, address);
+                self.state.accept_friend_request(&owner, &address).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::RemoveFriend { address } => {
+                log::info!("Player {} removing friend {}", owner, address);
+                self.state.remove_friend(&owner, &address).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::SendRoomChat { room_id, text } => {
+                if text.is_empty() || text.len() > MAX_CHAT_MESSAGE_LEN {
+                    return Err(GameStationError::InvalidChatMessage);
+                }
+                let Some(room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if !room.players.contains(&owner) {
+                    return Err(GameStationError::NotInRoom);
+                }
+                
+                let message = ChatMessage {
+                    sender: owner,
+                    text,
+                    timestamp: self.runtime.system_time().micros(),
+                };
+                if !self.state.post_room_chat(&room_id, message).await? {
+                    return Err(GameStationError::ChatFull);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::CleanupExpiredRooms => {
+                let now = self.runtime.system_time().micros();
+                let expired = self.state.expired_rooms(now).await?;
+                let count = expired.len() as u32;
+                for room in expired {
+                    log::info!("Expiring stale room {}", room.room_id);
+                    self.expire_room(room).await?;
+                }
+                
+                let missed = self.state.missed_ready_checks(now).await?;
+                let ready_checks_failed = missed.len() as u32;
+                for mut room in missed {
+                    log::info!("Dropping unready players from room {}", room.room_id);
+                    for deposit in room.drop_unready() {
+                        self.refund_deposit(deposit).await?;
+                    }
+                    if room.players.is_empty() {
+                        self.expire_room(room).await?;
+                    } else {
+                        self.state.save_room(room).await?;
                     }
                 }
+                Ok(Response::RoomsExpired { count, ready_checks_failed })
             }
             
             Operation::SubmitMove { room_id, move_data } => {
                 log::info!("Player {} submitting move in room {}", owner, room_id);
-                // Move handling would be implemented based on game type
+                
+                let Some(room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                match room.host_chain {
+                    Some(host_chain) => {
+                        let message = Message::RoomMove { room_id, move_data };
+                        self.forward_to_host(&room, owner, host_chain, message)
+                    }
+                    None => self.submit_move(owner, room, move_data).await,
+                }
             }
-        }
-    }
-
-    async fn execute_message(&mut self, message: Message) {
-        match message {
-            Message::PlayerJoined { room_id, player } => {
-                log::info!("Player {} joined room {}", player, room_id);
+            
+            Operation::ClaimTimeout { room_id } => {
+                log::info!("Player {} claiming timeout in room {}", owner, room_id);
+                
+                let Some(room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                match room.host_chain {
+                    Some(host_chain) => {
+                        let message = Message::RoomClaimTimeout { room_id };
+                        self.forward_to_host(&room, owner, host_chain, message)
+                    }
+                    None => self.claim_timeout(owner, room).await,
+                }
             }
-            Message::GameMove { room_id, player, move_data: _ } => {
-                log::info!("Player {} made move in room {}", player, room_id);
+            
+            Operation::RequestRematch { room_id } => {
+                log::info!("Player {} requesting rematch in room {}", owner, room_id);
+                
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                // Tournament games count once towards the standings
+                if room.status != RoomStatus::Finished
+                    || room.tournament_id.is_some()
+                    || !room.players.contains(&owner)
+                    || room.rematch_requests.contains(&owner)
+                {
+                    return Err(GameStationError::CannotRematch);
+                }
+                
+                // The rematch is played for the same stakes
+                let deposit = self.escrow_entry_fee(owner, room.entry_fee).await?;
+                room.escrow.saturating_add_assign(deposit.amount);
+                room.deposits.push(deposit);
+                let stake = self.escrow_wager(owner, room.wager).await?;
+                room.wager_deposits.push(stake);
+                room.rematch_requests.push(owner);
+                
+                let started = room.players.len() > 1 && room.rematch_requests.len() == room.players.len();
+                if started {
+                    room.start_rematch();
+                    room.turn_deadline = Some(
+                        self.runtime.system_time().micros() + self.state.config.get().turn_timeout,
+                    );
+                    log::info!("Rematch started in room {}", room_id);
+                    if room.dedicated_chain {
+                        self.host_on_new_chain(&mut room);
+                    }
+                }
+                
+                self.state.save_room(room).await?;
+                Ok(Response::RematchRequested { started })
             }
-            Message::GameEnded { room_id, winner, scores: _ } => {
-                log::info!("Game {} ended. Winner: {:?}", room_id, winner);
+            
+            Operation::LinkIdentity { primary_address, secondary_address, target_chain } => {
+                log::info!("Player {} linking {} -> {}", owner, secondary_address, primary_address);
+                
+                if owner != primary_address && owner != secondary_address {
+                    log::warn!("Player {} is not a party to this identity link", owner);
+                    return Err(GameStationError::NotPartyToLink);
+                }
+                if !self.state.can_link_identity(&primary_address, &secondary_address).await? {
+                    return Err(GameStationError::IdentityAlreadyLinked);
+                }
+                
+                let linked = self.state
+                    .record_identity_consent(&primary_address, &secondary_address, &owner)
+                    .await?;
+                
+                // Forward our consent so the other chain can complete the link
+                self.runtime
+                    .prepare_message(Message::LinkIdentity {
+                        primary_address,
+                        secondary_address,
+                        proof: owner,
+                    })
+                    .with_authentication()
+                    .send_to(target_chain);
+                Ok(Response::IdentityLinkConsented { linked })
             }
-            Message::LeaderboardUpdate { game_type, entry } => {
-                log::info!("Leaderboard update for {:?}: {}", game_type, entry.player_name);
+            
+            Operation::ClaimDailyTokens => {
+                let day = self.runtime.system_time().micros() / MICROS_PER_DAY;
+                
+                match self.state.claim_daily_tokens(&owner, day).await? {
+                    Some(amount) => {
+                        log::info!("Player {} claimed {} free-play tokens", owner, amount);
+                        Ok(Response::TokensClaimed { amount })
+                    }
+                    None => Err(GameStationError::NothingToClaim),
+                }
             }
-        }
-    }
-
-    async fn store(mut self) {
-        self.state.save().await.expect("Failed to save state");
+            
+            Operation::SetPaused { paused } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} set paused to {}", owner, paused);
+                self.state.paused.set(paused);
+                Ok(Response::Ok)
+            }
+            
+            Operation::RemoveLeaderboardEntry { game_type, player } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} removing {} from the {:?} board", owner, player, game_type);
+                self.state.remove_leaderboard_entry(game_type, &player).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::ForceCloseRoom { room_id } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} force-closing room {}", owner, room_id);
+                let room = self.state.rooms.get(&room_id).await?.ok_or(GameStationError::RoomNotFound)?;
+                self.expire_room(room).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::ReportPlayer { player, reason } => {
+                if player == owner || reason.trim().is_empty() || reason.len() > MAX_REPORT_REASON_LEN {
+                    return Err(GameStationError::InvalidReport);
+                }
+                log::info!("Player {} reported {}", owner, player);
+                let report = PlayerReport {
+                    reporter: owner,
+                    reason,
+                    timestamp: now,
+                };
+                self.state.file_report(&player, report).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::BanPlayer { player } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} banned {}", owner, player);
+                self.state.banned.insert(&player, now)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::UnbanPlayer { player } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} unbanned {}", owner, player);
+                self.state.banned.remove(&player)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::CreateTournament {
+                game_type,
+                format,
+                max_players,
+                entry_fee,
+                check_in_window,
+                refund_no_shows,
+            } => {
+                log::info!("Player {} creating {:?} tournament", owner, game_type);
+                
+                if game_type.is_single_player() {
+                    return Err(GameStationError::SinglePlayerGame);
+                }
+                
+                let tournament_id = self.state.next_tournament_id(self.runtime.chain_id());
+                let mut tournament = state::Tournament {
+                    tournament_id: tournament_id.clone(),
+                    game_type,
+                    creator: owner,
+                    format,
+                    status: TournamentStatus::Registration,
+                    max_players,
+                    entry_fee,
+                    deposits: Vec::new(),
+                    players: vec![(owner, self.runtime.chain_id())],
+                    check_in_window,
+                    check_in_deadline: None,
+                    checked_in: Vec::new(),
+                    refund_no_shows,
+                    current_round: 0,
+                    pairings: Vec::new(),
+                    created_at: now,
+                };
+                if !format.is_valid() || max_players < 2 {
+                    return Err(GameStationError::InvalidTournament);
+                }
+                
+                // The creator plays and pays in like everyone else
+                let deposit = self.escrow_entry_fee(owner, entry_fee).await?;
+                tournament.deposits.push(deposit);
+                self.state.tournaments.insert(&tournament_id, tournament)?;
+                Ok(Response::TournamentCreated { tournament_id })
+            }
+            
+            Operation::JoinTournament { tournament_id } => {
+                log::info!("Player {} joining tournament {}", owner, tournament_id);
+                
+                let Some(mut tournament) = self.state.tournaments.get(&tournament_id).await? else {
+                    return Err(GameStationError::TournamentNotFound);
+                };
+                if tournament.status != TournamentStatus::Registration
+                    || tournament.players.len() >= tournament.max_players as usize
+                    || tournament.players.iter().any(|(player, _)| *player == owner)
+                {
+                    return Err(GameStationError::TournamentNotJoinable);
+                }
+                
+                let deposit = self.escrow_entry_fee(owner, tournament.entry_fee).await?;
+                tournament.deposits.push(deposit);
+                tournament.players.push((owner, self.runtime.chain_id()));
+                self.state.tournaments.insert(&tournament_id, tournament)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::CheckInTournament { tournament_id } => {
+                let Some(mut tournament) = self.state.tournaments.get(&tournament_id).await? else {
+                    return Err(GameStationError::TournamentNotFound);
+                };
+                if tournament.status != TournamentStatus::CheckIn
+                    || tournament.check_in_deadline.is_some_and(|deadline| now >= deadline)
+                {
+                    return Err(GameStationError::CheckInClosed);
+                }
+                if !tournament.players.iter().any(|(player, _)| *player == owner) {
+                    return Err(GameStationError::NotRegistered);
+                }
+                
+                if !tournament.checked_in.contains(&owner) {
+                    log::info!("Player {} checked in to tournament {}", owner, tournament_id);
+                    tournament.checked_in.push(owner);
+                    self.state.tournaments.insert(&tournament_id, tournament)?;
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::Prestige => {
+                let Some(rank) = self.state.prestige(&owner).await? else {
+                    return Err(GameStationError::CannotPrestige);
+                };
+                log::info!("Player {} reached prestige {}", owner, rank);
+                self.state
+                    .mint_credits(&owner, CREDITS_PER_PRESTIGE, CreditReason::Prestige, now)
+                    .await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::BuySeasonPass => {
+                let key = (*self.state.current_season.get(), owner);
+                let mut pass = self.state.season_passes.get(&key).await?.unwrap_or_default();
+                if pass.premium {
+                    return Err(GameStationError::PassAlreadyPremium);
+                }
+                
+                let price = self.state.config.get().pass_premium_price;
+                if price > Amount::ZERO {
+                    if self.runtime.owner_balance(owner) < price {
+                        return Err(GameStationError::InsufficientBalance);
+                    }
+                    let destination = self.escrow_account();
+                    self.runtime.transfer(owner, destination, price);
+                    // The price is revenue, withdrawn from the treasury like the rake
+                    self.state.treasury.get_mut().saturating_add_assign(price);
+                    self.state.fees_collected.get_mut().saturating_add_assign(price);
+                }
+                
+                log::info!("Player {} bought the season {} pass", owner, key.0);
+                pass.premium = true;
+                self.state.season_passes.insert(&key, pass)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::ClaimPassReward { tier, premium } => {
+                let key = (*self.state.current_season.get(), owner);
+                let mut pass = self.state.season_passes.get(&key).await?.unwrap_or_default();
+                let config = self.state.config.get();
+                let reached = pass.tier(config.pass_xp_per_tier, config.pass_tiers);
+                
+                let claimed = if premium { &mut pass.claimed_premium } else { &mut pass.claimed_free };
+                if tier == 0 || tier > reached || claimed.contains(&tier) || (premium && !pass.premium) {
+                    return Err(GameStationError::PassRewardUnavailable);
+                }
+                claimed.push(tier);
+                
+                let amount = if premium {
+                    PASS_PREMIUM_REWARD_PER_TIER.saturating_mul(tier as u128)
+                } else {
+                    PASS_FREE_REWARD
+                };
+                self.state.season_passes.insert(&key, pass)?;
+                self.state.credit_winnings(&owner, Amount::ZERO, amount, 0).await?;
+                Ok(Response::TokensClaimed { amount })
+            }
+            
+            Operation::StartTournament { tournament_id } => {
+                let Some(mut tournament) = self.state.tournaments.get(&tournament_id).await? else {
+                    return Err(GameStationError::TournamentNotFound);
+                };
+                if tournament.creator != owner {
+                    return Err(GameStationError::NotTournamentCreator);
+                }
+                
+                match tournament.status {
+                    TournamentStatus::Registration if tournament.players.len() < 2 => {
+                        return Err(GameStationError::CannotStartTournament);
+                    }
+                    TournamentStatus::Registration if tournament.check_in_window > 0 => {
+                        log::info!("Opening check-in for tournament {}", tournament_id);
+                        tournament.status = TournamentStatus::CheckIn;
+                        tournament.check_in_deadline = Some(now + tournament.check_in_window);
+                        self.state.tournaments.insert(&tournament_id, tournament)?;
+                        return Ok(Response::Ok);
+                    }
+                    TournamentStatus::Registration => {}
+                    TournamentStatus::CheckIn => {
+                        let everyone_in = tournament.checked_in.len() == tournament.players.len();
+                        if !everyone_in && tournament.check_in_deadline.is_some_and(|deadline| now < deadline) {
+                            return Err(GameStationError::CheckInOpen);
+                        }
+                        for deposit in tournament.drop_no_shows() {
+                            self.refund_deposit(deposit).await?;
+                        }
+                        if tournament.players.len() < 2 {
+                            log::info!("Cancelling tournament {}: too few players checked in", tournament_id);
+                            for deposit in std::mem::take(&mut tournament.deposits) {
+                                self.refund_deposit(deposit).await?;
+                            }
+                            tournament.status = TournamentStatus::Cancelled;
+                            self.state.tournaments.insert(&tournament_id, tournament)?;
+                            return Ok(Response::Ok);
+                        }
+                    }
+                    _ => return Err(GameStationError::CannotStartTournament),
+                }
+                
+                // Pairings are drawn from whoever is left, so the field is re-seeded
+                log::info!("Starting tournament {} with {} players", tournament_id, tournament.players.len());
+                tournament.status = TournamentStatus::InProgress;
+                tournament.schedule_league(now);
+                self.start_tournament_round(&mut tournament).await?;
+                self.state.tournaments.insert(&tournament_id, tournament)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::SetRake { rake_bps } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                if rake_bps > MAX_RAKE_BPS {
+                    return Err(GameStationError::InvalidRake);
+                }
+                log::info!("Admin {} set the rake to {} bps", owner, rake_bps);
+                let mut config = self.state.config.get().clone();
+                config.rake_bps = rake_bps;
+                self.state.config.set(config);
+                Ok(Response::Ok)
+            }
+            
+            Operation::WithdrawTreasury { amount, target } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let mut treasury = *self.state.treasury.get();
+                if amount > treasury {
+                    return Err(GameStationError::InsufficientTreasury);
+                }
+                log::info!("Admin {} withdrawing {} from the treasury", owner, amount);
+                treasury.saturating_sub_assign(amount);
+                self.state.treasury.set(treasury);
+                let source = self.escrow_account().owner;
+                self.runtime.transfer(source, target, amount);
+                Ok(Response::Ok)
+            }
+            
+            Operation::FundHouseBankroll { amount } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                if self.runtime.owner_balance(owner) < amount {
+                    return Err(GameStationError::InsufficientBalance);
+                }
+                log::info!("Admin {} adding {} to the house bankroll", owner, amount);
+                let destination = self.escrow_account();
+                self.runtime.transfer(owner, destination, amount);
+                self.state.house_bankroll.get_mut().saturating_add_assign(amount);
+                Ok(Response::Ok)
+            }
+            
+            Operation::WithdrawHouseBankroll { amount, target } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let mut bankroll = *self.state.house_bankroll.get();
+                if amount > bankroll {
+                    return Err(GameStationError::HouseBankrollTooLow);
+                }
+                log::info!("Admin {} withdrawing {} from the house bankroll", owner, amount);
+                bankroll.saturating_sub_assign(amount);
+                self.state.house_bankroll.set(bankroll);
+                let source = self.escrow_account().owner;
+                self.runtime.transfer(source, target, amount);
+                Ok(Response::Ok)
+            }
+            
+            Operation::Withdraw { amount, target } => {
+                log::info!("Player {} withdrawing {} to {}", owner, amount, target.owner);
+                
+                if !self.state.take_claimable(&owner, amount).await? {
+                    return Err(GameStationError::InsufficientWinnings);
+                }
+                let source = self.escrow_account().owner;
+                self.runtime.transfer(source, target, amount);
+                Ok(Response::Ok)
+            }
+            
+            Operation::BoostRoom { room_id } => {
+                log::info!("Player {} boosting room {}", owner, room_id);
+                
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                if room.status != RoomStatus::Waiting || !room.players.contains(&owner) {
+                    return Err(GameStationError::RoomNotJoinable);
+                }
+                if !self.state.burn_credits(&owner, ROOM_BOOST_COST, CreditReason::RoomBoost, now).await? {
+                    return Err(GameStationError::InsufficientCredits);
+                }
+                
+                // Boosting again extends a running boost
+                let boost_start = room.boosted_until.unwrap_or(now).max(now);
+                room.boosted_until = Some(boost_start + ROOM_BOOST_DURATION);
+                self.state.save_room(room).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::BuyCosmetic { item_id } => {
+                log::info!("Player {} buying cosmetic {}", owner, item_id);
+                
+                let Some(item) = cosmetics::find(&item_id) else {
+                    return Err(GameStationError::CosmeticNotFound);
+                };
+                let profile = self.state.get_or_create_user(&owner).await?;
+                if profile.unlocked_cosmetics.contains(&item_id) {
+                    return Err(GameStationError::CosmeticAlreadyOwned);
+                }
+                if !self.state.buy_cosmetic(&owner, item, now).await? {
+                    return Err(GameStationError::InsufficientCredits);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::EquipCosmetic { slot, item_id } => {
+                if let Some(item_id) = &item_id {
+                    if cosmetics::slot_of(item_id) != Some(slot) {
+                        return Err(GameStationError::WrongCosmeticSlot);
+                    }
+                    let profile = self.state.get_or_create_user(&owner).await?;
+                    if !profile.unlocked_cosmetics.contains(item_id) {
+                        return Err(GameStationError::CosmeticNotOwned);
+                    }
+                }
+                self.state.equip_cosmetic(&owner, slot, item_id).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::TipPlayer { recipient, amount, memo } => {
+                let empty = match amount {
+                    TipAmount::Tokens(tokens) => tokens == Amount::ZERO,
+                    TipAmount::Credits(credits) => credits == 0,
+                };
+                if recipient == owner || empty || memo.len() > MAX_TIP_MEMO_LEN {
+                    return Err(GameStationError::InvalidTip);
+                }
+                log::info!("Player {} tipping {} {:?}", owner, recipient, amount);
+                
+                let (tokens, credits) = match amount {
+                    TipAmount::Tokens(tokens) => {
+                        if self.runtime.owner_balance(owner) < tokens {
+                            return Err(GameStationError::InsufficientBalance);
+                        }
+                        let destination = Account { chain_id: self.runtime.chain_id(), owner: recipient };
+                        self.runtime.transfer(owner, destination, tokens);
+                        (tokens, 0)
+                    }
+                    TipAmount::Credits(credits) => {
+                        if !self.state.burn_credits(&owner, credits, CreditReason::TipSent, now).await? {
+                            return Err(GameStationError::InsufficientCredits);
+                        }
+                        self.state
+                            .mint_credits(&recipient, credits, CreditReason::TipReceived, now)
+                            .await?;
+                        (Amount::ZERO, credits)
+                    }
+                };
+                self.state
+                    .record_tip(TipRecord {
+                        sender: owner,
+                        recipient,
+                        tokens,
+                        credits,
+                        memo,
+                        timestamp: now,
+                    })
+                    .await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::RegisterWithReferral { code } => {
+                let Some(referrer) = self.state.referral_codes.get(&code.to_uppercase()).await? else {
+                    return Err(GameStationError::ReferralNotFound);
+                };
+                
+                // Linked addresses are one player, and referrals cannot go in a circle
+                let mut referrer_profile = self.state.get_or_create_user(&referrer).await?;
+                let same_player = self.state.primary_identity(&referrer).await?
+                    == self.state.primary_identity(&owner).await?;
+                if same_player || referrer_profile.referred_by == Some(owner) {
+                    return Err(GameStationError::SelfReferral);
+                }
+                
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                if profile.referred_by.is_some() || profile.room_games_played > 0 {
+                    return Err(GameStationError::NotEligibleForReferral);
+                }
+                log::info!("Player {} registered with referrer {}", owner, referrer);
+                profile.referred_by = Some(referrer);
+                self.state.users.insert(&owner, profile)?;
+                
+                referrer_profile.referral_count += 1;
+                self.state.users.insert(&referrer, referrer_profile)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::CreateClan { tag, name } => {
+                let tag = tag.to_uppercase();
+                let tag_len = tag.chars().count();
+                if !(MIN_CLAN_TAG_LEN..=MAX_CLAN_TAG_LEN).contains(&tag_len)
+                    || !tag.chars().all(|c| c.is_ascii_alphanumeric())
+                    || name.trim().is_empty()
+                    || name.len() > MAX_CLAN_NAME_LEN
+                {
+                    return Err(GameStationError::InvalidClan);
+                }
+                if self.state.get_or_create_user(&owner).await?.clan_tag.is_some() {
+                    return Err(GameStationError::AlreadyInClan);
+                }
+                if self.state.clans.contains_key(&tag).await? {
+                    return Err(GameStationError::ClanTagTaken);
+                }
+                
+                log::info!("Player {} founded clan [{}] {}", owner, tag, name);
+                let clan = state::Clan {
+                    tag: tag.clone(),
+                    name,
+                    members: vec![(owner, ClanRole::Leader)],
+                    join_requests: Vec::new(),
+                    treasury: 0,
+                    created_at: now,
+                    wars: Vec::new(),
+                };
+                self.state.clans.insert(&tag, clan)?;
+                self.state.set_clan_tag(&owner, Some(tag)).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::RequestJoinClan { tag } => {
+                let tag = tag.to_uppercase();
+                let Some(mut clan) = self.state.clans.get(&tag).await? else {
+                    return Err(GameStationError::ClanNotFound);
+                };
+                if self.state.get_or_create_user(&owner).await?.clan_tag.is_some() {
+                    return Err(GameStationError::AlreadyInClan);
+                }
+                if clan.members.len() >= MAX_CLAN_SIZE {
+                    return Err(GameStationError::ClanFull);
+                }
+                if !clan.join_requests.contains(&owner) {
+                    clan.join_requests.push(owner);
+                }
+                self.state.clans.insert(&tag, clan)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::ReviewJoinRequest { player, accept } => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                if clan.role_of(&owner) < Some(ClanRole::Officer) {
+                    return Err(GameStationError::ClanRankTooLow);
+                }
+                let Some(idx) = clan.join_requests.iter().position(|p| *p == player) else {
+                    return Err(GameStationError::NoJoinRequest);
+                };
+                clan.join_requests.remove(idx);
+                
+                if accept {
+                    // The player may have joined another clan since asking
+                    if self.state.get_or_create_user(&player).await?.clan_tag.is_some() {
+                        return Err(GameStationError::AlreadyInClan);
+                    }
+                    if clan.members.len() >= MAX_CLAN_SIZE {
+                        return Err(GameStationError::ClanFull);
+                    }
+                    log::info!("Player {} joined clan [{}]", player, tag);
+                    clan.members.push((player, ClanRole::Member));
+                    self.state.set_clan_tag(&player, Some(tag.clone())).await?;
+                }
+                self.state.clans.insert(&tag, clan)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::LeaveClan => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                clan.remove_member(&owner);
+                self.state.set_clan_tag(&owner, None).await?;
+                if clan.members.is_empty() {
+                    log::info!("Clan [{}] disbanded", tag);
+                    self.state.clans.remove(&tag)?;
+                } else {
+                    self.state.clans.insert(&tag, clan)?;
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::SetClanRole { player, role } => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                if clan.role_of(&owner) != Some(ClanRole::Leader) {
+                    return Err(GameStationError::ClanRankTooLow);
+                }
+                if player == owner || clan.role_of(&player).is_none() {
+                    return Err(GameStationError::NotInClan);
+                }
+                if role == ClanRole::Leader {
+                    clan.set_role(&owner, ClanRole::Officer);
+                }
+                clan.set_role(&player, role);
+                self.state.clans.insert(&tag, clan)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::KickFromClan { player } => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                let Some(player_role) = clan.role_of(&player) else {
+                    return Err(GameStationError::NotInClan);
+                };
+                let own_role = clan.role_of(&owner);
+                if own_role < Some(ClanRole::Officer) || own_role <= Some(player_role) {
+                    return Err(GameStationError::ClanRankTooLow);
+                }
+                log::info!("Player {} removed {} from clan [{}]", owner, player, tag);
+                clan.remove_member(&player);
+                self.state.set_clan_tag(&player, None).await?;
+                self.state.clans.insert(&tag, clan)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::DonateToClan { credits } => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                if !self.state.burn_credits(&owner, credits, CreditReason::ClanDonation, now).await? {
+                    return Err(GameStationError::InsufficientCredits);
+                }
+                clan.treasury += credits;
+                self.state.clans.insert(&tag, clan)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::GrantClanCredits { player, credits } => {
+                let (tag, mut clan) = self.own_clan(&owner).await?;
+                if clan.role_of(&owner) != Some(ClanRole::Leader) {
+                    return Err(GameStationError::ClanRankTooLow);
+                }
+                if clan.role_of(&player).is_none() {
+                    return Err(GameStationError::NotInClan);
+                }
+                if credits > clan.treasury {
+                    return Err(GameStationError::InsufficientClanTreasury);
+                }
+                clan.treasury -= credits;
+                self.state.clans.insert(&tag, clan)?;
+                self.state.mint_credits(&player, credits, CreditReason::ClanGrant, now).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::ScheduleClanWar { clans, starts_at, ends_at, reward } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let tags = clans.map(|tag| tag.to_uppercase());
+                if tags[0] == tags[1] || starts_at >= ends_at || ends_at <= now {
+                    return Err(GameStationError::InvalidClanWar);
+                }
+                
+                let war_id = format!("War-{}-{}-{}", tags[0], tags[1], starts_at);
+                for tag in &tags {
+                    let Some(mut clan) = self.state.clans.get(tag).await? else {
+                        return Err(GameStationError::ClanNotFound);
+                    };
+                    clan.wars.push(war_id.clone());
+                    self.state.clans.insert(tag, clan)?;
+                }
+                log::info!("Admin {} scheduled clan war {}", owner, war_id);
+                let war = state::ClanWar {
+                    war_id: war_id.clone(),
+                    scores: tags.into_iter().map(|tag| (tag, 0)).collect(),
+                    starts_at,
+                    ends_at,
+                    reward,
+                    winner: None,
+                    settled: false,
+                };
+                self.state.clan_wars.insert(&war_id, war)?;
+                Ok(Response::ClanWarScheduled { war_id })
+            }
+            
+            Operation::SettleClanWar { war_id } => {
+                let Some(mut war) = self.state.clan_wars.get(&war_id).await? else {
+                    return Err(GameStationError::ClanWarNotFound);
+                };
+                if war.settled || now < war.ends_at {
+                    return Err(GameStationError::CannotSettleClanWar);
+                }
+                
+                let best = war.scores.iter().map(|(_, score)| *score).max().unwrap_or(0);
+                let leaders: Vec<&String> = war
+                    .scores
+                    .iter()
+                    .filter(|(_, score)| *score == best)
+                    .map(|(tag, _)| tag)
+                    .collect();
+                // A draw pays nobody
+                if let [winner] = leaders[..] {
+                    war.winner = Some(winner.clone());
+                }
+                
+                for (tag, _) in &war.scores {
+                    // Disbanded clans drop out of the payout
+                    let Some(mut clan) = self.state.clans.get(tag).await? else {
+                        continue;
+                    };
+                    clan.wars.retain(|id| *id != war_id);
+                    if war.winner.as_ref() == Some(tag) {
+                        clan.treasury += war.reward;
+                    }
+                    self.state.clans.insert(tag, clan)?;
+                }
+                if let Some(winner) = &war.winner {
+                    log::info!("Clan [{}] won clan war {}", winner, war_id);
+                    self.state.update_clan_standing(winner, 0, 1).await?;
+                }
+                war.settled = true;
+                self.state.clan_wars.insert(&war_id, war)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::BlockPlayer { player } => {
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                if player == owner || profile.blocked.len() >= MAX_BLOCK_LIST {
+                    return Err(GameStationError::InvalidBlock);
+                }
+                log::info!("Player {} blocked {}", owner, player);
+                if !profile.blocked.contains(&player) {
+                    profile.blocked.push(player);
+                }
+                self.state.users.insert(&owner, profile)?;
+                self.state.remove_friend(&owner, &player).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::UnblockPlayer { player } => {
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                profile.blocked.retain(|p| *p != player);
+                self.state.users.insert(&owner, profile)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::MutePlayer { player } => {
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                if player == owner || profile.muted.len() >= MAX_BLOCK_LIST {
+                    return Err(GameStationError::InvalidBlock);
+                }
+                if !profile.muted.contains(&player) {
+                    profile.muted.push(player);
+                }
+                self.state.users.insert(&owner, profile)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::UnmutePlayer { player } => {
+                let mut profile = self.state.get_or_create_user(&owner).await?;
+                profile.muted.retain(|p| *p != player);
+                self.state.users.insert(&owner, profile)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::CommitMinesweeperBoards { commitments } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} committed {} Minesweeper boards", owner, commitments.len());
+                for board_commitment in commitments {
+                    self.state.minesweeper_boards.push_back(board_commitment);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::RevealMinesweeperBoard { player, seed } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let Some(session) = self.state.minesweeper_sessions.get(&player).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                if session.submitted_at.is_none() {
+                    return Err(GameStationError::SessionNotSubmitted);
+                }
+                if commitment(&[&seed]) != session.board_commitment {
+                    return Err(MoveError::CommitmentMismatch.into());
+                }
+                
+                let result = minesweeper::replay(&minesweeper::mines(&seed), &session.clicks)?;
+                self.state.minesweeper_sessions.remove(&player)?;
+                self.record_minesweeper_result(player, result).await?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::AddTriviaQuestion { prompt, choices, answer } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let question = TriviaQuestion { prompt, choices, answer };
+                if !question.is_valid() {
+                    return Err(GameStationError::InvalidTriviaQuestion);
+                }
+                let index = self.state.trivia_questions.count();
+                log::info!("Admin {} added trivia question {}", owner, index);
+                self.state.trivia_questions.push(question);
+                Ok(Response::Ok)
+            }
+            
+            Operation::PublishDailyWord { day, commitment } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                if self.state.daily_words.contains_key(&day).await? {
+                    return Err(GameStationError::WordAlreadyPublished);
+                }
+                log::info!("Admin {} published the word for day {}", owner, day);
+                let word = state::DailyWord { commitment, published_at: now, answer: None };
+                self.state.daily_words.insert(&day, word)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::RevealDailyWord { day, word, salt } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let Some(mut daily) = self.state.daily_words.get(&day).await? else {
+                    return Err(GameStationError::WordNotOpen);
+                };
+                if daily.answer.is_some() {
+                    return Err(GameStationError::WordNotOpen);
+                }
+                if now < (day + 1) * MICROS_PER_DAY {
+                    return Err(GameStationError::DayNotOver);
+                }
+                if daily_word::word_commitment(&word, &salt) != daily.commitment {
+                    return Err(MoveError::CommitmentMismatch.into());
+                }
+                log::info!("Admin {} revealed the word for day {}", owner, day);
+                self.state.score_daily_word(day, &word, now).await?;
+                daily.answer = Some(word);
+                self.state.daily_words.insert(&day, daily)?;
+                Ok(Response::Ok)
+            }
+            
+            Operation::CommitBlackjackShoes { commitments } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                log::info!("Admin {} committed {} blackjack shoes", owner, commitments.len());
+                for shoe in commitments {
+                    self.state.blackjack_shoes.push_back(shoe);
+                }
+                Ok(Response::Ok)
+            }
+            
+            Operation::DealBlackjack { player, preimages } => {
+                if !is_admin {
+                    return Err(GameStationError::NotAdmin);
+                }
+                let Some(mut session) = self.state.blackjack_hands.get(&player).await? else {
+                    return Err(GameStationError::NoActiveSession);
+                };
+                for preimage in preimages {
+                    if !session.hand.wants_card() {
+                        break;
+                    }
+                    if commitment(&[&preimage]) != session.chain_head {
+                        return Err(MoveError::CommitmentMismatch.into());
+                    }
+                    session.hand.deal(blackjack::draw_card(&preimage, &session.salt));
+                    session.chain_head = preimage;
+                }
+                session.updated_at = now;
+                match session.hand.result {
+                    Some(result) => self.settle_blackjack(player, session, result).await,
+                    None => {
+                        self.state.blackjack_hands.insert(&player, session)?;
+                        Ok(Response::Ok)
+                    }
+                }
+            }
+        }
+    }
+    
+    /// The clan `player` belongs to, with its tag
+    async fn own_clan(&mut self, player: &AccountOwner) -> Result<(String, state::Clan), GameStationError> {
+        let profile = self.state.get_or_create_user(player).await?;
+        let Some(tag) = profile.clan_tag else {
+            return Err(GameStationError::NotInClan);
+        };
+        match self.state.clans.get(&tag).await? {
+            Some(clan) => Ok((tag, clan)),
+            None => Err(GameStationError::ClanNotFound),
+        }
+    }
+    
+    /// Validate and apply a move in a room played on this chain
+    async fn submit_move(
+        &mut self,
+        owner: AccountOwner,
+        mut room: state::GameRoom,
+        move_data: Vec<u8>,
+    ) -> Result<Response, GameStationError> {
+        let room_id = room.room_id.clone();
+        if room.status != RoomStatus::InProgress {
+            return Err(GameStationError::RoomNotInProgress);
+        }
+        let Some(player_idx) = room.players.iter().position(|p| *p == owner) else {
+            return Err(GameStationError::NotInRoom);
+        };
+        let now = self.runtime.system_time().micros();
+        if room.is_turn_overdue(now) {
+            log::warn!("Move in room {} is past the turn deadline", room_id);
+            return Err(GameStationError::TurnDeadlinePassed);
+        }
+        
+        // A trivia game draws its questions with its first move
+        if let Some(GameState::Trivia(game)) = room.game_state.as_mut() {
+            if !game.is_started() {
+                let seed = self.session_seed(&owner, room_id.as_bytes(), now);
+                let questions = self.state.draw_trivia_questions(seed, trivia::ROUNDS).await?;
+                if questions.is_empty() {
+                    return Err(GameStationError::NoTriviaQuestions);
+                }
+                game.start(questions, now);
+            }
+        }
+        
+        let result = match room.game_state.as_mut() {
+            Some(GameState::TicTacToe(game)) => {
+                match (
+                    PlayerMark::from_player_index(player_idx),
+                    TicTacToeState::decode_move(&move_data),
+                ) {
+                    (Some(mark), Ok((row, col))) => game.apply_move(mark, row, col),
+                    (None, _) => Err(MoveError::NotYourTurn),
+                    (_, Err(error)) => Err(error),
+                }
+            }
+            Some(GameState::ConnectFour(game)) => {
+                match (
+                    PlayerMark::from_player_index(player_idx),
+                    ConnectFourState::decode_move(&move_data),
+                ) {
+                    (Some(mark), Ok(col)) => game.apply_move(mark, col),
+                    (None, _) => Err(MoveError::NotYourTurn),
+                    (_, Err(error)) => Err(error),
+                }
+            }
+            Some(GameState::Chess(game)) => {
+                match (Color::from_player_index(player_idx), ChessState::decode_move(&move_data)) {
+                    (Some(color), Ok(chess_move)) => game.apply_move(color, chess_move),
+                    (None, _) => Err(MoveError::NotYourTurn),
+                    (_, Err(error)) => Err(error),
+                }
+            }
+            Some(GameState::Checkers(game)) => {
+                CheckersState::decode_move(&move_data)
+                    .and_then(|path| game.apply_move(player_idx, path))
+            }
+            Some(GameState::Battleship(game)) => {
+                BattleshipState::decode_move(&move_data)
+                    .and_then(|battleship_move| game.apply_move(player_idx, battleship_move))
+            }
+            Some(GameState::Gomoku(game)) => {
+                GomokuState::decode_move(&move_data)
+                    .and_then(|gomoku_move| game.apply_move(player_idx, gomoku_move))
+            }
+            Some(GameState::DotsAndBoxes(game)) => {
+                DotsAndBoxesState::decode_move(&move_data)
+                    .and_then(|line| game.apply_move(player_idx, line))
+            }
+            Some(GameState::Reversi(game)) => {
+                ReversiState::decode_move(&move_data)
+                    .and_then(|(row, col)| game.apply_move(player_idx, row, col))
+            }
+            Some(GameState::RockPaperScissors(game)) => {
+                RockPaperScissorsState::decode_move(&move_data)
+                    .and_then(|rps_move| game.apply_move(player_idx, rps_move))
+            }
+            Some(GameState::Ludo(game)) => {
+                LudoState::decode_move(&move_data)
+                    .and_then(|ludo_move| game.apply_move(player_idx, ludo_move))
+            }
+            Some(GameState::Memory(game)) => {
+                MemoryState::decode_move(&move_data)
+                    .and_then(|memory_move| game.apply_move(player_idx, memory_move))
+            }
+            Some(GameState::Poker(game)) => {
+                PokerState::decode_move(&move_data)
+                    .and_then(|poker_move| game.apply_move(player_idx, poker_move))
+            }
+            Some(GameState::Trivia(game)) => {
+                TriviaState::decode_move(&move_data)
+                    .and_then(|trivia_move| game.apply_move(player_idx, trivia_move, now))
+            }
+            Some(GameState::SnakeLadders(game)) => {
+                SnakeLaddersState::decode_move(&move_data)
+                    .and_then(|mv| game.apply_move(player_idx, mv))
+            }
+            Some(GameState::Uno(game)) => {
+                UnoState::decode_move(&move_data)
+                    .and_then(|uno_move| game.apply_move(player_idx, uno_move))
+            }
+            Some(GameState::Yahtzee(game)) => {
+                let entropy = self.runtime.system_time().micros();
+                YahtzeeState::decode_move(&move_data)
+                    .and_then(|yahtzee_move| game.apply_move(player_idx, yahtzee_move, entropy))
+            }
+            _ => return Err(GameStationError::NoGameInProgress),
+        };
+        
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                log::warn!("Rejected move from {} in room {}: {}", owner, room_id, error);
+                return Err(error.into());
+            }
+        };
+        
+        let message = Message::GameMove {
+            room_id: room_id.clone(),
+            player: owner,
+            move_data: move_data.clone(),
+        };
+        self.notify_room(&room, message);
+        self.emit_event(GameEvent::MoveMade {
+            room_id: room_id.clone(),
+            player: owner,
+            move_data: move_data.clone(),
+            timestamp: now,
+        });
+        
+        let record = MoveRecord {
+            player: owner,
+            move_data,
+            timestamp: now,
+            round: room.round_winners.len() as u32 + 1,
+        };
+        self.state.log_move(&room_id, record).await?;
+        
+        // Every final Yahtzee score is posted, win or lose
+        let final_scores: Vec<u32> = match &room.game_state {
+            Some(GameState::Yahtzee(game)) if outcome != GameOutcome::Ongoing => {
+                game.scorecards.iter().map(|card| card.total() as u32).collect()
+            }
+            _ => Vec::new(),
+        };
+        
+        room.turn_deadline = Some(now + self.state.config.get().turn_timeout);
+        let finished = match outcome {
+            GameOutcome::Ongoing => false,
+            GameOutcome::Winner(idx) => room.record_round(room.players.get(idx).copied()),
+            GameOutcome::Draw => room.record_round(None),
+        };
+        if finished {
+            log::info!("Room {} finished. Winner: {:?}", room_id, room.series_winner());
+            room.turn_deadline = None;
+            self.finish_room(&mut room).await?;
+        }
+        
+        // Posted after the results are recorded, so the entries carry current win rates
+        for (player, &score) in room.players.iter().zip(&final_scores) {
+            self.state.get_or_create_user(player).await?;
+            self.state.update_yahtzee_score(player, score, now).await?;
+            self.report_to_hub(GameType::Yahtzee, LeaderboardKind::Score, player).await?;
+            let score = score as u64;
+            self.state
+                .update_windowed_leaderboards(GameType::Yahtzee, player, score, now)
+                .await?;
+        }
+        
+        self.state.save_room(room).await?;
+        Ok(Response::MoveAccepted { finished })
+    }
+    
+    /// End a room played on this chain by forfeit, once the player to move or
+    /// anyone owing a commit or reveal is out of time
+    async fn claim_timeout(
+        &mut self,
+        owner: AccountOwner,
+        mut room: state::GameRoom,
+    ) -> Result<Response, GameStationError> {
+        let room_id = room.room_id.clone();
+        if !room.players.contains(&owner) || !room.is_turn_overdue(self.runtime.system_time().micros()) {
+            return Err(GameStationError::NoTimeoutToClaim);
+        }
+        // The player to move, or everyone holding up a commit or reveal
+        let stalled = match room.current_turn() {
+            Some((player, _)) => vec![player],
+            None => room.stalled_players(),
+        };
+        // Only a player who isn't holding the game up can end it
+        if stalled.is_empty() || stalled.contains(&owner) {
+            return Err(GameStationError::NoTimeoutToClaim);
+        }
+        for player in &stalled {
+            log::info!("Player {} forfeits room {} on time", player, room_id);
+        }
+        
+        room.forfeit_stalled(&stalled);
+        self.finish_room(&mut room).await?;
+        self.state.save_room(room).await?;
+        Ok(Response::Ok)
+    }
+    
+    /// Send a player's action on to the dedicated chain a room is played on.
+    /// The message carries the player's signature so the room chain knows who acted.
+    fn forward_to_host(
+        &mut self,
+        room: &state::GameRoom,
+        owner: AccountOwner,
+        host_chain: ChainId,
+        message: Message,
+    ) -> Result<Response, GameStationError> {
+        if room.status != RoomStatus::InProgress {
+            return Err(GameStationError::RoomNotInProgress);
+        }
+        if !room.players.contains(&owner) {
+            return Err(GameStationError::NotInRoom);
+        }
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .send_to(host_chain);
+        Ok(Response::Forwarded { chain_id: host_chain })
+    }
+    
+    /// Record a finished Snake game on the player's profile and the leaderboards
+    async fn record_snake_score(&mut self, owner: AccountOwner, score: u32) -> Result<Response, GameStationError> {
+        // Ensure user profile exists
+        self.state.get_or_create_user(&owner).await?;
+        
+        // Update score and leaderboard
+        let now = self.runtime.system_time().micros();
+        let is_new_high = self.state.update_snake_score(&owner, score, now).await?;
+        self.report_to_hub(GameType::Snake, LeaderboardKind::Score, &owner).await?;
+        self.state
+            .update_windowed_leaderboards(GameType::Snake, &owner, score as u64, now)
+            .await?;
+        
+        let record = MatchRecord {
+            game_type: GameType::Snake,
+            opponent: None,
+            result: MatchResult::Completed,
+            score: score as u64,
+            timestamp: now,
+        };
+        self.state.record_match(&owner, record).await?;
+        self.emit_event(GameEvent::ScoreSubmitted {
+            player: owner,
+            game_type: GameType::Snake,
+            score: score as u64,
+            timestamp: now,
+        });
+        self.state.record_recent_game(RecentGame {
+            game_type: GameType::Snake,
+            players: vec![owner],
+            room_id: None,
+            winner: None,
+            score: Some(score as u64),
+            timestamp: now,
+        });
+        self.state
+            .record_quest_event(&owner, now, QuestEvent::SnakeGame { score })
+            .await?;
+        
+        if is_new_high {
+            log::info!("New high score for player {}!", owner);
+        }
+        Ok(Response::ScoreAccepted { new_high: is_new_high })
+    }
+    
+    /// Record a replay-verified score of a single-player game on the
+    /// leaderboards and match history
+    async fn record_session_score(
+        &mut self,
+        game_type: GameType,
+        owner: AccountOwner,
+        score: u32,
+    ) -> Result<Response, GameStationError> {
+        self.state.get_or_create_user(&owner).await?;
+        
+        let now = self.runtime.system_time().micros();
+        let is_new_high = self.state.update_session_score(game_type, &owner, score, now).await?;
+        self.report_to_hub(game_type, LeaderboardKind::Score, &owner).await?;
+        self.state.update_windowed_leaderboards(game_type, &owner, score as u64, now).await?;
+        
+        let record = MatchRecord {
+            game_type: game_type,
+            opponent: None,
+            result: MatchResult::Completed,
+            score: score as u64,
+            timestamp: now,
+        };
+        self.state.record_match(&owner, record).await?;
+        self.emit_event(GameEvent::ScoreSubmitted {
+            player: owner,
+            game_type,
+            score: score as u64,
+            timestamp: now,
+        });
+        self.state.record_recent_game(RecentGame {
+            game_type,
+            players: vec![owner],
+            room_id: None,
+            winner: None,
+            score: Some(score as u64),
+            timestamp: now,
+        });
+        Ok(Response::ScoreAccepted { new_high: is_new_high })
+    }
+    
+    /// Record a scored Minesweeper session on the leaderboards and match history
+    async fn record_minesweeper_result(
+        &mut self,
+        player: AccountOwner,
+        result: MinesweeperResult,
+    ) -> Result<(), GameStationError> {
+        log::info!("Minesweeper session of {} scored {}", player, result.score);
+        self.state.get_or_create_user(&player).await?;
+        let now = self.runtime.system_time().micros();
+        let record = MatchRecord {
+            game_type: GameType::Minesweeper,
+            opponent: None,
+            result: if result.won { MatchResult::Win } else { MatchResult::Loss },
+            score: result.score as u64,
+            timestamp: now,
+        };
+        self.state.record_match(&player, record).await?;
+        
+        self.state.update_minesweeper_result(&player, result.won, result.score, now).await?;
+        self.report_to_hub(GameType::Minesweeper, LeaderboardKind::Score, &player).await?;
+        let score = result.score as u64;
+        self.state
+            .update_windowed_leaderboards(GameType::Minesweeper, &player, score, now)
+            .await?;
+        self.emit_event(GameEvent::ScoreSubmitted {
+            player,
+            game_type: GameType::Minesweeper,
+            score: result.score as u64,
+            timestamp: now,
+        });
+        self.state.record_recent_game(RecentGame {
+            game_type: GameType::Minesweeper,
+            players: vec![player],
+            room_id: None,
+            winner: None,
+            score: Some(result.score as u64),
+            timestamp: now,
+        });
+        Ok(())
+    }
+    
+    /// Pay out a finished blackjack hand. The house keeps the stakes and its
+    /// reserve, less whatever the hand pays the player.
+    async fn settle_blackjack(
+        &mut self,
+        player: AccountOwner,
+        session: state::BlackjackSession,
+        result: BlackjackResult,
+    ) -> Result<Response, GameStationError> {
+        let doubled = session.hand.doubled;
+        let staked = if doubled { session.bet.saturating_mul(2) } else { session.bet };
+        let payout =
+            Amount::from_attos(u128::from(session.bet) * result.payout_halves(doubled) / 2);
+        log::info!("Blackjack hand of {} settled as {:?}, paying {}", player, result, payout);
+        
+        let bankroll = self.state.house_bankroll.get_mut();
+        bankroll.saturating_add_assign(session.reserved.saturating_add(staked));
+        bankroll.saturating_sub_assign(payout);
+        let tokens_won = payout.saturating_sub(staked).saturating_div(Amount::ONE) as u64;
+        self.state.credit_winnings(&player, payout, Amount::ZERO, tokens_won).await?;
+        self.state.blackjack_hands.remove(&player)?;
+        
+        let record = MatchRecord {
+            game_type: GameType::Blackjack,
+            opponent: None,
+            result: match result {
+                BlackjackResult::Blackjack | BlackjackResult::Win => MatchResult::Win,
+                BlackjackResult::Push => MatchResult::Draw,
+                BlackjackResult::Loss => MatchResult::Loss,
+            },
+            score: 0,
+            timestamp: self.runtime.system_time().micros(),
+        };
+        self.state.record_match(&player, record).await?;
+        Ok(Response::BlackjackSettled { result, payout })
+    }
+    
+    /// Seed for a new single-player session of `game`. It mixes in the chain
+    /// and block time, so the player can't pick it.
+    fn session_seed(&mut self, owner: &AccountOwner, game: &[u8], now: u64) -> u64 {
+        let chain_id = self.runtime.chain_id().to_string();
+        let hash = commitment(&[
+            game,
+            owner.to_string().as_bytes(),
+            chain_id.as_bytes(),
+            &now.to_le_bytes(),
+        ]);
+        u64::from_le_bytes(hash[..8].try_into().expect("hash has at least 8 bytes"))
+    }
+    
+    /// Share a player's entry on a local leaderboard with the hub chain's
+    /// global leaderboard, or merge it directly if this is the hub
+    async fn report_to_hub(
+        &mut self,
+        game_type: GameType,
+        kind: LeaderboardKind,
+        player: &AccountOwner,
+    ) -> Result<(), GameStationError> {
+        let Some(hub_chain) = self.runtime.application_parameters().hub_chain else {
+            return Ok(());
+        };
+        let Some(entry) = self.state.leaderboard_entry(game_type, kind, player).await? else {
+            return Ok(());
+        };
+        
+        if hub_chain == self.runtime.chain_id() {
+            self.state.merge_global_entry(game_type, kind, entry).await?;
+        } else {
+            let update = Message::LeaderboardUpdate { game_type, kind, entry };
+            self.runtime.send_message(hub_chain, update);
+        }
+        Ok(())
+    }
+    
+    /// Apply a cross-chain message, rejecting it if it is not valid
+    async fn handle_message(&mut self, message: Message) -> Result<(), GameStationError> {
+        self.state.roll_season(self.runtime.system_time().micros());
+        
+        match message {
+            Message::PlayerJoined { room_id, player } => {
+                log::info!("Player {} joined room {}", player, room_id);
+                
+                let Some(mut mirror) = self.state.room_mirror(&room_id).await? else {
+                    return Ok(());
+                };
+                if !mirror.players.contains(&player) {
+                    mirror.players.push(player);
+                }
+                self.state.room_mirrors.insert(&room_id, mirror)?;
+            }
+            Message::GameMove { room_id, player, move_data } => {
+                log::info!("Player {} made move in room {}", player, room_id);
+                
+                let Some(mut mirror) = self.state.room_mirror(&room_id).await? else {
+                    return Ok(());
+                };
+                mirror.status = RoomStatus::InProgress;
+                mirror.move_count += 1;
+                mirror.last_move = Some((player, move_data));
+                self.state.room_mirrors.insert(&room_id, mirror)?;
+            }
+            Message::GameEnded { room_id, winner, scores } => {
+                log::info!("Game {} ended. Winner: {:?}", room_id, winner);
+                
+                let Some(mut mirror) = self.state.room_mirror(&room_id).await? else {
+                    return Ok(());
+                };
+                mirror.status = RoomStatus::Finished;
+                mirror.winner = winner;
+                mirror.scores = scores;
+                self.state.room_mirrors.insert(&room_id, mirror)?;
+            }
+            Message::HostRoom {
+                room_id,
+                game_type,
+                players,
+                series_target,
+                board_size,
+                swap2_opening,
+                turn_timeout,
+            } => {
+                let Some(home_chain) = self.runtime.message_id().map(|id| id.chain_id) else {
+                    return Err(GameStationError::UnexpectedOrigin);
+                };
+                let Some(&(creator, creator_chain)) = players.first() else {
+                    return Err(GameStationError::InvalidRoomHandoff);
+                };
+                if self.state.rooms.contains_key(&room_id).await? {
+                    return Err(GameStationError::InvalidRoomHandoff);
+                }
+                log::info!("Hosting room {} for chain {}", room_id, home_chain);
+                
+                let now = self.runtime.system_time().micros();
+                let mut room = state::GameRoom::new(
+                    room_id.clone(),
+                    game_type,
+                    creator,
+                    creator_chain,
+                    players.len() as u8,
+                    now,
+                    0,
+                );
+                room.players = players.iter().map(|(player, _)| *player).collect();
+                room.player_chains = players;
+                room.series_target = series_target;
+                room.board_size = board_size;
+                room.swap2_opening = swap2_opening;
+                room.home_chain = Some(home_chain);
+                room.start(now + turn_timeout);
+                self.state.save_room(room).await?;
+            }
+            Message::RoomMove { room_id, move_data } => {
+                let Some(player) = self.runtime.authenticated_signer() else {
+                    return Err(GameStationError::Unauthenticated);
+                };
+                let Some(room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                self.submit_move(player, room, move_data).await?;
+            }
+            Message::RoomClaimTimeout { room_id } => {
+                let Some(player) = self.runtime.authenticated_signer() else {
+                    return Err(GameStationError::Unauthenticated);
+                };
+                let Some(room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                self.claim_timeout(player, room).await?;
+            }
+            Message::RoomFinished { room_id, series_wins, round_winners, forfeited, moves } => {
+                let Some(mut room) = self.state.rooms.get(&room_id).await? else {
+                    return Err(GameStationError::RoomNotFound);
+                };
+                // Only the room's own chain can report its result
+                let origin = self.runtime.message_id().map(|id| id.chain_id);
+                if room.host_chain.is_none() || origin != room.host_chain {
+                    return Err(GameStationError::UnexpectedOrigin);
+                }
+                log::info!("Room {} finished on its dedicated chain", room_id);
+                
+                for record in moves {
+                    self.state.log_move(&room_id, record).await?;
+                }
+                room.series_wins = series_wins;
+                room.round_winners = round_winners;
+                room.forfeited = forfeited;
+                room.status = RoomStatus::Finished;
+                room.host_chain = None;
+                self.conclude_match(&mut room).await?;
+                self.state.save_room(room).await?;
+            }
+            Message::LeaderboardUpdate { game_type, kind, entry } => {
+                log::info!("Leaderboard update for {:?}: {}", game_type, entry.player_name);
+                
+                if self.runtime.application_parameters().hub_chain != Some(self.runtime.chain_id()) {
+                    log::warn!("Ignoring leaderboard update sent to a chain that is not the hub");
+                    return Ok(());
+                }
+                self.state.merge_global_entry(game_type, kind, entry).await?;
+            }
+            Message::LinkIdentity { primary_address, secondary_address, proof } => {
+                // The consent only counts if it was signed by the address it claims
+                if self.runtime.authenticated_signer() != Some(proof) {
+                    log::warn!("Rejecting identity link consent without matching signer");
+                    return Err(GameStationError::InvalidIdentityProof);
+                }
+                if !self.state.can_link_identity(&primary_address, &secondary_address).await? {
+                    log::warn!("Rejecting identity link of an address that is already linked");
+                    return Err(GameStationError::IdentityAlreadyLinked);
+                }
+                
+                let linked = self.state
+                    .record_identity_consent(&primary_address, &secondary_address, &proof)
+                    .await?;
+                if linked {
+                    log::info!("Linked {} to primary identity {}", secondary_address, primary_address);
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// The application's own account on this chain, which holds room escrow
+    fn escrow_account(&mut self) -> Account {
+        Account {
+            chain_id: self.runtime.chain_id(),
+            owner: AccountOwner::from(self.runtime.application_id().forget_abi()),
+        }
+    }
+    
+    /// Take an entry fee from `player` into escrow.
+    ///
+    /// Free-play tokens are used first; otherwise the fee is transferred from
+    /// the player's native balance. Fails if neither can cover it.
+    async fn escrow_entry_fee(
+        &mut self,
+        player: AccountOwner,
+        fee: Amount,
+    ) -> Result<state::EscrowDeposit, GameStationError> {
+        if fee == Amount::ZERO {
+            return Ok(state::EscrowDeposit { player, amount: fee, free_play: false });
+        }
+        
+        if self.state.spend_free_play(&player, fee).await? {
+            return Ok(state::EscrowDeposit { player, amount: fee, free_play: true });
+        }
+        
+        if self.runtime.owner_balance(player) < fee {
+            return Err(GameStationError::EntryFeeNotCovered);
+        }
+        let destination = self.escrow_account();
+        self.runtime.transfer(player, destination, fee);
+        
+        Ok(state::EscrowDeposit { player, amount: fee, free_play: false })
+    }
+    
+    /// Lock a player's wager stake in escrow. Unlike entry fees, wagers
+    /// cannot be paid with free-play tokens.
+    async fn escrow_wager(
+        &mut self,
+        player: AccountOwner,
+        wager: Amount,
+    ) -> Result<state::EscrowDeposit, GameStationError> {
+        if wager > Amount::ZERO {
+            if self.runtime.owner_balance(player) < wager {
+                return Err(GameStationError::InsufficientBalance);
+            }
+            let destination = self.escrow_account();
+            self.runtime.transfer(player, destination, wager);
+            let now = self.runtime.system_time().micros();
+            self.state.record_daily_wager(wager, now).await?;
+        }
+        Ok(state::EscrowDeposit { player, amount: wager, free_play: false })
+    }
+    
+    /// Return an escrow deposit to the player who made it
+    async fn refund_deposit(&mut self, deposit: state::EscrowDeposit) -> Result<(), GameStationError> {
+        if deposit.amount == Amount::ZERO {
+            return Ok(());
+        }
+        
+        if deposit.free_play {
+            self.state.credit_winnings(&deposit.player, Amount::ZERO, deposit.amount, 0).await?;
+        } else {
+            let source = self.escrow_account().owner;
+            let destination = Account { chain_id: self.runtime.chain_id(), owner: deposit.player };
+            self.runtime.transfer(source, destination, deposit.amount);
+        }
+        Ok(())
+    }
+    
+    /// Refund everyone in an expired or force-closed room and remove it from state
+    async fn expire_room(&mut self, mut room: state::GameRoom) -> Result<(), GameStationError> {
+        let deposits = std::mem::take(&mut room.deposits);
+        let stakes = std::mem::take(&mut room.wager_deposits);
+        for deposit in deposits.into_iter().chain(stakes) {
+            self.refund_deposit(deposit).await?;
+        }
+        self.state.delete_room(&room.room_id).await?;
+        self.state.room_chat.remove_entry(&room.room_id)?;
+        self.state.move_logs.remove_entry(&room.room_id)?;
+        Ok(())
+    }
+    
+    /// Open a temporary chain for a room that just started and move the game
+    /// there. The lobby and the escrow stay on this chain.
+    fn host_on_new_chain(&mut self, room: &mut state::GameRoom) {
+        let ownership = self.runtime.chain_ownership();
+        let permissions = ApplicationPermissions::new_single(self.runtime.application_id().forget_abi());
+        let (_, host_chain) = self.runtime.open_chain(ownership, permissions, Amount::ZERO);
+        
+        let message = Message::HostRoom {
+            room_id: room.room_id.clone(),
+            game_type: room.game_type,
+            players: room.player_chains.clone(),
+            series_target: room.series_target,
+            board_size: room.board_size,
+            swap2_opening: room.swap2_opening,
+            turn_timeout: self.state.config.get().turn_timeout,
+        };
+        self.runtime.send_message(host_chain, message);
+        log::info!("Room {} is now played on chain {}", room.room_id, host_chain);
+        
+        room.host_chain = Some(host_chain);
+        room.game_state = None;
+        room.turn_deadline = None;
+    }
+    
+    /// Settle a finished room, or report it back to the chain that created it
+    /// if this is the room's temporary chain, which then closes
+    async fn finish_room(&mut self, room: &mut state::GameRoom) -> Result<(), GameStationError> {
+        let Some(home_chain) = room.home_chain else {
+            return self.conclude_match(room).await;
+        };
+        
+        let message = Message::RoomFinished {
+            room_id: room.room_id.clone(),
+            series_wins: room.series_wins.clone(),
+            round_winners: room.round_winners.clone(),
+            forfeited: room.forfeited.clone(),
+            moves: self.state.room_moves(&room.room_id).await,
+        };
+        self.runtime.send_message(home_chain, message);
+        self.runtime
+            .close_chain()
+            .map_err(|_| GameStationError::CannotCloseChain)
+    }
+    
+    /// Record the result of a finished room and settle its escrow
+    async fn conclude_match(&mut self, room: &mut state::GameRoom) -> Result<(), GameStationError> {
+        let now = self.runtime.system_time().micros();
+        
+        let winner = room.series_winner();
+        for player in &room.players {
+            let result = match winner {
+                Some(w) if w == *player => MatchResult::Win,
+                Some(_) => MatchResult::Loss,
+                None if room.forfeited.contains(player) => MatchResult::Loss,
+                None => MatchResult::Draw,
+            };
+            let score = room
+                .series_wins
+                .iter()
+                .find(|(p, _)| p == player)
+                .map_or(0, |(_, wins)| *wins as u64);
+            // In multi-player rooms the opponent shown is the winner, or the first other player
+            let opponent = winner
+                .filter(|w| w != player)
+                .or_else(|| room.players.iter().find(|p| *p != player).copied());
+            
+            let record = MatchRecord {
+                game_type: room.game_type,
+                opponent,
+                result,
+                score,
+                timestamp: now,
+            };
+            self.state.record_match(player, record).await?;
+            
+            let event = QuestEvent::GameFinished {
+                game_type: room.game_type,
+                won: result == MatchResult::Win,
+            };
+            self.state.record_quest_event(player, now, event).await?;
+            self.state.record_room_game(player, now).await?;
+            
+            if result == MatchResult::Win {
+                self.state.mint_credits(player, CREDITS_PER_WIN, CreditReason::MatchWin, now).await?;
+                self.state.record_clan_war_win(player, now).await?;
+            }
+        }
+        
+        // Each pair of players has a result unless someone else won
+        for (i, player_a) in room.players.iter().enumerate() {
+            for player_b in &room.players[i + 1..] {
+                if winner.is_none_or(|w| w == *player_a || w == *player_b) {
+                    let encounter = Encounter { game_type: room.game_type, winner, timestamp: now };
+                    self.state.record_head_to_head(*player_a, *player_b, encounter).await?;
+                }
+            }
+        }
+        
+        // Rated after the results are counted, so the entries carry current win rates
+        if let (true, [player_a, player_b]) = (room.game_type.is_rated(), &room.players[..]) {
+            self.state
+                .record_rated_match(room.game_type, player_a, player_b, winner, now)
+                .await?;
+            for player in [player_a, player_b] {
+                self.report_to_hub(room.game_type, LeaderboardKind::Rating, player).await?;
+            }
+        }
+        
+        self.emit_event(GameEvent::GameFinished {
+            room_id: room.room_id.clone(),
+            game_type: room.game_type,
+            winner,
+            timestamp: now,
+        });
+        self.state.record_recent_game(RecentGame {
+            game_type: room.game_type,
+            players: room.players.clone(),
+            room_id: Some(room.room_id.clone()),
+            winner,
+            score: None,
+            timestamp: now,
+        });
+        self.settle_room(room).await
+    }
+    
+    /// Pay out a finished room's escrow and notify the participant chains.
+    ///
+    /// The series winner takes the whole pot and both wager stakes; on a draw
+    /// the pot is split evenly between all players and each stake goes back
+    /// to whoever staked it.
+    async fn settle_room(&mut self, room: &mut state::GameRoom) -> Result<(), GameStationError> {
+        let recipients: Vec<AccountOwner> = match room.series_winner() {
+            Some(winner) => vec![winner],
+            None => room.drawing_players(),
+        };
+        if recipients.is_empty() {
+            return Ok(());
+        }
+        
+        if let Some(tournament_id) = room.tournament_id.clone() {
+            self.record_tournament_result(&tournament_id, &room.room_id, room.series_winner()).await?;
+        }
+        
+        self.pay_out(&room.deposits, &recipients, room.rake_bps).await?;
+        room.escrow = Amount::ZERO;
+        room.deposits.clear();
+        
+        let stakes = std::mem::take(&mut room.wager_deposits);
+        match room.series_winner() {
+            Some(winner) => self.pay_out(&stakes, &[winner], room.rake_bps).await?,
+            None => {
+                for stake in stakes {
+                    self.refund_deposit(stake).await?;
+                }
+            }
+        }
+        
+        let message = Message::GameEnded {
+            room_id: room.room_id.clone(),
+            winner: room.series_winner(),
+            scores: room
+                .series_wins
+                .iter()
+                .map(|(player, wins)| (*player, *wins as u64))
+                .collect(),
+        };
+        self.notify_room(room, message);
+        Ok(())
+    }
+    
+    /// Split escrowed deposits evenly between `recipients`. Each pool is
+    /// only shared among the recipients who staked into it, so a free-play
+    /// winner never takes native tokens; a pool none of them staked into is
+    /// refunded to its depositors instead. A rake of `rake_bps` is taken from
+    /// the native tokens into the treasury and the rest is credited to each
+    /// recipient's claimable balance, staying in escrow until withdrawn.
+    /// Free-play tokens go back to their free-play balance.
+    async fn pay_out(
+        &mut self,
+        deposits: &[state::EscrowDeposit],
+        recipients: &[AccountOwner],
+        rake_bps: u16,
+    ) -> Result<(), GameStationError> {
+        let staked_into = |free_play: bool| -> Vec<AccountOwner> {
+            recipients
+                .iter()
+                .copied()
+                .filter(|recipient| {
+                    deposits.iter().any(|deposit| {
+                        deposit.player == *recipient && deposit.free_play == free_play
+                    })
+                })
+                .collect()
+        };
+        let native_winners = staked_into(false);
+        let free_play_winners = staked_into(true);
+        
+        // Nobody left to share a pool with, so give it back untouched
+        for deposit in deposits {
+            let winners = if deposit.free_play { &free_play_winners } else { &native_winners };
+            if winners.is_empty() {
+                self.refund_deposit(deposit.clone()).await?;
+            }
+        }
+        
+        let mut native_pot = Amount::ZERO;
+        if !native_winners.is_empty() {
+            native_pot = state::native_total(deposits);
+            let rake = state::rake_on(deposits, rake_bps);
+            if rake > Amount::ZERO {
+                native_pot.saturating_sub_assign(rake);
+                self.state.treasury.get_mut().saturating_add_assign(rake);
+                self.state.fees_collected.get_mut().saturating_add_assign(rake);
+            }
+        }
+        let mut free_play_pot = Amount::ZERO;
+        if !free_play_winners.is_empty() {
+            for deposit in deposits.iter().filter(|deposit| deposit.free_play) {
+                free_play_pot.saturating_add_assign(deposit.amount);
+            }
+        }
+        
+        let share_of = |pot: Amount, winners: &[AccountOwner]| {
+            Amount::from_attos(u128::from(pot) / winners.len().max(1) as u128)
+        };
+        let native_share = share_of(native_pot, &native_winners);
+        let free_play_share = share_of(free_play_pot, &free_play_winners);
+        
+        for recipient in recipients {
+            let native =
+                if native_winners.contains(recipient) { native_share } else { Amount::ZERO };
+            let free_play =
+                if free_play_winners.contains(recipient) { free_play_share } else { Amount::ZERO };
+            let tokens_won = native.saturating_add(free_play).saturating_div(Amount::ONE) as u64;
+            self.state.credit_winnings(recipient, native, free_play, tokens_won).await?;
+        }
+        Ok(())
+    }
+    
+    /// Move a tournament on to its next round and seat each of the round's
+    /// pairings in a new room. Swiss rounds are paired here from the current
+    /// standings, and a player given a bye is credited with a win straight away.
+    async fn start_tournament_round(
+        &mut self,
+        tournament: &mut state::Tournament,
+    ) -> Result<(), GameStationError> {
+        tournament.current_round += 1;
+        let round = tournament.current_round;
+        let now = self.runtime.system_time().micros();
+        let home_chain = self.runtime.chain_id();
+        
+        if let TournamentFormat::Swiss { .. } = tournament.format {
+            let pairs = tournament::swiss_pairings(&tournament.standings(), &tournament.pairings);
+            for (player_a, player_b) in pairs {
+                tournament.pairings.push(Pairing {
+                    round,
+                    player_a,
+                    player_b,
+                    room_id: None,
+                    finished: player_b.is_none(),
+                    winner: player_b.is_none().then_some(player_a),
+                    due_by: None,
+                });
+            }
+        }
+        
+        for idx in 0..tournament.pairings.len() {
+            let pairing = &tournament.pairings[idx];
+            if pairing.round != round || pairing.finished || pairing.room_id.is_some() {
+                continue;
+            }
+            let (player_a, Some(player_b)) = (pairing.player_a, pairing.player_b) else {
+                continue;
+            };
+            
+            let chain_of = |player: AccountOwner| {
+                tournament
+                    .players
+                    .iter()
+                    .find(|(p, _)| *p == player)
+                    .map_or(home_chain, |(_, chain_id)| *chain_id)
+            };
+            let (chain_a, chain_b) = (chain_of(player_a), chain_of(player_b));
+            
+            let room_id = format!("{}-R{}-{}", tournament.tournament_id, round, idx);
+            let mut room = state::GameRoom::new(
+                room_id.clone(),
+                tournament.game_type,
+                player_a,
+                chain_a,
+                2,
+                now,
+                self.state.config.get().waiting_room_ttl,
+            );
+            room.visibility = RoomVisibility::Private;
+            room.allowlist = vec![player_a, player_b];
+            room.players.push(player_b);
+            room.player_chains.push((player_b, chain_b));
+            room.tournament_id = Some(tournament.tournament_id.clone());
+            room.start(now + self.state.config.get().turn_timeout);
+            
+            for player in [player_a, player_b] {
+                self.notify_room(&room, Message::PlayerJoined { room_id: room_id.clone(), player });
+            }
+            self.emit_event(GameEvent::RoomCreated {
+                room_id: room_id.clone(),
+                game_type: tournament.game_type,
+                creator: tournament.creator,
+                timestamp: now,
+            });
+            self.state.save_room(room).await?;
+            tournament.pairings[idx].room_id = Some(room_id);
+        }
+        Ok(())
+    }
+    
+    /// Record the result of a tournament game, then pair the next round or
+    /// finish the tournament once every game of the round is done
+    async fn record_tournament_result(
+        &mut self,
+        tournament_id: &str,
+        room_id: &str,
+        winner: Option<AccountOwner>,
+    ) -> Result<(), GameStationError> {
+        let Some(mut tournament) = self.state.tournaments.get(tournament_id).await? else {
+            return Ok(());
+        };
+        let Some(pairing) = tournament
+            .pairings
+            .iter_mut()
+            .find(|p| p.room_id.as_deref() == Some(room_id) && !p.finished)
+        else {
+            return Ok(());
+        };
+        pairing.finished = true;
+        pairing.winner = winner;
+        
+        if tournament.round_complete() {
+            if tournament.current_round < tournament.total_rounds() {
+                self.start_tournament_round(&mut tournament).await?;
+            } else {
+                // Points, then Buchholz, decide the champion, who takes the prize pool
+                tournament.status = TournamentStatus::Finished;
+                if let Some(champion) = tournament.standings().first() {
+                    log::info!("{} won tournament {}", champion.player, tournament_id);
+                    let rake_bps = self.state.config.get().rake_bps;
+                    self.pay_out(&tournament.deposits, &[champion.player], rake_bps).await?;
+                    tournament.deposits.clear();
+                }
+            }
+        }
+        self.state.tournaments.insert(tournament_id, tournament)?;
+        Ok(())
+    }
+    
+    /// Publish an event on its stream for off-chain consumers
+    fn emit_event(&mut self, event: GameEvent) {
+        let stream = StreamName(event.stream_name().as_bytes().to_vec());
+        self.runtime.emit(stream, &event);
+    }
+    
+    /// Send a room event to every other chain a player joined the room from,
+    /// so they can keep their mirror of the room up to date
+    fn notify_room(&mut self, room: &state::GameRoom, message: Message) {
+        let mut notified = Vec::new();
+        for (_, chain_id) in &room.player_chains {
+            if *chain_id != self.runtime.chain_id() && !notified.contains(chain_id) {
+                notified.push(*chain_id);
+                self.runtime.send_message(*chain_id, message.clone());
+            }
+        }
     }
 }
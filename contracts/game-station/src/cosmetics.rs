@@ -0,0 +1,64 @@
+//! Linera Game Station - Cosmetics
+//!
+//! The shop's catalog of cosmetic items. The catalog is fixed in code, so
+//! every chain and client sees the same items and prices; players' owned
+//! items live in their profiles.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a cosmetic is shown; a player equips at most one item per slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum CosmeticSlot {
+    Avatar,
+    BoardSkin,
+    SnakeSkin,
+    /// Prestige rank badge
+    Badge,
+    /// Prestige rank profile frame
+    Frame,
+}
+
+/// An item sold in the shop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CosmeticItem {
+    pub item_id: &'static str,
+    pub name: &'static str,
+    pub slot: CosmeticSlot,
+    /// Price in arcade credits
+    pub price: u64,
+}
+
+const fn item(item_id: &'static str, name: &'static str, slot: CosmeticSlot, price: u64) -> CosmeticItem {
+    CosmeticItem { item_id, name, slot, price }
+}
+
+/// Every item sold in the shop
+pub const CATALOG: [CosmeticItem; 9] = [
+    item("avatar-robot", "Robot", CosmeticSlot::Avatar, 100),
+    item("avatar-ghost", "Ghost", CosmeticSlot::Avatar, 100),
+    item("avatar-dragon", "Dragon", CosmeticSlot::Avatar, 400),
+    item("board-neon", "Neon Grid", CosmeticSlot::BoardSkin, 150),
+    item("board-retro", "Retro Wood", CosmeticSlot::BoardSkin, 150),
+    item("board-galaxy", "Galaxy", CosmeticSlot::BoardSkin, 500),
+    item("snake-rainbow", "Rainbow", CosmeticSlot::SnakeSkin, 150),
+    item("snake-pixel", "Pixel", CosmeticSlot::SnakeSkin, 150),
+    item("snake-gold", "Gold Scales", CosmeticSlot::SnakeSkin, 600),
+];
+
+/// Look up a shop item by id
+pub fn find(item_id: &str) -> Option<&'static CosmeticItem> {
+    CATALOG.iter().find(|item| item.item_id == item_id)
+}
+
+/// Slot a cosmetic is equipped in, covering both shop items and the
+/// `prestige-badge-N` / `prestige-frame-N` items granted on prestige
+pub fn slot_of(item_id: &str) -> Option<CosmeticSlot> {
+    if let Some(item) = find(item_id) {
+        return Some(item.slot);
+    }
+    match item_id.strip_prefix("prestige-")?.split_once('-')? {
+        ("badge", _) => Some(CosmeticSlot::Badge),
+        ("frame", _) => Some(CosmeticSlot::Frame),
+        _ => None,
+    }
+}
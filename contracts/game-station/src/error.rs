@@ -0,0 +1,206 @@
+//! Linera Game Station - Errors
+//! 
+//! Failures that abort an operation or message. The contract panics with the
+//! error's description so the whole transaction is rejected.
+
+use linera_sdk::views::ViewError;
+use thiserror::Error;
+
+use crate::games::MoveError;
+
+/// Reasons an operation or message can fail
+#[derive(Debug, Error)]
+pub enum GameStationError {
+    #[error("operation requires an authenticated signer")]
+    Unauthenticated,
+    #[error("storage error: {0}")]
+    View(#[from] ViewError),
+    #[error("invalid move: {0}")]
+    InvalidMove(#[from] MoveError),
+    #[error("claimed score {claimed} does not match replayed score {replayed}")]
+    ScoreMismatch { claimed: u32, replayed: u32 },
+    #[error("room not found")]
+    RoomNotFound,
+    #[error("room has expired")]
+    RoomExpired,
+    #[error("not invited to this room")]
+    NotInvited,
+    #[error("room is full, already started, or already joined")]
+    RoomNotJoinable,
+    #[error("entry fee not covered")]
+    EntryFeeNotCovered,
+    #[error("not a player in this room")]
+    NotInRoom,
+    #[error("room is not in progress")]
+    RoomNotInProgress,
+    #[error("cannot leave a room that is in progress")]
+    CannotLeaveRoom,
+    #[error("turn deadline has passed")]
+    TurnDeadlinePassed,
+    #[error("room has no game in progress")]
+    NoGameInProgress,
+    #[error("no timeout to claim")]
+    NoTimeoutToClaim,
+    #[error("cannot request a rematch")]
+    CannotRematch,
+    #[error("this game is single-player")]
+    SinglePlayerGame,
+    #[error("chat message is empty or too long")]
+    InvalidChatMessage,
+    #[error("room chat is full")]
+    ChatFull,
+    #[error("not a party to this identity link")]
+    NotPartyToLink,
+    #[error("no free-play tokens to claim today")]
+    NothingToClaim,
+    #[error("message did not come from the expected chain")]
+    UnexpectedOrigin,
+    #[error("room hand-off is invalid")]
+    InvalidRoomHandoff,
+    #[error("could not close the room's temporary chain")]
+    CannotCloseChain,
+    #[error("identity link consent was not signed by the consenting address")]
+    InvalidIdentityProof,
+    #[error("a linked address can't be linked again or have addresses linked to it")]
+    IdentityAlreadyLinked,
+    #[error("username is already taken")]
+    UsernameTaken,
+    #[error("room size is out of range")]
+    InvalidRoomSize,
+    #[error("operation is restricted to the admin")]
+    NotAdmin,
+    #[error("the game station is paused")]
+    Paused,
+    #[error("account is banned")]
+    Banned,
+    #[error("report reason is empty or too long, or targets the reporter")]
+    InvalidReport,
+    #[error("tournament settings are out of range")]
+    InvalidTournament,
+    #[error("tournament not found")]
+    TournamentNotFound,
+    #[error("tournament is full, already started, or already joined")]
+    TournamentNotJoinable,
+    #[error("only the tournament creator can do this")]
+    NotTournamentCreator,
+    #[error("tournament cannot start without registration open and at least two players")]
+    CannotStartTournament,
+    #[error("tournament is not taking check-ins")]
+    CheckInClosed,
+    #[error("tournament check-in is still open")]
+    CheckInOpen,
+    #[error("not registered for this tournament")]
+    NotRegistered,
+    #[error("prestige requires the maximum level")]
+    CannotPrestige,
+    #[error("balance does not cover the price")]
+    InsufficientBalance,
+    #[error("season pass is already premium")]
+    PassAlreadyPremium,
+    #[error("season pass tier is locked or already claimed")]
+    PassRewardUnavailable,
+    #[error("wagers are only for two-player TicTacToe and Rock-Paper-Scissors rooms")]
+    InvalidWager,
+    #[error("board size is out of range for this game")]
+    InvalidBoardSize,
+    #[error("the swap2 opening is only for Gomoku rooms")]
+    InvalidOpeningRule,
+    #[error("the room's wager was not accepted")]
+    WagerNotAccepted,
+    #[error("rake is above the maximum")]
+    InvalidRake,
+    #[error("treasury balance does not cover the withdrawal")]
+    InsufficientTreasury,
+    #[error("claimable balance does not cover the withdrawal")]
+    InsufficientWinnings,
+    #[error("not enough arcade credits")]
+    InsufficientCredits,
+    #[error("cosmetic not found in the shop")]
+    CosmeticNotFound,
+    #[error("cosmetic is already owned")]
+    CosmeticAlreadyOwned,
+    #[error("cosmetic is not owned")]
+    CosmeticNotOwned,
+    #[error("cosmetic does not fit this slot")]
+    WrongCosmeticSlot,
+    #[error("NFT avatar is not owned by the signer")]
+    NftNotOwned,
+    #[error("tip is empty, to the sender, or has too long a memo")]
+    InvalidTip,
+    #[error("referral code not found")]
+    ReferralNotFound,
+    #[error("players cannot refer themselves or their own referrer")]
+    SelfReferral,
+    #[error("referrals are only for new players without a referrer")]
+    NotEligibleForReferral,
+    #[error("clan tag or name is invalid")]
+    InvalidClan,
+    #[error("clan tag is already taken")]
+    ClanTagTaken,
+    #[error("clan not found")]
+    ClanNotFound,
+    #[error("player is already in a clan")]
+    AlreadyInClan,
+    #[error("player is not in the clan")]
+    NotInClan,
+    #[error("clan role does not allow this")]
+    ClanRankTooLow,
+    #[error("clan is full")]
+    ClanFull,
+    #[error("no pending join request from this player")]
+    NoJoinRequest,
+    #[error("clan treasury does not cover the grant")]
+    InsufficientClanTreasury,
+    #[error("clan war needs two different clans and a window that has not ended")]
+    InvalidClanWar,
+    #[error("clan war not found")]
+    ClanWarNotFound,
+    #[error("clan war has not ended or was already settled")]
+    CannotSettleClanWar,
+    #[error("one of the players has blocked the other")]
+    Blocked,
+    #[error("cannot block or mute yourself, or the list is full")]
+    InvalidBlock,
+    #[error("the player has too many pending friend requests")]
+    FriendRequestsFull,
+    #[error("only the room creator can do this")]
+    NotRoomCreator,
+    #[error("room is not running a ready check, or its deadline has passed")]
+    NoReadyCheck,
+    #[error("no game session has been started")]
+    NoActiveSession,
+    #[error("no committed Minesweeper boards are left to deal")]
+    NoBoardAvailable,
+    #[error("clicks for this session were already submitted")]
+    SessionAlreadySubmitted,
+    #[error("clicks for this session have not been submitted yet")]
+    SessionNotSubmitted,
+    #[error("trivia question is malformed")]
+    InvalidTriviaQuestion,
+    #[error("the trivia question bank is empty")]
+    NoTriviaQuestions,
+    #[error("trivia rooms are played on the chain holding the question bank")]
+    TriviaOnDedicatedChain,
+    #[error("that day's Sudoku has not been published yet")]
+    PuzzleNotPublished,
+    #[error("grid is not a solution to that day's Sudoku")]
+    InvalidSudokuSolution,
+    #[error("that day's Sudoku was already solved")]
+    AlreadySolved,
+    #[error("a blackjack hand is already in play")]
+    HandInProgress,
+    #[error("the house bankroll can't cover that")]
+    HouseBankrollTooLow,
+    #[error("no committed blackjack shoes are left to deal")]
+    NoShoeAvailable,
+    #[error("guess must be five lowercase letters")]
+    InvalidWord,
+    #[error("that day's word is not open for guesses")]
+    WordNotOpen,
+    #[error("that day's word has already been published")]
+    WordAlreadyPublished,
+    #[error("no guesses left for that day's word")]
+    OutOfGuesses,
+    #[error("that day's word can't be revealed until the day is over")]
+    DayNotOver,
+}
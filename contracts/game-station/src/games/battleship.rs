@@ -0,0 +1,244 @@
+//! Battleship engine
+//!
+//! Fleets stay hidden: each player only commits to a hash of their layout
+//! and a salt. Players take turns firing, and the player fired at answers
+//! hit or miss. Once a fleet has been sunk, or a player has fired at every
+//! square, both players reveal their layouts. The contract checks each one
+//! against its commitment and every answer given, and a player caught lying
+//! loses.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, GameOutcome, MoveError};
+
+/// Width and height of each player's board
+pub const BOARD_SIZE: u8 = 10;
+
+/// Length of every ship in a fleet
+pub const FLEET: [u8; 5] = [5, 4, 3, 3, 2];
+
+/// Squares a whole fleet covers
+pub const FLEET_SQUARES: usize = 17;
+
+/// A ship placed from (`row`, `col`), extending down or to the right
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ship {
+    pub row: u8,
+    pub col: u8,
+    pub length: u8,
+    pub vertical: bool,
+}
+
+impl Ship {
+    fn squares(self) -> impl Iterator<Item = (u8, u8)> {
+        (0..self.length).map(move |i| {
+            if self.vertical {
+                (self.row + i, self.col)
+            } else {
+                (self.row, self.col + i)
+            }
+        })
+    }
+}
+
+/// Commitment a player submits for their fleet
+pub fn fleet_commitment(ships: &[Ship], salt: &[u8]) -> [u8; 32] {
+    let layout: Vec<u8> = ships
+        .iter()
+        .flat_map(|ship| [ship.row, ship.col, ship.length, ship.vertical as u8])
+        .collect();
+    commitment(&[&layout, salt])
+}
+
+/// Moves a player can make in a Battleship room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BattleshipMove {
+    /// Commit to a fleet layout before the game starts, `fleet_commitment(ships, salt)`
+    CommitFleet { commitment: [u8; 32] },
+    /// Fire at a square of the opponent's board on your turn
+    Fire { row: u8, col: u8 },
+    /// Say whether the shot just fired at you hit
+    Answer { hit: bool },
+    /// Reveal your fleet once the shooting is over
+    RevealFleet { ships: Vec<Ship>, salt: Vec<u8> },
+}
+
+/// A shot and the answer it got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shot {
+    pub row: u8,
+    pub col: u8,
+    pub hit: Option<bool>,
+}
+
+/// Battleship game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleshipState {
+    pub commitments: [Option<[u8; 32]>; 2],
+    /// Shots each player has fired at the other's board
+    pub shots: [Vec<Shot>; 2],
+    /// Player who fires next or, while a shot awaits its answer, who answers
+    pub current_turn: usize,
+    pub awaiting_answer: bool,
+    /// Set once the shooting is over and fleets are being revealed
+    pub revealing: bool,
+    /// Whether each player's revealed fleet matched their commitment and answers
+    pub honest: [Option<bool>; 2],
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl Default for BattleshipState {
+    fn default() -> Self {
+        Self {
+            commitments: [None; 2],
+            shots: [Vec::new(), Vec::new()],
+            current_turn: 0,
+            awaiting_answer: false,
+            revealing: false,
+            honest: [None; 2],
+            move_count: 0,
+            result: None,
+        }
+    }
+}
+
+impl BattleshipState {
+    /// Whether both fleets are committed and turns are being taken
+    pub fn is_started(&self) -> bool {
+        self.commitments.iter().all(Option::is_some)
+    }
+    
+    /// Hits a player has been told their shots scored
+    pub fn hits(&self, player: usize) -> usize {
+        self.shots[player].iter().filter(|shot| shot.hit == Some(true)).count()
+    }
+    
+    /// Decode BCS-encoded move data
+    pub fn decode_move(move_data: &[u8]) -> Result<BattleshipMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        mv: BattleshipMove,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player > 1 {
+            return Err(MoveError::NotYourTurn);
+        }
+        
+        match mv {
+            BattleshipMove::CommitFleet { commitment } => {
+                if self.commitments[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.commitments[player] = Some(commitment);
+            }
+            BattleshipMove::Fire { row, col } => {
+                if !self.is_started() || self.revealing || self.awaiting_answer {
+                    return Err(MoveError::IllegalMove);
+                }
+                if player != self.current_turn {
+                    return Err(MoveError::NotYourTurn);
+                }
+                if row >= BOARD_SIZE || col >= BOARD_SIZE {
+                    return Err(MoveError::OutOfBounds);
+                }
+                if self.shots[player].iter().any(|shot| (shot.row, shot.col) == (row, col)) {
+                    return Err(MoveError::CellOccupied);
+                }
+                self.shots[player].push(Shot { row, col, hit: None });
+                self.awaiting_answer = true;
+                self.current_turn = 1 - player;
+            }
+            BattleshipMove::Answer { hit } => {
+                if !self.awaiting_answer {
+                    return Err(MoveError::IllegalMove);
+                }
+                if player != self.current_turn {
+                    return Err(MoveError::NotYourTurn);
+                }
+                let shooter = 1 - player;
+                if let Some(shot) = self.shots[shooter].last_mut() {
+                    shot.hit = Some(hit);
+                }
+                self.awaiting_answer = false;
+                
+                let fired_everywhere = self.shots[shooter].len() == (BOARD_SIZE as usize).pow(2);
+                if self.hits(shooter) == FLEET_SQUARES || fired_everywhere {
+                    self.revealing = true;
+                    self.current_turn = 0;
+                } else {
+                    // The player who answered fires next
+                    self.current_turn = player;
+                }
+            }
+            BattleshipMove::RevealFleet { ships, salt } => {
+                if !self.revealing || self.honest[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.honest[player] = Some(self.is_honest(player, &ships, &salt));
+                self.current_turn = 1 - player;
+            }
+        }
+        self.move_count += 1;
+        
+        let (Some(honest_0), Some(honest_1)) = (self.honest[0], self.honest[1]) else {
+            return Ok(GameOutcome::Ongoing);
+        };
+        let outcome = match (honest_0, honest_1) {
+            (true, false) => GameOutcome::Winner(0),
+            (false, true) => GameOutcome::Winner(1),
+            (false, false) => GameOutcome::Draw,
+            // Both told the truth, so whoever sank the other's fleet wins
+            (true, true) => match (0..2).find(|&p| self.hits(p) == FLEET_SQUARES) {
+                Some(winner) => GameOutcome::Winner(winner),
+                None => GameOutcome::Draw,
+            },
+        };
+        self.result = Some(outcome);
+        Ok(outcome)
+    }
+    
+    /// Whether `ships` is a valid fleet matching `player`'s commitment and
+    /// every answer they gave
+    fn is_honest(&self, player: usize, ships: &[Ship], salt: &[u8]) -> bool {
+        if self.commitments[player] != Some(fleet_commitment(ships, salt)) {
+            return false;
+        }
+        
+        let mut lengths: Vec<u8> = ships.iter().map(|ship| ship.length).collect();
+        let mut fleet = FLEET.to_vec();
+        lengths.sort_unstable();
+        fleet.sort_unstable();
+        if lengths != fleet {
+            return false;
+        }
+        
+        let mut occupied = [[false; BOARD_SIZE as usize]; BOARD_SIZE as usize];
+        for ship in ships {
+            let start = if ship.vertical { ship.row } else { ship.col };
+            let fits = start as usize + ship.length as usize <= BOARD_SIZE as usize;
+            if ship.row >= BOARD_SIZE || ship.col >= BOARD_SIZE || !fits {
+                return false;
+            }
+            for (row, col) in ship.squares() {
+                let square = &mut occupied[row as usize][col as usize];
+                if *square {
+                    return false;
+                }
+                *square = true;
+            }
+        }
+        
+        self.shots[1 - player]
+            .iter()
+            .all(|shot| shot.hit == Some(occupied[shot.row as usize][shot.col as usize]))
+    }
+}
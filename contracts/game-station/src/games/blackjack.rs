@@ -0,0 +1,176 @@
+//! Blackjack engine
+//!
+//! One player against the house, dealt from an infinite shoe with no hole
+//! card: the dealer's second card comes only after the player has finished.
+//! The dealer stands on all 17s, a natural pays 3 to 2, and the player may
+//! double down on their first two cards. There is no splitting or insurance.
+//!
+//! The house can't pick the cards. Before the hand it commits to the end of
+//! a hash chain, `commitment(x1)` where `x1 = commitment(x2)` and so on, and
+//! deals each card by revealing the next value back along the chain. Each
+//! card mixes that value with a salt the player chose when betting, so the
+//! house can't have built the chain around the cards it wanted either.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, randomness::SeededRng, MoveError};
+
+/// A player's decision on their hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlackjackAction {
+    Hit,
+    Stand,
+    /// Double the bet and take exactly one more card
+    DoubleDown,
+}
+
+/// How a finished hand went for the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlackjackResult {
+    /// A natural, paid 3 to 2
+    Blackjack,
+    Win,
+    Push,
+    Loss,
+}
+
+impl BlackjackResult {
+    /// What the hand returns to the player, in halves of the original bet
+    pub fn payout_halves(self, doubled: bool) -> u128 {
+        let stake = if doubled { 4 } else { 2 };
+        match self {
+            BlackjackResult::Blackjack => 5,
+            BlackjackResult::Win => 2 * stake,
+            BlackjackResult::Push => stake,
+            BlackjackResult::Loss => 0,
+        }
+    }
+}
+
+/// Card dealt by revealing `preimage` from the house's hash chain in a hand
+/// the player salted with `salt`. Cards 0 to 51 run ace to king in each suit.
+pub fn draw_card(preimage: &[u8; 32], salt: &[u8]) -> u8 {
+    let hash = commitment(&[b"blackjack", preimage, salt]);
+    let seed = u64::from_le_bytes(hash[..8].try_into().expect("hash has at least 8 bytes"));
+    SeededRng::new(seed).below(52) as u8
+}
+
+/// Best total of `cards`, counting an ace as 11 if that doesn't bust
+pub fn hand_total(cards: &[u8]) -> u8 {
+    let hard: u8 = cards.iter().map(|&card| (card % 13 + 1).min(10)).sum();
+    let has_ace = cards.iter().any(|&card| card % 13 == 0);
+    if has_ace && hard + 10 <= 21 {
+        hard + 10
+    } else {
+        hard
+    }
+}
+
+fn is_natural(cards: &[u8]) -> bool {
+    cards.len() == 2 && hand_total(cards) == 21
+}
+
+/// A hand in play
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlackjackHand {
+    pub player_cards: Vec<u8>,
+    pub dealer_cards: Vec<u8>,
+    pub doubled: bool,
+    /// The player has hit or doubled and is waiting on the card
+    pub hitting: bool,
+    /// The player has stood, doubled, or reached 21
+    pub standing: bool,
+    /// Set once the hand has been decided
+    pub result: Option<BlackjackResult>,
+}
+
+impl BlackjackHand {
+    /// Whether the hand is waiting on the house to deal a card
+    pub fn wants_card(&self) -> bool {
+        if self.result.is_some() {
+            return false;
+        }
+        self.player_cards.len() < 2 || self.hitting || (self.standing && self.dealer_draws())
+    }
+    
+    /// Whether the hand is waiting on the player
+    pub fn wants_action(&self) -> bool {
+        self.result.is_none() && !self.wants_card() && !self.standing
+    }
+    
+    /// Deal the next card: the player's first, the dealer's first and the
+    /// player's second, then whoever is drawing
+    pub fn deal(&mut self, card: u8) {
+        if self.dealer_cards.is_empty() && self.player_cards.len() == 1 {
+            self.dealer_cards.push(card);
+        } else if self.player_cards.len() < 2 || self.hitting {
+            self.player_cards.push(card);
+            self.hitting = false;
+            self.standing |= self.doubled;
+        } else {
+            self.dealer_cards.push(card);
+        }
+        self.resolve();
+    }
+    
+    /// Apply the player's decision
+    pub fn act(&mut self, action: BlackjackAction) -> Result<(), MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if !self.wants_action() {
+            return Err(MoveError::NotYourTurn);
+        }
+        match action {
+            BlackjackAction::Hit => self.hitting = true,
+            BlackjackAction::Stand => self.standing = true,
+            BlackjackAction::DoubleDown => {
+                if self.player_cards.len() != 2 {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.doubled = true;
+                self.hitting = true;
+            }
+        }
+        self.resolve();
+        Ok(())
+    }
+    
+    /// Whether the dealer takes another card. Against a natural the dealer
+    /// only draws the one card that could tie it.
+    fn dealer_draws(&self) -> bool {
+        if is_natural(&self.player_cards) {
+            return self.dealer_cards.len() < 2;
+        }
+        hand_total(&self.dealer_cards) < 17
+    }
+    
+    /// Settle the hand once nothing more can change it
+    fn resolve(&mut self) {
+        if self.player_cards.len() < 2 || self.hitting {
+            return;
+        }
+        let player = hand_total(&self.player_cards);
+        if player > 21 {
+            self.result = Some(BlackjackResult::Loss);
+            return;
+        }
+        // Nothing beats 21, so stand on it
+        self.standing |= player == 21;
+        if !self.standing || self.dealer_draws() {
+            return;
+        }
+        
+        let dealer = hand_total(&self.dealer_cards);
+        self.result = Some(
+            match (is_natural(&self.player_cards), is_natural(&self.dealer_cards)) {
+                (true, true) => BlackjackResult::Push,
+                (true, false) => BlackjackResult::Blackjack,
+                (false, true) => BlackjackResult::Loss,
+                _ if dealer > 21 || player > dealer => BlackjackResult::Win,
+                _ if player == dealer => BlackjackResult::Push,
+                _ => BlackjackResult::Loss,
+            },
+        );
+    }
+}
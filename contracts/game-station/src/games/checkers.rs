@@ -0,0 +1,309 @@
+//! Checkers (English draughts) engine
+//!
+//! Men move and capture diagonally forward, kings one square either way.
+//! Capturing is mandatory, and a capturing piece must keep jumping while it
+//! can; a man reaching the far row is crowned and its move ends there.
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+
+/// Moves in a row without a capture or crowning before the game is drawn
+pub const QUIET_MOVE_LIMIT: u32 = 80;
+
+/// A square as (row, col), both `0..8`. Only squares with an even
+/// `row + col` are played on.
+pub type Square = (u8, u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckersPiece {
+    /// Index of the owning player in the room's player list
+    pub player: usize,
+    pub king: bool,
+}
+
+/// Checkers game state. The first player starts on rows 0 to 2 and moves
+/// first; the second starts on rows 5 to 7.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckersState {
+    /// Pieces indexed by `[row][col]`
+    pub board: [[Option<CheckersPiece>; 8]; 8],
+    pub current_turn: usize,
+    pub move_count: u32,
+    /// Moves since the last capture or crowning
+    pub quiet_moves: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl Default for CheckersState {
+    fn default() -> Self {
+        let mut board = [[None; 8]; 8];
+        for row in (0..3).chain(5..8) {
+            for col in (0..8).filter(|col| (row + col) % 2 == 0) {
+                let player = if row < 3 { 0 } else { 1 };
+                board[row][col] = Some(CheckersPiece { player, king: false });
+            }
+        }
+        Self {
+            board,
+            current_turn: 0,
+            move_count: 0,
+            quiet_moves: 0,
+            result: None,
+        }
+    }
+}
+
+impl CheckersState {
+    /// Decode move data as the squares the piece visits, `[row, col]` pairs
+    /// from its start square to where it lands after its last jump
+    pub fn decode_move(move_data: &[u8]) -> Result<Vec<Square>, MoveError> {
+        if move_data.len() < 4 || move_data.len() % 2 != 0 {
+            return Err(MoveError::InvalidEncoding);
+        }
+        let path: Vec<Square> = move_data.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+        if path.iter().any(|&(row, col)| row >= 8 || col >= 8) {
+            return Err(MoveError::OutOfBounds);
+        }
+        Ok(path)
+    }
+    
+    /// Move `player`'s piece along `path`, rejecting it unless it is legal
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        path: Vec<Square>,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if !self.legal_moves(player).contains(&path) {
+            return Err(MoveError::IllegalMove);
+        }
+        
+        let (from, to) = (path[0], path[path.len() - 1]);
+        let Some(mut piece) = self.board[from.0 as usize][from.1 as usize].take() else {
+            return Err(MoveError::IllegalMove);
+        };
+        let mut captures = 0;
+        for hop in path.windows(2) {
+            if hop[0].0.abs_diff(hop[1].0) == 2 {
+                let over = ((hop[0].0 + hop[1].0) / 2, (hop[0].1 + hop[1].1) / 2);
+                self.board[over.0 as usize][over.1 as usize] = None;
+                captures += 1;
+            }
+        }
+        let crowned = !piece.king && to.0 == far_row(player);
+        piece.king |= crowned;
+        self.board[to.0 as usize][to.1 as usize] = Some(piece);
+        
+        self.move_count += 1;
+        self.quiet_moves = if captures > 0 || crowned { 0 } else { self.quiet_moves + 1 };
+        self.current_turn = 1 - player;
+        
+        // A player with no pieces or no moves left loses
+        if self.legal_moves(self.current_turn).is_empty() {
+            self.result = Some(GameOutcome::Winner(player));
+        } else if self.quiet_moves >= QUIET_MOVE_LIMIT {
+            self.result = Some(GameOutcome::Draw);
+        }
+        Ok(self.result.unwrap_or(GameOutcome::Ongoing))
+    }
+    
+    /// Every legal move for `player`, as the squares each one visits. Only
+    /// complete capture sequences are legal when any capture is available.
+    pub fn legal_moves(&self, player: usize) -> Vec<Vec<Square>> {
+        let mut captures = Vec::new();
+        let mut steps = Vec::new();
+        for row in 0..8u8 {
+            for col in 0..8u8 {
+                let Some(piece) = self.board[row as usize][col as usize] else {
+                    continue;
+                };
+                if piece.player != player {
+                    continue;
+                }
+                let from = (row, col);
+                self.jumps(piece, &mut vec![from], &mut Vec::new(), &mut captures);
+                for (dr, dc) in directions(piece) {
+                    if let Some(to) = offset(from, dr, dc).filter(|to| self.is_empty(*to)) {
+                        steps.push(vec![from, to]);
+                    }
+                }
+            }
+        }
+        if captures.is_empty() {
+            steps
+        } else {
+            captures
+        }
+    }
+    
+    fn is_empty(&self, (row, col): Square) -> bool {
+        self.board[row as usize][col as usize].is_none()
+    }
+    
+    /// Extend the capture sequence in `path` every way it can go, adding
+    /// each finished sequence to `moves`. Jumped pieces stay on the board
+    /// until the move ends, so they block landings and can't be jumped twice.
+    fn jumps(
+        &self,
+        piece: CheckersPiece,
+        path: &mut Vec<Square>,
+        jumped: &mut Vec<Square>,
+        moves: &mut Vec<Vec<Square>>,
+    ) {
+        let from = path[path.len() - 1];
+        let start = path[0];
+        let crowned = !piece.king && from.0 == far_row(piece.player) && path.len() > 1;
+        
+        let mut extended = false;
+        if !crowned {
+            for (dr, dc) in directions(piece) {
+                let Some(over) = offset(from, dr, dc) else {
+                    continue;
+                };
+                let Some(to) = offset(over, dr, dc) else {
+                    continue;
+                };
+                let enemy = self.board[over.0 as usize][over.1 as usize]
+                    .is_some_and(|other| other.player != piece.player);
+                // The moving piece has left its start square, so it can land there again
+                let open = self.is_empty(to) || to == start;
+                if enemy && open && !jumped.contains(&over) {
+                    path.push(to);
+                    jumped.push(over);
+                    self.jumps(piece, path, jumped, moves);
+                    path.pop();
+                    jumped.pop();
+                    extended = true;
+                }
+            }
+        }
+        if !extended && path.len() > 1 {
+            moves.push(path.clone());
+        }
+    }
+}
+
+/// Row where `player`'s men are crowned
+fn far_row(player: usize) -> u8 {
+    if player == 0 {
+        7
+    } else {
+        0
+    }
+}
+
+/// Directions a piece may move and capture in, as (row, col) steps
+fn directions(piece: CheckersPiece) -> Vec<(i8, i8)> {
+    let forward = if piece.player == 0 { 1 } else { -1 };
+    let mut directions = vec![(forward, -1), (forward, 1)];
+    if piece.king {
+        directions.extend([(-forward, -1), (-forward, 1)]);
+    }
+    directions
+}
+
+/// The square `(dr, dc)` away from `square`, if it is on the board
+fn offset(square: Square, dr: i8, dc: i8) -> Option<Square> {
+    let row = square.0 as i8 + dr;
+    let col = square.1 as i8 + dc;
+    ((0..8).contains(&row) && (0..8).contains(&col)).then_some((row as u8, col as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A board with only `pieces`, as (square, player, king), and player 0 to move
+    fn position(pieces: &[(Square, usize, bool)]) -> CheckersState {
+        let mut game = CheckersState { board: [[None; 8]; 8], ..CheckersState::default() };
+        for &((row, col), player, king) in pieces {
+            game.board[row as usize][col as usize] = Some(CheckersPiece { player, king });
+        }
+        game
+    }
+    
+    #[test]
+    fn opening_moves() {
+        let mut game = CheckersState::default();
+        assert_eq!(game.legal_moves(0).len(), 7);
+        assert_eq!(game.apply_move(1, vec![(5, 1), (4, 0)]), Err(MoveError::NotYourTurn));
+        // Men only move forward, one square at a time
+        assert_eq!(game.apply_move(0, vec![(2, 0), (4, 2)]), Err(MoveError::IllegalMove));
+        assert_eq!(game.apply_move(0, vec![(2, 0), (3, 1)]), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.current_turn, 1);
+    }
+    
+    #[test]
+    fn capturing_is_mandatory() {
+        let mut game = position(&[
+            ((2, 2), 0, false),
+            ((2, 6), 0, false),
+            ((3, 3), 1, false),
+            ((7, 7), 1, false),
+        ]);
+        assert_eq!(game.legal_moves(0), vec![vec![(2, 2), (4, 4)]]);
+        assert_eq!(game.apply_move(0, vec![(2, 6), (3, 7)]), Err(MoveError::IllegalMove));
+        
+        assert_eq!(game.apply_move(0, vec![(2, 2), (4, 4)]), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.board[3][3], None);
+        assert_eq!(game.quiet_moves, 0);
+    }
+    
+    #[test]
+    fn multi_jump_must_be_completed() {
+        let mut game = position(&[((0, 0), 0, false), ((1, 1), 1, false), ((3, 3), 1, false)]);
+        assert_eq!(game.legal_moves(0), vec![vec![(0, 0), (2, 2), (4, 4)]]);
+        assert_eq!(game.apply_move(0, vec![(0, 0), (2, 2)]), Err(MoveError::IllegalMove));
+        
+        // Taking the last pieces wins
+        assert_eq!(game.apply_move(0, vec![(0, 0), (2, 2), (4, 4)]), Ok(GameOutcome::Winner(0)));
+        assert_eq!(game.board[1][1], None);
+        assert_eq!(game.board[3][3], None);
+    }
+    
+    #[test]
+    fn multi_jump_branches_are_all_offered() {
+        let game = position(&[
+            ((2, 2), 0, false),
+            ((3, 3), 1, false),
+            ((5, 3), 1, false),
+            ((5, 5), 1, false),
+        ]);
+        let mut moves = game.legal_moves(0);
+        moves.sort();
+        assert_eq!(moves, vec![vec![(2, 2), (4, 4), (6, 2)], vec![(2, 2), (4, 4), (6, 6)]]);
+    }
+    
+    #[test]
+    fn crowning_ends_the_move() {
+        let mut game = position(&[((5, 1), 0, false), ((6, 2), 1, false), ((6, 4), 1, false)]);
+        // As a king it could jump on over (6, 4), but being crowned stops it
+        assert_eq!(game.legal_moves(0), vec![vec![(5, 1), (7, 3)]]);
+        
+        assert_eq!(game.apply_move(0, vec![(5, 1), (7, 3)]), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.board[7][3], Some(CheckersPiece { player: 0, king: true }));
+    }
+    
+    #[test]
+    fn kings_move_backwards() {
+        let game = position(&[((4, 4), 0, true), ((7, 7), 1, false)]);
+        let mut moves = game.legal_moves(0);
+        moves.sort();
+        assert_eq!(
+            moves,
+            vec![
+                vec![(4, 4), (3, 3)],
+                vec![(4, 4), (3, 5)],
+                vec![(4, 4), (5, 3)],
+                vec![(4, 4), (5, 5)],
+            ]
+        );
+    }
+}
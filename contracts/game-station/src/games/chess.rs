@@ -0,0 +1,676 @@
+//! Chess engine
+//!
+//! Full move legality: a move may not leave the mover's king in check,
+//! castling needs the right, empty squares between king and rook and no
+//! attacked square on the king's path, and en passant is only allowed right
+//! after the double step. Checkmate and stalemate end the game, and
+//! threefold repetition and the fifty-move rule are drawn automatically.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, GameOutcome, MoveError};
+
+/// Half-moves without a capture or pawn move before the game is drawn
+pub const FIFTY_MOVE_PLIES: u32 = 100;
+
+const KNIGHT_STEPS: [(i8, i8); 8] =
+    [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+/// One step in every direction, which is also how far queens slide
+const KING_STEPS: [(i8, i8); 8] =
+    [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+const PROMOTIONS: [PieceKind; 4] =
+    [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight];
+
+/// Side of the board; the first player in the room plays White
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    /// Index of the player with this color in the room's player list
+    pub fn player_index(self) -> usize {
+        match self {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+    
+    /// Color played by the player at `index`, if any
+    pub fn from_player_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Color::White),
+            1 => Some(Color::Black),
+            _ => None,
+        }
+    }
+    
+    pub fn opponent(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+    
+    /// Rank step of this color's pawns
+    fn forward(self) -> i8 {
+        match self {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+    
+    /// Rank the pieces start on
+    fn back_rank(self) -> u8 {
+        match self {
+            Color::White => 0,
+            Color::Black => 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Piece {
+    pub kind: PieceKind,
+    pub color: Color,
+}
+
+/// A square as (file, rank), both `0..8`; a1 is (0, 0) and h8 is (7, 7)
+pub type Square = (u8, u8);
+
+/// Pieces indexed by `[rank][file]`
+pub type Board = [[Option<Piece>; 8]; 8];
+
+/// A move from one square to another. Castling is the king moving two
+/// files; `promotion` is required when a pawn reaches the last rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChessMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceKind>,
+}
+
+/// Castling moves each side may still make
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+impl CastlingRights {
+    fn kingside(&self, color: Color) -> bool {
+        match color {
+            Color::White => self.white_kingside,
+            Color::Black => self.black_kingside,
+        }
+    }
+    
+    fn queenside(&self, color: Color) -> bool {
+        match color {
+            Color::White => self.white_queenside,
+            Color::Black => self.black_queenside,
+        }
+    }
+    
+    /// Drop the rights a move from or onto `square` ends: moving the king or
+    /// a rook, or having the rook captured
+    fn revoke(&mut self, square: Square) {
+        match square {
+            (4, 0) => (self.white_kingside, self.white_queenside) = (false, false),
+            (0, 0) => self.white_queenside = false,
+            (7, 0) => self.white_kingside = false,
+            (4, 7) => (self.black_kingside, self.black_queenside) = (false, false),
+            (0, 7) => self.black_queenside = false,
+            (7, 7) => self.black_kingside = false,
+            _ => {}
+        }
+    }
+}
+
+/// Chess game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChessState {
+    pub board: Board,
+    pub side_to_move: Color,
+    pub castling: CastlingRights,
+    /// Square a pawn may capture onto en passant, right after a double step
+    pub en_passant: Option<Square>,
+    /// Half-moves since the last capture or pawn move
+    pub halfmove_clock: u32,
+    /// Half-moves played so far
+    pub move_count: u32,
+    /// Keys of the positions since the last capture or pawn move, which no
+    /// earlier position can repeat
+    pub history: Vec<u64>,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl Default for ChessState {
+    fn default() -> Self {
+        let back = [
+            PieceKind::Rook,
+            PieceKind::Knight,
+            PieceKind::Bishop,
+            PieceKind::Queen,
+            PieceKind::King,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            PieceKind::Rook,
+        ];
+        let mut board: Board = [[None; 8]; 8];
+        for (file, kind) in back.into_iter().enumerate() {
+            board[0][file] = Some(Piece { kind, color: Color::White });
+            board[1][file] = Some(Piece { kind: PieceKind::Pawn, color: Color::White });
+            board[6][file] = Some(Piece { kind: PieceKind::Pawn, color: Color::Black });
+            board[7][file] = Some(Piece { kind, color: Color::Black });
+        }
+        
+        let mut state = Self {
+            board,
+            side_to_move: Color::White,
+            castling: CastlingRights {
+                white_kingside: true,
+                white_queenside: true,
+                black_kingside: true,
+                black_queenside: true,
+            },
+            en_passant: None,
+            halfmove_clock: 0,
+            move_count: 0,
+            history: Vec::new(),
+            result: None,
+        };
+        state.history.push(state.position_key());
+        state
+    }
+}
+
+impl ChessState {
+    /// Decode move data as a coordinate move in ASCII, e.g. `e2e4`, or
+    /// `e7e8q` for a promotion
+    pub fn decode_move(move_data: &[u8]) -> Result<ChessMove, MoveError> {
+        let square = |file: u8, rank: u8| match (file, rank) {
+            (b'a'..=b'h', b'1'..=b'8') => Ok((file - b'a', rank - b'1')),
+            _ => Err(MoveError::InvalidEncoding),
+        };
+        let (from, to, promotion) = match move_data {
+            [f1, r1, f2, r2] => (square(*f1, *r1)?, square(*f2, *r2)?, None),
+            [f1, r1, f2, r2, piece] => {
+                let kind = match piece.to_ascii_lowercase() {
+                    b'q' => PieceKind::Queen,
+                    b'r' => PieceKind::Rook,
+                    b'b' => PieceKind::Bishop,
+                    b'n' => PieceKind::Knight,
+                    _ => return Err(MoveError::InvalidEncoding),
+                };
+                (square(*f1, *r1)?, square(*f2, *r2)?, Some(kind))
+            }
+            _ => return Err(MoveError::InvalidEncoding),
+        };
+        Ok(ChessMove { from, to, promotion })
+    }
+    
+    /// Play `chess_move` for `color`, rejecting it unless it is legal
+    pub fn apply_move(
+        &mut self,
+        color: Color,
+        chess_move: ChessMove,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if color != self.side_to_move {
+            return Err(MoveError::NotYourTurn);
+        }
+        if !self.legal_moves().contains(&chess_move) {
+            return Err(MoveError::IllegalMove);
+        }
+        
+        self.play(chess_move);
+        
+        let key = self.position_key();
+        let repetitions = self.history.iter().filter(|k| **k == key).count();
+        if self.legal_moves().is_empty() {
+            // Checkmate, or stalemate if the side to move is not in check
+            self.result = Some(if self.in_check(self.side_to_move) {
+                GameOutcome::Winner(color.player_index())
+            } else {
+                GameOutcome::Draw
+            });
+        } else if self.halfmove_clock >= FIFTY_MOVE_PLIES || repetitions >= 3 {
+            self.result = Some(GameOutcome::Draw);
+        }
+        Ok(self.result.unwrap_or(GameOutcome::Ongoing))
+    }
+    
+    /// Every legal move for the side to move
+    pub fn legal_moves(&self) -> Vec<ChessMove> {
+        let color = self.side_to_move;
+        self.pseudo_legal_moves(color)
+            .into_iter()
+            .filter(|chess_move| {
+                let board = self.board_after(*chess_move);
+                king_square(&board, color)
+                    .is_some_and(|king| !square_attacked(&board, king, color.opponent()))
+            })
+            .collect()
+    }
+    
+    /// Whether `color`'s king is attacked
+    pub fn in_check(&self, color: Color) -> bool {
+        king_square(&self.board, color)
+            .is_some_and(|king| square_attacked(&self.board, king, color.opponent()))
+    }
+    
+    fn piece_at(&self, square: Square) -> Option<Piece> {
+        piece_at(&self.board, square)
+    }
+    
+    /// Moves that follow the pieces' movement rules, ignoring whether they
+    /// leave the mover's king in check
+    fn pseudo_legal_moves(&self, color: Color) -> Vec<ChessMove> {
+        let mut moves = Vec::new();
+        for rank in 0..8 {
+            for file in 0..8 {
+                let from = (file, rank);
+                if let Some(piece) = self.piece_at(from).filter(|p| p.color == color) {
+                    self.piece_moves(from, piece, &mut moves);
+                }
+            }
+        }
+        moves
+    }
+    
+    fn piece_moves(&self, from: Square, piece: Piece, moves: &mut Vec<ChessMove>) {
+        let color = piece.color;
+        let open_or_enemy = |square: Square| self.piece_at(square).is_none_or(|p| p.color != color);
+        match piece.kind {
+            PieceKind::Pawn => self.pawn_moves(from, color, moves),
+            PieceKind::Knight | PieceKind::King => {
+                let steps = if piece.kind == PieceKind::Knight { KNIGHT_STEPS } else { KING_STEPS };
+                for (df, dr) in steps {
+                    if let Some(to) = offset(from, df, dr).filter(|to| open_or_enemy(*to)) {
+                        moves.push(ChessMove { from, to, promotion: None });
+                    }
+                }
+                if piece.kind == PieceKind::King {
+                    self.castling_moves(from, color, moves);
+                }
+            }
+            PieceKind::Bishop | PieceKind::Rook | PieceKind::Queen => {
+                let directions = match piece.kind {
+                    PieceKind::Bishop => &BISHOP_DIRECTIONS[..],
+                    PieceKind::Rook => &ROOK_DIRECTIONS[..],
+                    _ => &KING_STEPS[..],
+                };
+                for &(df, dr) in directions {
+                    let mut next = offset(from, df, dr);
+                    while let Some(to) = next {
+                        let occupant = self.piece_at(to);
+                        if occupant.is_none_or(|p| p.color != color) {
+                            moves.push(ChessMove { from, to, promotion: None });
+                        }
+                        if occupant.is_some() {
+                            break;
+                        }
+                        next = offset(to, df, dr);
+                    }
+                }
+            }
+        }
+    }
+    
+    fn pawn_moves(&self, from: Square, color: Color, moves: &mut Vec<ChessMove>) {
+        let forward = color.forward();
+        let mut push = |to: Square| {
+            if to.1 == color.opponent().back_rank() {
+                for kind in PROMOTIONS {
+                    moves.push(ChessMove { from, to, promotion: Some(kind) });
+                }
+            } else {
+                moves.push(ChessMove { from, to, promotion: None });
+            }
+        };
+        
+        let empty = |square: &Square| self.piece_at(*square).is_none();
+        if let Some(one) = offset(from, 0, forward).filter(empty) {
+            push(one);
+            let start_rank = (color.back_rank() as i8 + forward) as u8;
+            if from.1 == start_rank {
+                if let Some(two) = offset(one, 0, forward).filter(empty) {
+                    push(two);
+                }
+            }
+        }
+        for df in [-1, 1] {
+            let Some(to) = offset(from, df, forward) else {
+                continue;
+            };
+            let captures = self.piece_at(to).is_some_and(|p| p.color != color);
+            if captures || self.en_passant == Some(to) {
+                push(to);
+            }
+        }
+    }
+    
+    fn castling_moves(&self, from: Square, color: Color, moves: &mut Vec<ChessMove>) {
+        let rank = color.back_rank();
+        if from != (4, rank) || self.in_check(color) {
+            return;
+        }
+        let enemy = color.opponent();
+        let empty = |files: &[u8]| files.iter().all(|&file| self.piece_at((file, rank)).is_none());
+        let safe = |files: &[u8]| {
+            files.iter().all(|&file| !square_attacked(&self.board, (file, rank), enemy))
+        };
+        
+        if self.castling.kingside(color) && empty(&[5, 6]) && safe(&[5, 6]) {
+            moves.push(ChessMove { from, to: (6, rank), promotion: None });
+        }
+        if self.castling.queenside(color) && empty(&[1, 2, 3]) && safe(&[2, 3]) {
+            moves.push(ChessMove { from, to: (2, rank), promotion: None });
+        }
+    }
+    
+    /// The board after `chess_move`, including the rook's part of castling
+    /// and the pawn taken en passant
+    fn board_after(&self, chess_move: ChessMove) -> Board {
+        let ChessMove { from, to, promotion } = chess_move;
+        let mut board = self.board;
+        let Some(piece) = board[from.1 as usize][from.0 as usize].take() else {
+            return board;
+        };
+        
+        if piece.kind == PieceKind::Pawn && from.0 != to.0 && self.piece_at(to).is_none() {
+            board[from.1 as usize][to.0 as usize] = None;
+        }
+        if piece.kind == PieceKind::King && from.0.abs_diff(to.0) == 2 {
+            let (rook_from, rook_to) = if to.0 == 6 { (7, 5) } else { (0, 3) };
+            board[from.1 as usize][rook_to] = board[from.1 as usize][rook_from].take();
+        }
+        board[to.1 as usize][to.0 as usize] = Some(Piece {
+            kind: promotion.unwrap_or(piece.kind),
+            color: piece.color,
+        });
+        board
+    }
+    
+    /// Play a move already known to be legal
+    fn play(&mut self, chess_move: ChessMove) {
+        let ChessMove { from, to, .. } = chess_move;
+        let Some(piece) = self.piece_at(from) else {
+            return;
+        };
+        let irreversible = piece.kind == PieceKind::Pawn || self.piece_at(to).is_some();
+        
+        self.board = self.board_after(chess_move);
+        self.castling.revoke(from);
+        self.castling.revoke(to);
+        self.en_passant = (piece.kind == PieceKind::Pawn && from.1.abs_diff(to.1) == 2)
+            .then_some((from.0, (from.1 + to.1) / 2));
+        if irreversible {
+            self.halfmove_clock = 0;
+            self.history.clear();
+        } else {
+            self.halfmove_clock += 1;
+        }
+        self.side_to_move = self.side_to_move.opponent();
+        self.move_count += 1;
+        self.history.push(self.position_key());
+    }
+    
+    /// Hash of everything that makes two positions the same for repetition:
+    /// placement, side to move, castling rights and en passant square
+    fn position_key(&self) -> u64 {
+        let mut bytes = Vec::with_capacity(70);
+        for piece in self.board.iter().flatten() {
+            bytes.push(match piece {
+                None => 0,
+                Some(piece) => 1 + piece.kind as u8 + 6 * piece.color as u8,
+            });
+        }
+        bytes.push(self.side_to_move as u8);
+        let castling = [
+            self.castling.white_kingside,
+            self.castling.white_queenside,
+            self.castling.black_kingside,
+            self.castling.black_queenside,
+        ];
+        bytes.extend(castling.map(u8::from));
+        bytes.push(self.en_passant.map_or(0, |(file, _)| file + 1));
+        let hash = commitment(&[&bytes]);
+        u64::from_le_bytes(hash[..8].try_into().expect("hash has at least 8 bytes"))
+    }
+}
+
+fn piece_at(board: &Board, (file, rank): Square) -> Option<Piece> {
+    board[rank as usize][file as usize]
+}
+
+/// The square `(df, dr)` away from `square`, if it is on the board
+fn offset(square: Square, df: i8, dr: i8) -> Option<Square> {
+    let file = square.0 as i8 + df;
+    let rank = square.1 as i8 + dr;
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((file as u8, rank as u8))
+}
+
+fn king_square(board: &Board, color: Color) -> Option<Square> {
+    (0..8u8)
+        .flat_map(|rank| (0..8u8).map(move |file| (file, rank)))
+        .find(|square| piece_at(board, *square) == Some(Piece { kind: PieceKind::King, color }))
+}
+
+/// Whether any of `by`'s pieces attacks `square`
+fn square_attacked(board: &Board, square: Square, by: Color) -> bool {
+    let holds = |target: Option<Square>, kinds: &[PieceKind]| {
+        target
+            .and_then(|target| piece_at(board, target))
+            .is_some_and(|piece| piece.color == by && kinds.contains(&piece.kind))
+    };
+    
+    // Pawns attack diagonally forward, so look one rank back from `square`
+    let pawn_rank = -by.forward();
+    if [-1, 1].into_iter().any(|df| holds(offset(square, df, pawn_rank), &[PieceKind::Pawn])) {
+        return true;
+    }
+    if KNIGHT_STEPS.iter().any(|&(df, dr)| holds(offset(square, df, dr), &[PieceKind::Knight])) {
+        return true;
+    }
+    if KING_STEPS.iter().any(|&(df, dr)| holds(offset(square, df, dr), &[PieceKind::King])) {
+        return true;
+    }
+    
+    let slides = |directions: &[(i8, i8)], kinds: &[PieceKind]| {
+        directions.iter().any(|&(df, dr)| {
+            let mut next = offset(square, df, dr);
+            while let Some(target) = next {
+                if piece_at(board, target).is_some() {
+                    return holds(Some(target), kinds);
+                }
+                next = offset(target, df, dr);
+            }
+            false
+        })
+    };
+    slides(&ROOK_DIRECTIONS, &[PieceKind::Rook, PieceKind::Queen])
+        || slides(&BISHOP_DIRECTIONS, &[PieceKind::Bishop, PieceKind::Queen])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Color::{Black, White};
+    use PieceKind::{Bishop, King, Knight, Pawn, Queen, Rook};
+    
+    fn square(name: &str) -> Square {
+        let bytes = name.as_bytes();
+        (bytes[0] - b'a', bytes[1] - b'1')
+    }
+    
+    fn mv(text: &str) -> ChessMove {
+        ChessState::decode_move(text.as_bytes()).unwrap()
+    }
+    
+    /// Play `moves` in turn from `game`, returning the last outcome
+    fn play_all(game: &mut ChessState, moves: &[&str]) -> GameOutcome {
+        let mut outcome = GameOutcome::Ongoing;
+        for text in moves {
+            outcome = game.apply_move(game.side_to_move, mv(text)).unwrap();
+        }
+        outcome
+    }
+    
+    /// A position with only `pieces` on the board and no castling rights
+    fn position(pieces: &[(&str, PieceKind, Color)], side_to_move: Color) -> ChessState {
+        let mut game = ChessState {
+            board: [[None; 8]; 8],
+            side_to_move,
+            castling: CastlingRights {
+                white_kingside: false,
+                white_queenside: false,
+                black_kingside: false,
+                black_queenside: false,
+            },
+            en_passant: None,
+            halfmove_clock: 0,
+            move_count: 0,
+            history: Vec::new(),
+            result: None,
+        };
+        for &(name, kind, color) in pieces {
+            let (file, rank) = square(name);
+            game.board[rank as usize][file as usize] = Some(Piece { kind, color });
+        }
+        game.history.push(game.position_key());
+        game
+    }
+    
+    #[test]
+    fn legal_and_illegal_moves() {
+        let mut game = ChessState::default();
+        assert_eq!(game.legal_moves().len(), 20);
+        assert_eq!(game.apply_move(Black, mv("e7e5")), Err(MoveError::NotYourTurn));
+        assert_eq!(game.apply_move(White, mv("e2e5")), Err(MoveError::IllegalMove));
+        assert_eq!(game.apply_move(White, mv("f1c4")), Err(MoveError::IllegalMove));
+        assert_eq!(ChessState::decode_move(b"e2e9"), Err(MoveError::InvalidEncoding));
+        
+        assert_eq!(play_all(&mut game, &["e2e4", "e7e5", "g1f3"]), GameOutcome::Ongoing);
+        // The black pawn blocks the white one
+        assert_eq!(game.apply_move(Black, mv("e5e4")), Err(MoveError::IllegalMove));
+    }
+    
+    #[test]
+    fn pinned_piece_cannot_expose_the_king() {
+        let mut game = position(
+            &[("e1", King, White), ("e2", Bishop, White), ("e8", Rook, Black), ("a8", King, Black)],
+            White,
+        );
+        assert_eq!(game.apply_move(White, mv("e2d3")), Err(MoveError::IllegalMove));
+        assert_eq!(game.apply_move(White, mv("e1d1")), Ok(GameOutcome::Ongoing));
+    }
+    
+    #[test]
+    fn castling_through_check_is_illegal() {
+        let pieces = [
+            ("e1", King, White),
+            ("a1", Rook, White),
+            ("h1", Rook, White),
+            ("f8", Rook, Black),
+            ("h8", King, Black),
+        ];
+        let mut game = position(&pieces, White);
+        game.castling.white_kingside = true;
+        game.castling.white_queenside = true;
+        
+        // The black rook covers f1, which the king would pass through
+        assert_eq!(game.apply_move(White, mv("e1g1")), Err(MoveError::IllegalMove));
+        assert_eq!(game.apply_move(White, mv("e1c1")), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.piece_at(square("d1")), Some(Piece { kind: Rook, color: White }));
+        assert_eq!(game.piece_at(square("a1")), None);
+    }
+    
+    #[test]
+    fn cannot_castle_out_of_check() {
+        let pieces =
+            [("e1", King, White), ("h1", Rook, White), ("e8", Rook, Black), ("a8", King, Black)];
+        let mut game = position(&pieces, White);
+        game.castling.white_kingside = true;
+        assert_eq!(game.apply_move(White, mv("e1g1")), Err(MoveError::IllegalMove));
+    }
+    
+    #[test]
+    fn en_passant_only_right_after_the_double_step() {
+        let mut game = ChessState::default();
+        play_all(&mut game, &["e2e4", "a7a6", "e4e5", "d7d5"]);
+        assert_eq!(game.apply_move(White, mv("e5d6")), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.piece_at(square("d6")), Some(Piece { kind: Pawn, color: White }));
+        assert_eq!(game.piece_at(square("d5")), None);
+        
+        let mut game = ChessState::default();
+        play_all(&mut game, &["e2e4", "a7a6", "e4e5", "d7d5", "h2h3", "h7h6"]);
+        assert_eq!(game.apply_move(White, mv("e5d6")), Err(MoveError::IllegalMove));
+    }
+    
+    #[test]
+    fn promotion_must_name_a_piece() {
+        let pieces = [("a7", Pawn, White), ("e1", King, White), ("h8", King, Black)];
+        let mut game = position(&pieces, White);
+        assert_eq!(game.apply_move(White, mv("a7a8")), Err(MoveError::IllegalMove));
+        assert_eq!(game.apply_move(White, mv("a7a8n")), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.piece_at(square("a8")), Some(Piece { kind: Knight, color: White }));
+        
+        let mut game = position(&pieces, White);
+        game.apply_move(White, mv("a7a8q")).unwrap();
+        assert_eq!(game.piece_at(square("a8")), Some(Piece { kind: Queen, color: White }));
+        assert!(game.in_check(Black));
+    }
+    
+    #[test]
+    fn checkmate_ends_the_game() {
+        let mut game = ChessState::default();
+        let outcome = play_all(&mut game, &["f2f3", "e7e5", "g2g4", "d8h4"]);
+        assert_eq!(outcome, GameOutcome::Winner(Black.player_index()));
+        assert_eq!(game.apply_move(White, mv("e1f2")), Err(MoveError::GameOver));
+    }
+    
+    #[test]
+    fn stalemate_is_a_draw() {
+        let pieces = [("f7", King, White), ("g5", Queen, White), ("h8", King, Black)];
+        let mut game = position(&pieces, White);
+        assert_eq!(game.apply_move(White, mv("g5g6")), Ok(GameOutcome::Draw));
+        assert!(!game.in_check(Black));
+    }
+    
+    #[test]
+    fn threefold_repetition_is_a_draw() {
+        let mut game = ChessState::default();
+        let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+        assert_eq!(play_all(&mut game, &shuffle), GameOutcome::Ongoing);
+        assert_eq!(play_all(&mut game, &shuffle[..3]), GameOutcome::Ongoing);
+        assert_eq!(play_all(&mut game, &shuffle[3..]), GameOutcome::Draw);
+    }
+    
+    #[test]
+    fn fifty_quiet_moves_are_a_draw() {
+        let pieces = [("e1", King, White), ("a1", Rook, White), ("h8", King, Black)];
+        let mut game = position(&pieces, White);
+        game.halfmove_clock = FIFTY_MOVE_PLIES - 1;
+        assert_eq!(game.apply_move(White, mv("a1a2")), Ok(GameOutcome::Draw));
+    }
+}
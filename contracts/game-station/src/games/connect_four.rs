@@ -0,0 +1,99 @@
+//! Connect Four engine
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+use crate::PlayerMark;
+
+/// Columns on the board
+pub const COLUMNS: usize = 7;
+/// Rows on the board
+pub const ROWS: usize = 6;
+
+/// Directions a line of four can run in, as (row, col) steps
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Connect Four game state. Row 0 is the bottom of the board, where
+/// discs land first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectFourState {
+    pub board: [[Option<PlayerMark>; COLUMNS]; ROWS],
+    pub current_turn: PlayerMark,
+    pub winner: Option<PlayerMark>,
+    pub move_count: u8,
+}
+
+impl Default for ConnectFourState {
+    fn default() -> Self {
+        Self {
+            board: [[None; COLUMNS]; ROWS],
+            current_turn: PlayerMark::X,
+            winner: None,
+            move_count: 0,
+        }
+    }
+}
+
+impl ConnectFourState {
+    /// Decode move data as `[col]`
+    pub fn decode_move(move_data: &[u8]) -> Result<usize, MoveError> {
+        match move_data {
+            [col] => Ok(*col as usize),
+            _ => Err(MoveError::InvalidEncoding),
+        }
+    }
+    
+    /// Drop a `mark` disc into `col`; it falls to the lowest empty row
+    pub fn apply_move(&mut self, mark: PlayerMark, col: usize) -> Result<GameOutcome, MoveError> {
+        if self.winner.is_some() || self.move_count as usize >= ROWS * COLUMNS {
+            return Err(MoveError::GameOver);
+        }
+        if mark != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if col >= COLUMNS {
+            return Err(MoveError::OutOfBounds);
+        }
+        let Some(row) = (0..ROWS).find(|&row| self.board[row][col].is_none()) else {
+            return Err(MoveError::CellOccupied);
+        };
+        
+        self.board[row][col] = Some(mark);
+        self.move_count += 1;
+        
+        if self.completes_line(row, col, mark) {
+            self.winner = Some(mark);
+            return Ok(GameOutcome::Winner(mark.player_index()));
+        }
+        if self.move_count as usize == ROWS * COLUMNS {
+            return Ok(GameOutcome::Draw);
+        }
+        
+        self.current_turn = mark.opponent();
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    /// Whether the disc just placed at (`row`, `col`) is part of four in a row
+    fn completes_line(&self, row: usize, col: usize, mark: PlayerMark) -> bool {
+        DIRECTIONS.iter().any(|&(dr, dc)| {
+            let forward = self.run_length(row, col, dr, dc, mark);
+            let backward = self.run_length(row, col, -dr, -dc, mark);
+            forward + backward + 1 >= 4
+        })
+    }
+    
+    /// Number of `mark` discs in a row from (`row`, `col`), not counting itself
+    fn run_length(&self, row: usize, col: usize, dr: isize, dc: isize, mark: PlayerMark) -> usize {
+        let mut count = 0;
+        let (mut r, mut c) = (row as isize + dr, col as isize + dc);
+        while (0..ROWS as isize).contains(&r)
+            && (0..COLUMNS as isize).contains(&c)
+            && self.board[r as usize][c as usize] == Some(mark)
+        {
+            count += 1;
+            r += dr;
+            c += dc;
+        }
+        count
+    }
+}
@@ -0,0 +1,70 @@
+//! Daily word game
+//!
+//! Players get six tries at a five-letter word. The admin commits to each
+//! day's word as a salted hash when publishing it and reveals it once the
+//! day is over, so it can't be read from the chain while guesses are open.
+//! Any five letters make a valid guess, since a dictionary is too large to
+//! keep on-chain.
+
+use serde::{Deserialize, Serialize};
+
+use super::commitment;
+
+/// Letters in a word
+pub const WORD_LEN: usize = 5;
+/// Guesses a player gets each day
+pub const MAX_GUESSES: usize = 6;
+
+/// How a guessed letter compares with the answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LetterFeedback {
+    /// Right letter in the right place
+    Correct,
+    /// In the answer, but somewhere else
+    Present,
+    Absent,
+}
+
+impl LetterFeedback {
+    /// One-character form: `G` correct, `Y` present, `-` absent
+    pub fn symbol(self) -> char {
+        match self {
+            LetterFeedback::Correct => 'G',
+            LetterFeedback::Present => 'Y',
+            LetterFeedback::Absent => '-',
+        }
+    }
+}
+
+/// Whether `word` is five lowercase ASCII letters
+pub fn is_valid_word(word: &str) -> bool {
+    word.len() == WORD_LEN && word.bytes().all(|letter| letter.is_ascii_lowercase())
+}
+
+/// Commitment the admin publishes for a day's word
+pub fn word_commitment(word: &str, salt: &[u8]) -> [u8; 32] {
+    commitment(&[word.as_bytes(), salt])
+}
+
+/// Feedback on each letter of `guess`. A letter guessed more times than the
+/// answer holds it is only marked present as many times as it appears.
+pub fn feedback(guess: &str, answer: &str) -> [LetterFeedback; WORD_LEN] {
+    let (guess, answer) = (guess.as_bytes(), answer.as_bytes());
+    let mut result = [LetterFeedback::Absent; WORD_LEN];
+    let mut unmatched = [0u8; 26];
+    for (i, (&letter, &expected)) in guess.iter().zip(answer).enumerate() {
+        if letter == expected {
+            result[i] = LetterFeedback::Correct;
+        } else {
+            unmatched[(expected - b'a') as usize] += 1;
+        }
+    }
+    for (i, &letter) in guess.iter().enumerate() {
+        let count = &mut unmatched[(letter - b'a') as usize];
+        if result[i] != LetterFeedback::Correct && *count > 0 {
+            result[i] = LetterFeedback::Present;
+            *count -= 1;
+        }
+    }
+    result
+}
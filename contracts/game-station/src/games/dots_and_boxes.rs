@@ -0,0 +1,169 @@
+//! Dots and Boxes engine
+//!
+//! Players take turns drawing a line between two neighbouring dots. A
+//! player who completes the fourth side of a box claims it and must draw
+//! again. Once every line is drawn, whoever claimed the most boxes wins.
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+
+/// Boxes along each side of the grid when the room doesn't choose
+pub const DEFAULT_SIZE: u8 = 4;
+/// Smallest grid a room can choose, in boxes along each side
+pub const MIN_SIZE: u8 = 2;
+/// Largest grid a room can choose, in boxes along each side
+pub const MAX_SIZE: u8 = 8;
+
+/// Fewest players a room can seat
+pub const MIN_PLAYERS: u8 = 2;
+/// Most players a room can seat
+pub const MAX_PLAYERS: u8 = 4;
+
+/// A line between two neighbouring dots. A horizontal line runs right from
+/// dot (`row`, `col`); a vertical one runs down from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Line {
+    pub vertical: bool,
+    pub row: u8,
+    pub col: u8,
+}
+
+/// Dots and Boxes game state for a square grid of `size` by `size` boxes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DotsAndBoxesState {
+    pub size: u8,
+    pub player_count: usize,
+    /// Drawn horizontal lines, by `[row * size + col]` for rows `0..=size`
+    pub horizontal: Vec<bool>,
+    /// Drawn vertical lines, by `[row * (size + 1) + col]` for cols `0..=size`
+    pub vertical: Vec<bool>,
+    /// Player who claimed each box, by `[row * size + col]`
+    pub boxes: Vec<Option<usize>>,
+    pub current_turn: usize,
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl DotsAndBoxesState {
+    /// An empty grid for `player_count` players
+    pub fn new(size: u8, player_count: usize) -> Self {
+        let n = size as usize;
+        Self {
+            size,
+            player_count,
+            horizontal: vec![false; (n + 1) * n],
+            vertical: vec![false; n * (n + 1)],
+            boxes: vec![None; n * n],
+            current_turn: 0,
+            move_count: 0,
+            result: None,
+        }
+    }
+    
+    /// Decode move data as `[orientation, row, col]`, with orientation 0
+    /// for a horizontal line and 1 for a vertical one
+    pub fn decode_move(move_data: &[u8]) -> Result<Line, MoveError> {
+        match move_data {
+            [orientation @ (0 | 1), row, col] => Ok(Line {
+                vertical: *orientation == 1,
+                row: *row,
+                col: *col,
+            }),
+            _ => Err(MoveError::InvalidEncoding),
+        }
+    }
+    
+    /// Boxes each player has claimed, by player index
+    pub fn box_counts(&self) -> Vec<u32> {
+        let mut counts = vec![0; self.player_count];
+        for &owner in self.boxes.iter().flatten() {
+            counts[owner] += 1;
+        }
+        counts
+    }
+    
+    /// Draw `line` for `player`, claiming any boxes it completes
+    pub fn apply_move(&mut self, player: usize, line: Line) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        let Some(index) = self.line_index(line) else {
+            return Err(MoveError::OutOfBounds);
+        };
+        let drawn = if line.vertical {
+            &mut self.vertical[index]
+        } else {
+            &mut self.horizontal[index]
+        };
+        if *drawn {
+            return Err(MoveError::CellOccupied);
+        }
+        *drawn = true;
+        self.move_count += 1;
+        
+        let mut claimed = false;
+        for (row, col) in self.boxes_beside(line) {
+            if self.is_complete(row, col) {
+                self.boxes[row * self.size as usize + col] = Some(player);
+                claimed = true;
+            }
+        }
+        
+        if self.boxes.iter().all(Option::is_some) {
+            let counts = self.box_counts();
+            let best = counts.iter().copied().max().unwrap_or(0);
+            let leaders: Vec<usize> = (0..counts.len()).filter(|&p| counts[p] == best).collect();
+            let outcome = match leaders[..] {
+                [winner] => GameOutcome::Winner(winner),
+                _ => GameOutcome::Draw,
+            };
+            self.result = Some(outcome);
+            return Ok(outcome);
+        }
+        if !claimed {
+            self.current_turn = (player + 1) % self.player_count;
+        }
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    /// Index of `line` in its orientation's list, if it is on the grid
+    fn line_index(&self, line: Line) -> Option<usize> {
+        let (row, col, n) = (line.row as usize, line.col as usize, self.size as usize);
+        if line.vertical {
+            (row < n && col <= n).then_some(row * (n + 1) + col)
+        } else {
+            (row <= n && col < n).then_some(row * n + col)
+        }
+    }
+    
+    /// Boxes on either side of `line` that are on the grid
+    fn boxes_beside(&self, line: Line) -> Vec<(usize, usize)> {
+        let (row, col, n) = (line.row as usize, line.col as usize, self.size as usize);
+        let sides = if line.vertical {
+            [(Some(row), col.checked_sub(1)), (Some(row), Some(col))]
+        } else {
+            [(row.checked_sub(1), Some(col)), (Some(row), Some(col))]
+        };
+        sides
+            .into_iter()
+            .filter_map(|side| match side {
+                (Some(row), Some(col)) if row < n && col < n => Some((row, col)),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    /// Whether all four sides of box (`row`, `col`) are drawn
+    fn is_complete(&self, row: usize, col: usize) -> bool {
+        let n = self.size as usize;
+        self.horizontal[row * n + col]
+            && self.horizontal[(row + 1) * n + col]
+            && self.vertical[row * (n + 1) + col]
+            && self.vertical[row * (n + 1) + col + 1]
+    }
+}
@@ -0,0 +1,196 @@
+//! Gomoku (five-in-a-row) engine
+//!
+//! Black and white take turns placing stones, black first, and the first
+//! to get exactly five in a row wins; a line of six or more doesn't count.
+//!
+//! Rooms may open with swap2 to offset black's first-move advantage. The
+//! first player places three stones, two black and one white. The second
+//! player then either picks a colour, or places a fourth and fifth stone
+//! and leaves the first player to pick. Whoever ends up white moves next.
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+
+/// Board sizes a room can choose, in intersections along each side
+pub const SIZES: [u8; 2] = [15, 19];
+/// Board size when the room doesn't choose
+pub const DEFAULT_SIZE: u8 = 15;
+
+/// Directions a line of five can run in, as (row, col) steps
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stone {
+    Black,
+    White,
+}
+
+/// Stage of the swap2 opening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Opening {
+    /// The first player is placing the three opening stones
+    Placing,
+    /// The second player picks a colour or places two more stones
+    SecondPlayerChoice,
+    /// The second player is placing the fourth and fifth stones
+    Extending,
+    /// The first player picks a colour
+    FirstPlayerChoice,
+    /// Colours are settled and play is normal
+    Done,
+}
+
+/// Moves a player can make in a Gomoku room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GomokuMove {
+    /// Place a stone. Its colour is whichever side is due a stone, which
+    /// during the opening may not be the placing player's own.
+    Place { row: u8, col: u8 },
+    /// Settle the swap2 opening by picking a colour
+    ChooseColor { black: bool },
+}
+
+/// Gomoku game state. Without swap2 the first player plays black.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GomokuState {
+    pub size: u8,
+    /// Stones indexed by `[row][col]`
+    pub board: Vec<Vec<Option<Stone>>>,
+    /// Index of the player playing black
+    pub black_player: usize,
+    pub opening: Opening,
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl GomokuState {
+    /// An empty `size` by `size` board, opening with swap2 if `swap2` is set
+    pub fn new(size: u8, swap2: bool) -> Self {
+        Self {
+            size,
+            board: vec![vec![None; size as usize]; size as usize],
+            black_player: 0,
+            opening: if swap2 { Opening::Placing } else { Opening::Done },
+            move_count: 0,
+            result: None,
+        }
+    }
+    
+    /// Decode BCS-encoded move data
+    pub fn decode_move(move_data: &[u8]) -> Result<GomokuMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Colour of the next stone placed: black whenever both sides have as
+    /// many stones on the board, white otherwise
+    pub fn next_stone(&self) -> Stone {
+        let stones = self.board.iter().flatten().flatten();
+        let black = stones.clone().filter(|&&stone| stone == Stone::Black).count();
+        let white = stones.count() - black;
+        if black == white {
+            Stone::Black
+        } else {
+            Stone::White
+        }
+    }
+    
+    /// Player due to move
+    pub fn current_turn(&self) -> usize {
+        match self.opening {
+            Opening::Placing | Opening::FirstPlayerChoice => 0,
+            Opening::SecondPlayerChoice | Opening::Extending => 1,
+            Opening::Done => match self.next_stone() {
+                Stone::Black => self.black_player,
+                Stone::White => 1 - self.black_player,
+            },
+        }
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(&mut self, player: usize, mv: GomokuMove) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player != self.current_turn() {
+            return Err(MoveError::NotYourTurn);
+        }
+        
+        match mv {
+            GomokuMove::ChooseColor { black } => {
+                self.black_player = match self.opening {
+                    Opening::SecondPlayerChoice if black => 1,
+                    Opening::SecondPlayerChoice => 0,
+                    Opening::FirstPlayerChoice if black => 0,
+                    Opening::FirstPlayerChoice => 1,
+                    _ => return Err(MoveError::IllegalMove),
+                };
+                self.opening = Opening::Done;
+                self.move_count += 1;
+                Ok(GameOutcome::Ongoing)
+            }
+            GomokuMove::Place { row, col } => {
+                if self.opening == Opening::FirstPlayerChoice {
+                    return Err(MoveError::IllegalMove);
+                }
+                let (row, col) = (row as usize, col as usize);
+                if row >= self.size as usize || col >= self.size as usize {
+                    return Err(MoveError::OutOfBounds);
+                }
+                if self.board[row][col].is_some() {
+                    return Err(MoveError::CellOccupied);
+                }
+                
+                let stone = self.next_stone();
+                self.board[row][col] = Some(stone);
+                self.move_count += 1;
+                
+                let stones = self.board.iter().flatten().flatten().count();
+                self.opening = match (self.opening, stones) {
+                    (Opening::Placing, 3) => Opening::SecondPlayerChoice,
+                    (Opening::SecondPlayerChoice, _) => Opening::Extending,
+                    (Opening::Extending, 5) => Opening::FirstPlayerChoice,
+                    (opening, _) => opening,
+                };
+                
+                if self.is_five(row, col, stone) {
+                    let winner = match stone {
+                        Stone::Black => self.black_player,
+                        Stone::White => 1 - self.black_player,
+                    };
+                    self.result = Some(GameOutcome::Winner(winner));
+                } else if stones == (self.size as usize).pow(2) {
+                    self.result = Some(GameOutcome::Draw);
+                }
+                Ok(self.result.unwrap_or(GameOutcome::Ongoing))
+            }
+        }
+    }
+    
+    /// Whether the stone just placed at (`row`, `col`) is part of a line of
+    /// exactly five
+    fn is_five(&self, row: usize, col: usize, stone: Stone) -> bool {
+        DIRECTIONS.iter().any(|&(dr, dc)| {
+            let forward = self.run_length(row, col, dr, dc, stone);
+            let backward = self.run_length(row, col, -dr, -dc, stone);
+            forward + backward + 1 == 5
+        })
+    }
+    
+    /// Number of `stone` stones in a row from (`row`, `col`), not counting itself
+    fn run_length(&self, row: usize, col: usize, dr: isize, dc: isize, stone: Stone) -> usize {
+        let size = self.size as isize;
+        let mut count = 0;
+        let (mut r, mut c) = (row as isize + dr, col as isize + dc);
+        while (0..size).contains(&r)
+            && (0..size).contains(&c)
+            && self.board[r as usize][c as usize] == Some(stone)
+        {
+            count += 1;
+            r += dr;
+            c += dc;
+        }
+        count
+    }
+}
@@ -0,0 +1,210 @@
+//! Ludo engine
+//!
+//! Each player races four tokens once around a shared 52-square track and
+//! up their own home column. A six brings a token out of the yard, landing
+//! on an opponent's token sends it back to its yard unless the square is
+//! safe, and a six or a capture earns another roll. The first player with
+//! every token home wins.
+//!
+//! The dice are seeded by a commit-reveal round among all the players, as
+//! in Snake & Ladders, so anyone can check every roll.
+
+use serde::{Deserialize, Serialize};
+
+use super::{randomness::CommitReveal, snake_ladders::roll_die, GameOutcome, MoveError};
+
+/// Fewest players a room can seat
+pub const MIN_PLAYERS: u8 = 2;
+/// Most players a room can seat
+pub const MAX_PLAYERS: u8 = 4;
+
+/// Tokens each player races
+pub const TOKENS: usize = 4;
+/// Squares around the shared track
+pub const TRACK_SQUARES: u8 = 52;
+/// Progress of a token on its home column's first square
+pub const HOME_COLUMN: u8 = TRACK_SQUARES;
+/// Progress of a token that has reached home
+pub const HOME: u8 = HOME_COLUMN + 5;
+
+/// Squares between neighbouring players' start squares
+const SEAT_SPACING: u8 = TRACK_SQUARES / MAX_PLAYERS;
+/// Safe squares relative to each start square: the start square itself and
+/// the star square eight ahead of it
+const SAFE_OFFSETS: [u8; 2] = [0, 8];
+
+/// Moves a player can make in a Ludo room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LudoMove {
+    /// Commit to a dice salt before the game starts
+    CommitSalt { commitment: [u8; 32] },
+    /// Reveal the committed salt
+    RevealSalt { salt: Vec<u8> },
+    /// Roll the die on your turn
+    Roll,
+    /// Move one of your tokens by the roll you just made
+    MoveToken { token: u8 },
+}
+
+/// Ludo game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LudoState {
+    /// Progress of each player's tokens: 0 in the yard, 1 to 51 around the
+    /// track from the player's start square, then up the home column to `HOME`
+    pub tokens: Vec<[u8; TOKENS]>,
+    /// Index of the player whose turn it is
+    pub current_turn: usize,
+    /// Roll the current player has made but not yet moved by
+    pub pending_roll: Option<u8>,
+    pub last_roll: Option<u8>,
+    /// Dice rolled so far, which picks each roll from the dice seed
+    pub rolls: u32,
+    pub winner: Option<usize>,
+    pub move_count: u32,
+    /// Salts from every player seed the dice, so no one can load them
+    pub dice: CommitReveal,
+}
+
+impl LudoState {
+    /// New game for `players` players with every token in its yard
+    pub fn new(players: usize) -> Self {
+        Self {
+            tokens: vec![[0; TOKENS]; players],
+            current_turn: 0,
+            pending_roll: None,
+            last_roll: None,
+            rolls: 0,
+            winner: None,
+            move_count: 0,
+            dice: CommitReveal::new(players),
+        }
+    }
+    
+    /// Whether the dice are seeded and turns are being taken
+    pub fn is_started(&self) -> bool {
+        self.dice.seed().is_some()
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<LudoMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(&mut self, player: usize, mv: LudoMove) -> Result<GameOutcome, MoveError> {
+        match mv {
+            LudoMove::CommitSalt { commitment } => {
+                self.dice.commit(player, commitment)?;
+                Ok(GameOutcome::Ongoing)
+            }
+            LudoMove::RevealSalt { salt } => {
+                self.dice.reveal(player, salt)?;
+                Ok(GameOutcome::Ongoing)
+            }
+            LudoMove::Roll => self.roll(player),
+            LudoMove::MoveToken { token } => self.move_token(player, token as usize),
+        }
+    }
+    
+    /// Roll the die for `player`. If none of their tokens can move by the
+    /// roll, the turn passes straight on.
+    fn roll(&mut self, player: usize) -> Result<GameOutcome, MoveError> {
+        let seed = self.turn_seed(player)?;
+        if self.pending_roll.is_some() {
+            return Err(MoveError::IllegalMove);
+        }
+        let roll = roll_die(seed.wrapping_add(u64::from(self.rolls)));
+        self.rolls += 1;
+        self.move_count += 1;
+        self.last_roll = Some(roll);
+        
+        if (0..TOKENS).any(|token| self.target(player, token, roll).is_some()) {
+            self.pending_roll = Some(roll);
+        } else {
+            self.end_turn(false);
+        }
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    /// Move `player`'s `token` by the roll they just made
+    fn move_token(&mut self, player: usize, token: usize) -> Result<GameOutcome, MoveError> {
+        self.turn_seed(player)?;
+        let roll = self.pending_roll.ok_or(MoveError::IllegalMove)?;
+        if token >= TOKENS {
+            return Err(MoveError::OutOfBounds);
+        }
+        let target = self.target(player, token, roll).ok_or(MoveError::IllegalMove)?;
+        self.tokens[player][token] = target;
+        self.pending_roll = None;
+        self.move_count += 1;
+        
+        let captured = self.capture(player, target);
+        if self.tokens[player].iter().all(|&progress| progress == HOME) {
+            self.winner = Some(player);
+            return Ok(GameOutcome::Winner(player));
+        }
+        self.end_turn(roll == 6 || captured);
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    /// Check that the game is under way and it is `player`'s turn, and
+    /// return the dice seed
+    fn turn_seed(&self, player: usize) -> Result<u64, MoveError> {
+        if self.winner.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        let seed = self.dice.seed().ok_or(MoveError::IllegalMove)?;
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        Ok(seed)
+    }
+    
+    /// Where `player`'s `token` would end up after `roll`, if it can move.
+    /// Leaving the yard takes a six, and home must be reached exactly.
+    fn target(&self, player: usize, token: usize, roll: u8) -> Option<u8> {
+        match self.tokens[player][token] {
+            0 => (roll == 6).then_some(1),
+            HOME => None,
+            progress => Some(progress + roll).filter(|&target| target <= HOME),
+        }
+    }
+    
+    /// Send opponents' tokens on the track square `player` just moved to
+    /// back to their yards. Returns whether any were captured.
+    fn capture(&mut self, player: usize, progress: u8) -> bool {
+        let Some(square) = self.square(player, progress) else {
+            return false;
+        };
+        if SAFE_OFFSETS.iter().any(|offset| square % SEAT_SPACING == *offset) {
+            return false;
+        }
+        let mut captured = false;
+        for opponent in (0..self.tokens.len()).filter(|&p| p != player) {
+            for token in 0..TOKENS {
+                if self.square(opponent, self.tokens[opponent][token]) == Some(square) {
+                    self.tokens[opponent][token] = 0;
+                    captured = true;
+                }
+            }
+        }
+        captured
+    }
+    
+    /// Track square of a `player` token at `progress`, if it is on the
+    /// shared track rather than in the yard or the home column
+    fn square(&self, player: usize, progress: u8) -> Option<u8> {
+        if !(1..HOME_COLUMN).contains(&progress) {
+            return None;
+        }
+        // Two players sit opposite each other
+        let seat = if self.tokens.len() == 2 { player * 2 } else { player } as u8;
+        Some((seat * SEAT_SPACING + progress - 1) % TRACK_SQUARES)
+    }
+    
+    /// Pass the turn on, unless the current player has earned another roll
+    fn end_turn(&mut self, roll_again: bool) {
+        if !roll_again {
+            self.current_turn = (self.current_turn + 1) % self.tokens.len();
+        }
+    }
+}
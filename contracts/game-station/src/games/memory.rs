@@ -0,0 +1,158 @@
+//! Memory (Concentration) engine
+//!
+//! Cards lie face down in pairs. On their turn a player flips two; a
+//! matching pair is theirs to keep and they flip again, otherwise the turn
+//! passes. Once every pair is collected, whoever holds the most wins.
+//!
+//! The deck is shuffled from a seed every player contributes a salt to,
+//! committed before any is revealed, so nobody can stack it.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    randomness::{CommitReveal, SeededRng},
+    GameOutcome, MoveError,
+};
+
+/// Pairs in the deck
+pub const PAIRS: usize = 8;
+
+/// Fewest players a room can seat
+pub const MIN_PLAYERS: u8 = 2;
+/// Most players a room can seat
+pub const MAX_PLAYERS: u8 = 4;
+
+/// Moves a player can make in a Memory room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MemoryMove {
+    /// Commit to a shuffle salt before the game starts
+    CommitSalt { commitment: [u8; 32] },
+    /// Reveal the committed salt
+    RevealSalt { salt: Vec<u8> },
+    /// Flip the card at this position on your turn
+    Flip { card: u8 },
+}
+
+/// Memory game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryState {
+    /// Face of the card at each position, dealt once every salt is revealed
+    pub cards: Vec<u8>,
+    /// Player who collected the card at each position
+    pub collected_by: Vec<Option<usize>>,
+    /// Card the current player has flipped and not yet paired off
+    pub face_up: Option<usize>,
+    /// The last two cards flipped, left showing until the next flip
+    pub last_pair: Option<(usize, usize)>,
+    /// Pairs each player has collected
+    pub pairs: Vec<u32>,
+    /// Index of the player whose turn it is
+    pub current_turn: usize,
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+    /// Salts from every player seed the shuffle
+    pub shuffle: CommitReveal,
+}
+
+impl MemoryState {
+    /// New game for `players` players, waiting on their shuffle salts
+    pub fn new(players: usize) -> Self {
+        Self {
+            cards: Vec::new(),
+            collected_by: vec![None; PAIRS * 2],
+            face_up: None,
+            last_pair: None,
+            pairs: vec![0; players],
+            current_turn: 0,
+            move_count: 0,
+            result: None,
+            shuffle: CommitReveal::new(players),
+        }
+    }
+    
+    /// Whether the deck is dealt and turns are being taken
+    pub fn is_started(&self) -> bool {
+        !self.cards.is_empty()
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<MemoryMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(&mut self, player: usize, mv: MemoryMove) -> Result<GameOutcome, MoveError> {
+        match mv {
+            MemoryMove::CommitSalt { commitment } => {
+                self.shuffle.commit(player, commitment)?;
+                Ok(GameOutcome::Ongoing)
+            }
+            MemoryMove::RevealSalt { salt } => {
+                self.shuffle.reveal(player, salt)?;
+                if let Some(seed) = self.shuffle.seed() {
+                    self.cards = deal(seed);
+                }
+                Ok(GameOutcome::Ongoing)
+            }
+            MemoryMove::Flip { card } => self.flip(player, card as usize),
+        }
+    }
+    
+    fn flip(&mut self, player: usize, card: usize) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if !self.is_started() {
+            return Err(MoveError::IllegalMove);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if card >= self.cards.len() {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.collected_by[card].is_some() || self.face_up == Some(card) {
+            return Err(MoveError::CellOccupied);
+        }
+        self.move_count += 1;
+        
+        let Some(first) = self.face_up.take() else {
+            self.face_up = Some(card);
+            self.last_pair = None;
+            return Ok(GameOutcome::Ongoing);
+        };
+        self.last_pair = Some((first, card));
+        if self.cards[first] != self.cards[card] {
+            self.current_turn = (player + 1) % self.pairs.len();
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        // A match: the player keeps the pair and flips again
+        self.collected_by[first] = Some(player);
+        self.collected_by[card] = Some(player);
+        self.pairs[player] += 1;
+        if self.collected_by.iter().any(Option::is_none) {
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        let best = self.pairs.iter().copied().max().unwrap_or(0);
+        let leaders: Vec<usize> = (0..self.pairs.len()).filter(|&p| self.pairs[p] == best).collect();
+        let outcome = match leaders[..] {
+            [winner] => GameOutcome::Winner(winner),
+            _ => GameOutcome::Draw,
+        };
+        self.result = Some(outcome);
+        Ok(outcome)
+    }
+}
+
+/// Shuffle the deck from a seed: two cards of each face, Fisher-Yates
+pub fn deal(seed: u64) -> Vec<u8> {
+    let mut cards: Vec<u8> = (0..PAIRS as u8).flat_map(|face| [face, face]).collect();
+    let mut rng = SeededRng::new(seed);
+    for i in (1..cards.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        cards.swap(i, j);
+    }
+    cards
+}
@@ -0,0 +1,145 @@
+//! Minesweeper engine
+//!
+//! Boards come from a dealer, the admin account, so nobody playing knows
+//! where the mines are. The dealer commits to board seeds ahead of time,
+//! and each new session takes the next commitment. After the player has
+//! submitted their clicks the dealer reveals the seed, and the contract
+//! checks it against the commitment and replays the clicks on the board.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, randomness::SeededRng, MoveError};
+
+/// Rows on the board
+pub const ROWS: usize = 9;
+/// Columns on the board
+pub const COLUMNS: usize = 9;
+/// Mines hidden on the board
+pub const MINES: usize = 10;
+
+/// Points for each safe cell uncovered
+pub const POINTS_PER_CELL: u32 = 10;
+/// Bonus for clearing the board, less one point per second taken
+pub const WIN_BONUS: u32 = 1_000;
+
+/// Longest click sequence that will be replayed
+pub const MAX_CLICKS: usize = ROWS * COLUMNS;
+
+/// A click uncovering a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinesweeperClick {
+    pub row: u8,
+    pub col: u8,
+    /// Milliseconds since the session started
+    pub at_ms: u64,
+}
+
+/// Outcome of a replayed session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinesweeperResult {
+    pub won: bool,
+    /// Safe cells uncovered
+    pub cleared: u32,
+    /// Milliseconds from the session start to the last click
+    pub time_ms: u64,
+    pub score: u32,
+}
+
+/// Mine layout derived from a revealed board seed
+pub fn mines(seed: &[u8]) -> [[bool; COLUMNS]; ROWS] {
+    let hash = commitment(&[b"minesweeper", seed]);
+    let seed = u64::from_le_bytes(hash[..8].try_into().expect("hash has at least 8 bytes"));
+    let mut rng = SeededRng::new(seed);
+    let mut mines = [[false; COLUMNS]; ROWS];
+    let mut placed = 0;
+    while placed < MINES {
+        let cell = rng.below((ROWS * COLUMNS) as u64) as usize;
+        let (row, col) = (cell / COLUMNS, cell % COLUMNS);
+        if !mines[row][col] {
+            mines[row][col] = true;
+            placed += 1;
+        }
+    }
+    mines
+}
+
+/// Check that clicks are on the board, in time order, and no later than
+/// `elapsed_ms` after the session started
+pub fn validate_clicks(clicks: &[MinesweeperClick], elapsed_ms: u64) -> Result<(), MoveError> {
+    if clicks.is_empty() || clicks.len() > MAX_CLICKS {
+        return Err(MoveError::InvalidEncoding);
+    }
+    if clicks.iter().any(|click| click.row as usize >= ROWS || click.col as usize >= COLUMNS) {
+        return Err(MoveError::OutOfBounds);
+    }
+    let in_order = clicks.windows(2).all(|pair| pair[0].at_ms <= pair[1].at_ms);
+    if !in_order || clicks.iter().any(|click| click.at_ms > elapsed_ms) {
+        return Err(MoveError::IllegalMove);
+    }
+    Ok(())
+}
+
+/// Replay clicks on a board. Uncovering a cell with no neighbouring mines
+/// uncovers its neighbours too, like the web client; clicking a cell that
+/// is already uncovered does nothing. The game ends at the first mine or
+/// once every safe cell is uncovered, and any clicks after that are rejected.
+pub fn replay(
+    mines: &[[bool; COLUMNS]; ROWS],
+    clicks: &[MinesweeperClick],
+) -> Result<MinesweeperResult, MoveError> {
+    let safe_cells = (ROWS * COLUMNS - MINES) as u32;
+    let mut uncovered = [[false; COLUMNS]; ROWS];
+    let mut cleared = 0;
+    let mut exploded = false;
+    let mut time_ms = 0;
+    
+    for click in clicks {
+        if exploded || cleared == safe_cells {
+            return Err(MoveError::GameOver);
+        }
+        time_ms = click.at_ms;
+        let (row, col) = (click.row as usize, click.col as usize);
+        if mines[row][col] {
+            exploded = true;
+            continue;
+        }
+        
+        let mut pending = vec![(row, col)];
+        while let Some((row, col)) = pending.pop() {
+            if uncovered[row][col] {
+                continue;
+            }
+            uncovered[row][col] = true;
+            cleared += 1;
+            let neighbours = neighbours(row, col);
+            if neighbours.iter().all(|&(r, c)| !mines[r][c]) {
+                pending.extend(neighbours);
+            }
+        }
+    }
+    
+    let won = cleared == safe_cells;
+    let bonus = if won {
+        WIN_BONUS.saturating_sub((time_ms / 1_000) as u32)
+    } else {
+        0
+    };
+    Ok(MinesweeperResult {
+        won,
+        cleared,
+        time_ms,
+        score: cleared * POINTS_PER_CELL + bonus,
+    })
+}
+
+fn neighbours(row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::with_capacity(8);
+    for r in row.saturating_sub(1)..=(row + 1).min(ROWS - 1) {
+        for c in col.saturating_sub(1)..=(col + 1).min(COLUMNS - 1) {
+            if (r, c) != (row, col) {
+                cells.push((r, c));
+            }
+        }
+    }
+    cells
+}
@@ -0,0 +1,81 @@
+//! Linera Game Station - Game Engines
+//! 
+//! Move validation and win detection for the multiplayer games. Engines are
+//! pure functions over the game state types so both the contract and any
+//! off-chain tooling can run them.
+
+pub mod battleship;
+pub mod blackjack;
+pub mod checkers;
+pub mod chess;
+pub mod connect_four;
+pub mod daily_word;
+pub mod dots_and_boxes;
+pub mod gomoku;
+pub mod ludo;
+pub mod memory;
+pub mod minesweeper;
+pub mod poker;
+pub mod randomness;
+pub mod reversi;
+pub mod rock_paper_scissors;
+pub mod snake;
+pub mod snake_ladders;
+pub mod sudoku;
+pub mod tetris;
+pub mod tictactoe;
+pub mod trivia;
+pub mod twenty_forty_eight;
+pub mod uno;
+pub mod yahtzee;
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
+
+/// Result of applying a valid move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    /// The game continues
+    Ongoing,
+    /// The player at this index in the room's player list won
+    Winner(usize),
+    /// The game ended without a winner
+    Draw,
+}
+
+/// Reasons a move can be rejected
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MoveError {
+    #[error("move data could not be decoded")]
+    InvalidEncoding,
+    #[error("it is not this player's turn")]
+    NotYourTurn,
+    #[error("move is outside the board")]
+    OutOfBounds,
+    #[error("cell is already occupied")]
+    CellOccupied,
+    #[error("game is already over")]
+    GameOver,
+    #[error("move is not allowed in the current game state")]
+    IllegalMove,
+    #[error("revealed value does not match the commitment")]
+    CommitmentMismatch,
+}
+
+/// Mix a seed into a well-distributed pseudo-random value (SplitMix64)
+pub fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// SHA3-256 commitment over the concatenation of `parts`
+pub fn commitment(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
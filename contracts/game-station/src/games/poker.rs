@@ -0,0 +1,676 @@
+//! Heads-up poker engine
+//!
+//! Two players play no-limit Texas hold'em hands until one of them holds
+//! every chip. No dealer is trusted with the deck; it is shuffled with
+//! mental poker. Each player encrypts every card under a secret key with a
+//! commutative cipher (SRA: raising to a secret power modulo a prime) and
+//! reorders the deck, so neither knows where any card lies. A card is shown
+//! by stripping both keys off it: the opponent strips theirs from a
+//! player's hole cards and the player finishes them privately, while both
+//! strip theirs from the community cards in public.
+//!
+//! A hand that reaches showdown ends with both keys revealed. Every step of
+//! the deal is then checked against them before the pot is paid, and a
+//! player caught cheating loses the game.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+
+/// Chips each player starts with
+pub const STARTING_STACK: u32 = 100;
+/// Blind posted by the dealer
+pub const SMALL_BLIND: u32 = 1;
+/// Blind posted by the other player
+pub const BIG_BLIND: u32 = 2;
+
+/// Modulus of the card cipher, a safe prime: `(PRIME - 1) / 2` is prime too
+pub const PRIME: u64 = 9_223_372_036_854_771_239;
+/// Cards in the deck
+pub const DECK_SIZE: usize = 52;
+
+/// Deck position of the first community card, after both players' hole
+/// cards: player 0 is dealt positions 0 and 1, player 1 positions 2 and 3
+pub const BOARD_START: usize = 4;
+/// Community cards in a full board
+pub const BOARD_CARDS: usize = 5;
+
+/// Moves a player can make in a Poker room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PokerMove {
+    /// Encrypt every card of the deck under your key and submit them in a
+    /// new order. The dealer shuffles `encode_card` of each card, then the
+    /// other player shuffles the dealer's deck.
+    Shuffle { deck: Vec<u64> },
+    /// Strip your key from cards: the opponent's two hole cards, or the
+    /// community cards being dealt. The dealer strips theirs from the deck
+    /// first, then the other player from the dealer's results.
+    Unlock { values: Vec<u64> },
+    Fold,
+    /// Check, or call the opponent's bet
+    Call,
+    /// Bet or raise so your chips in this betting round total `to`
+    Raise { to: u32 },
+    /// Reveal your key at showdown so the deal can be checked
+    RevealKey { key: u64 },
+}
+
+/// Stage of the current hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PokerPhase {
+    /// The dealer, then the other player, encrypts and reorders the deck
+    Shuffling,
+    /// Each player unlocks the opponent's hole cards
+    DealingHoleCards,
+    Betting,
+    /// Both players unlock the next community cards
+    DealingBoard,
+    /// Both players reveal their keys
+    Showdown,
+}
+
+/// Poker game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PokerState {
+    pub stacks: [u32; 2],
+    /// Player on the button, who posts the small blind and shuffles first
+    pub dealer: usize,
+    /// Hands dealt so far, counting the current one
+    pub hand: u32,
+    pub phase: PokerPhase,
+    /// The deck after the dealer's shuffle, then after the other player's
+    pub shuffles: Vec<Vec<u64>>,
+    /// Each player's unlocking of the opponent's hole cards
+    pub hole_unlocks: [Option<Vec<u64>>; 2],
+    /// Each player's unlocking of the community cards dealt so far
+    pub board_unlocks: [Vec<u64>; 2],
+    /// Community cards dealt so far
+    pub board: Vec<u8>,
+    /// Chips each player has put into this hand's pot
+    pub contributed: [u32; 2],
+    /// Chips each player has put in during this betting round
+    pub committed: [u32; 2],
+    /// Player due to act in the betting round
+    pub to_act: usize,
+    /// Whether each player has acted since the last bet or raise
+    pub acted: [bool; 2],
+    /// Smallest raise allowed, the size of the last bet or raise
+    pub min_raise: u32,
+    /// Keys revealed at showdown
+    pub keys: [Option<u64>; 2],
+    /// Both players' hole cards in the last hand that reached showdown
+    pub last_showdown: Option<[[u8; 2]; 2]>,
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl Default for PokerState {
+    fn default() -> Self {
+        let mut state = Self {
+            stacks: [STARTING_STACK; 2],
+            dealer: 0,
+            hand: 0,
+            phase: PokerPhase::Shuffling,
+            shuffles: Vec::new(),
+            hole_unlocks: [None, None],
+            board_unlocks: [Vec::new(), Vec::new()],
+            board: Vec::new(),
+            contributed: [0; 2],
+            committed: [0; 2],
+            to_act: 0,
+            acted: [false; 2],
+            min_raise: BIG_BLIND,
+            keys: [None; 2],
+            last_showdown: None,
+            move_count: 0,
+            result: None,
+        };
+        state.start_hand();
+        state
+    }
+}
+
+impl PokerState {
+    pub fn decode_move(move_data: &[u8]) -> Result<PokerMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Player the hand is waiting on
+    pub fn waiting_on(&self) -> Option<usize> {
+        if self.result.is_some() {
+            return None;
+        }
+        let dealer = self.dealer;
+        match self.phase {
+            PokerPhase::Shuffling => {
+                Some(if self.shuffles.is_empty() { dealer } else { 1 - dealer })
+            }
+            PokerPhase::DealingHoleCards => self.hole_unlocks.iter().position(Option::is_none),
+            PokerPhase::Betting => Some(self.to_act),
+            PokerPhase::DealingBoard => {
+                let dealer_done = self.board_unlocks[dealer].len() > self.board.len();
+                Some(if dealer_done { 1 - dealer } else { dealer })
+            }
+            PokerPhase::Showdown => self.keys.iter().position(Option::is_none),
+        }
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(&mut self, player: usize, mv: PokerMove) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player > 1 {
+            return Err(MoveError::NotYourTurn);
+        }
+        let in_turn = self.waiting_on() == Some(player);
+        
+        match (self.phase, mv) {
+            (PokerPhase::Shuffling, PokerMove::Shuffle { deck }) => {
+                if !in_turn {
+                    return Err(MoveError::NotYourTurn);
+                }
+                if !is_valid_deck(&deck) {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.shuffles.push(deck);
+                if self.shuffles.len() == 2 {
+                    self.phase = PokerPhase::DealingHoleCards;
+                }
+            }
+            (PokerPhase::DealingHoleCards, PokerMove::Unlock { values }) => {
+                if self.hole_unlocks[player].is_some() || values.len() != 2 {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.hole_unlocks[player] = Some(values);
+                if self.hole_unlocks.iter().all(Option::is_some) {
+                    self.begin_betting();
+                }
+            }
+            (PokerPhase::DealingBoard, PokerMove::Unlock { values }) => {
+                if !in_turn {
+                    return Err(MoveError::NotYourTurn);
+                }
+                let count = if self.board.is_empty() { 3 } else { 1 };
+                if values.len() != count {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.board_unlocks[player].extend(&values);
+                if player != self.dealer {
+                    let cards: Option<Vec<u8>> = values.iter().map(|&v| decode_card(v)).collect();
+                    match cards {
+                        Some(cards) if cards.iter().all(|card| !self.board.contains(card)) => {
+                            self.board.extend(cards);
+                            self.begin_betting();
+                        }
+                        // Someone garbled the deal, so check it now
+                        _ => self.phase = PokerPhase::Showdown,
+                    }
+                }
+            }
+            (PokerPhase::Betting, action) => {
+                if !in_turn {
+                    return Err(MoveError::NotYourTurn);
+                }
+                self.bet(player, action)?;
+            }
+            (PokerPhase::Showdown, PokerMove::RevealKey { key }) => {
+                if self.keys[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.keys[player] = Some(key);
+                if self.keys.iter().all(Option::is_some) {
+                    self.showdown();
+                }
+            }
+            _ => return Err(MoveError::IllegalMove),
+        }
+        self.move_count += 1;
+        Ok(self.result.unwrap_or(GameOutcome::Ongoing))
+    }
+    
+    /// Take a betting action for `player`
+    fn bet(&mut self, player: usize, action: PokerMove) -> Result<(), MoveError> {
+        let opponent = 1 - player;
+        let all_in = self.committed[player] + self.stacks[player];
+        match action {
+            PokerMove::Fold => {
+                self.settle(Some(opponent));
+                return Ok(());
+            }
+            PokerMove::Call => {
+                let to = self.committed[opponent].max(self.committed[player]).min(all_in);
+                self.put_in(player, to - self.committed[player]);
+            }
+            PokerMove::Raise { to } => {
+                let facing = self.committed[opponent];
+                if self.stacks[opponent] == 0 || to <= facing || to > all_in {
+                    return Err(MoveError::IllegalMove);
+                }
+                // Going all in may fall short of a full raise
+                if to < facing + self.min_raise && to != all_in {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.min_raise = self.min_raise.max(to - facing);
+                self.put_in(player, to - self.committed[player]);
+                self.acted = [false; 2];
+            }
+            _ => return Err(MoveError::IllegalMove),
+        }
+        self.acted[player] = true;
+        
+        if self.round_settled() {
+            self.end_betting();
+        } else {
+            self.to_act = opponent;
+        }
+        Ok(())
+    }
+    
+    /// Move `amount` chips from `player`'s stack into the pot
+    fn put_in(&mut self, player: usize, amount: u32) {
+        self.stacks[player] -= amount;
+        self.committed[player] += amount;
+        self.contributed[player] += amount;
+    }
+    
+    /// Shuffle a new hand, with the blinds posted
+    fn start_hand(&mut self) {
+        self.hand += 1;
+        self.phase = PokerPhase::Shuffling;
+        self.shuffles.clear();
+        self.hole_unlocks = [None, None];
+        self.board_unlocks = [Vec::new(), Vec::new()];
+        self.board.clear();
+        self.contributed = [0; 2];
+        self.committed = [0; 2];
+        self.keys = [None; 2];
+        
+        let (dealer, other) = (self.dealer, 1 - self.dealer);
+        self.put_in(dealer, SMALL_BLIND.min(self.stacks[dealer]));
+        self.put_in(other, BIG_BLIND.min(self.stacks[other]));
+    }
+    
+    /// Open a betting round. The dealer acts first before the flop and
+    /// second after it.
+    fn begin_betting(&mut self) {
+        self.phase = PokerPhase::Betting;
+        self.acted = [false; 2];
+        self.min_raise = BIG_BLIND;
+        self.to_act = if self.board.is_empty() { self.dealer } else { 1 - self.dealer };
+        if self.stacks[self.to_act] == 0 {
+            self.to_act = 1 - self.to_act;
+        }
+        // A blind can put a player all in before anyone acts
+        if self.round_settled() {
+            self.end_betting();
+        }
+    }
+    
+    /// Whether every player still able to bet has acted and matched the
+    /// highest bet
+    fn round_settled(&self) -> bool {
+        let high = self.committed[0].max(self.committed[1]);
+        (0..2).all(|p| self.stacks[p] == 0 || (self.acted[p] && self.committed[p] == high))
+    }
+    
+    /// Close the betting round and deal on. Once a player is all in no
+    /// more betting is possible, so the hand goes straight to showdown and
+    /// the rest of the board is dealt from the revealed keys.
+    fn end_betting(&mut self) {
+        self.committed = [0; 2];
+        self.phase = if self.board.len() == BOARD_CARDS || self.stacks.contains(&0) {
+            PokerPhase::Showdown
+        } else {
+            PokerPhase::DealingBoard
+        };
+    }
+    
+    /// Check the deal and pay the pot to the better hand, or end the game
+    /// against a player caught cheating
+    fn showdown(&mut self) {
+        let cards = match self.verify_deal() {
+            Ok(cards) => cards,
+            Err(cheater) => {
+                self.result = Some(GameOutcome::Winner(1 - cheater));
+                return;
+            }
+        };
+        let hole = |player: usize| [cards[2 * player], cards[2 * player + 1]];
+        self.board = cards[BOARD_START..BOARD_START + BOARD_CARDS].to_vec();
+        self.last_showdown = Some([hole(0), hole(1)]);
+        
+        let strength = |player: usize| {
+            let mut seven = self.board.clone();
+            seven.extend(hole(player));
+            best_hand(&seven)
+        };
+        let winner = match strength(0).cmp(&strength(1)) {
+            Ordering::Greater => Some(0),
+            Ordering::Less => Some(1),
+            Ordering::Equal => None,
+        };
+        self.settle(winner);
+    }
+    
+    /// Check every step of the hand's deal against the revealed keys.
+    /// Returns the cards in deck order, or the first player found to have
+    /// cheated.
+    fn verify_deal(&self) -> Result<Vec<u8>, usize> {
+        let (first, second) = (self.dealer, 1 - self.dealer);
+        let unlock_key = |player: usize| self.keys[player].and_then(inverse_key).ok_or(player);
+        let unlock_keys = [unlock_key(0)?, unlock_key(1)?];
+        let unlock = |value: u64, player: usize| apply_key(value, unlock_keys[player]);
+        let (shuffled, deck) = (&self.shuffles[0], &self.shuffles[1]);
+        
+        // Each shuffle must be the deck before it under the shuffler's key
+        let unlocked = |values: &[u64], player: usize| {
+            let mut values: Vec<u64> = values.iter().map(|&v| unlock(v, player)).collect();
+            values.sort_unstable();
+            values
+        };
+        let fresh_deck: Vec<u64> = (0..DECK_SIZE as u8).map(encode_card).collect();
+        if unlocked(shuffled, first) != fresh_deck {
+            return Err(first);
+        }
+        let mut previous = shuffled.clone();
+        previous.sort_unstable();
+        if unlocked(deck, second) != previous {
+            return Err(second);
+        }
+        
+        // Each unlock must strip the unlocking player's key and nothing else
+        let unlocks_match = |locked: &[u64], unlocked: &[u64], player: usize| {
+            locked.iter().zip(unlocked).all(|(&v, &u)| unlock(v, player) == u)
+        };
+        for player in 0..2 {
+            let opponent = 1 - player;
+            let unlocked = self.hole_unlocks[player].as_deref().unwrap_or_default();
+            if !unlocks_match(&deck[2 * opponent..2 * opponent + 2], unlocked, player) {
+                return Err(player);
+            }
+        }
+        let board = &deck[BOARD_START..BOARD_START + BOARD_CARDS];
+        if !unlocks_match(board, &self.board_unlocks[first], first) {
+            return Err(first);
+        }
+        if !unlocks_match(&self.board_unlocks[first], &self.board_unlocks[second], second) {
+            return Err(second);
+        }
+        
+        Ok(deck
+            .iter()
+            .map(|&v| unlock(unlock(v, second), first))
+            .map(|v| decode_card(v).expect("a verified deck holds only card encodings"))
+            .collect())
+    }
+    
+    /// Pay the pot to `winner`, or split it, then deal the next hand unless
+    /// a player is out of chips
+    fn settle(&mut self, winner: Option<usize>) {
+        // Chips the opponent could not match go back to whoever put them in
+        let matched = self.contributed[0].min(self.contributed[1]);
+        for player in 0..2 {
+            self.stacks[player] += self.contributed[player] - matched;
+        }
+        match winner {
+            Some(winner) => self.stacks[winner] += 2 * matched,
+            None => {
+                self.stacks[0] += matched;
+                self.stacks[1] += matched;
+            }
+        }
+        
+        match self.stacks.iter().position(|&stack| stack == 0) {
+            Some(loser) => self.result = Some(GameOutcome::Winner(1 - loser)),
+            None => {
+                self.dealer = 1 - self.dealer;
+                self.start_hand();
+            }
+        }
+    }
+}
+
+/// Value of `card` before encryption. Cards 0 to 51 run two to ace in each
+/// suit. Every value is a square, so encryption can't be seen to change
+/// whether it is a quadratic residue.
+pub fn encode_card(card: u8) -> u64 {
+    (card as u64 + 2).pow(2)
+}
+
+/// Card with the unencrypted value `value`
+pub fn decode_card(value: u64) -> Option<u8> {
+    (0..DECK_SIZE as u8).find(|&card| encode_card(card) == value)
+}
+
+/// Encrypt `value` under `key`, or decrypt it with the key's inverse
+pub fn apply_key(value: u64, key: u64) -> u64 {
+    let prime = PRIME as u128;
+    let (mut base, mut exponent, mut result) = (value as u128 % prime, key, 1u128);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % prime;
+        }
+        base = base * base % prime;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Key that decrypts what `key` encrypts, if `key` is usable
+pub fn inverse_key(key: u64) -> Option<u64> {
+    let order = (PRIME - 1) as i128;
+    let (mut r0, mut r1) = (order, key as i128 % order);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let quotient = r0 / r1;
+        (r0, r1) = (r1, r0 - quotient * r1);
+        (t0, t1) = (t1, t0 - quotient * t1);
+    }
+    (r0 == 1).then(|| t0.rem_euclid(order) as u64)
+}
+
+/// Whether a submitted shuffle could be an encrypted deck: one distinct
+/// value in range per card
+fn is_valid_deck(deck: &[u64]) -> bool {
+    let mut values = deck.to_vec();
+    values.sort_unstable();
+    values.dedup();
+    deck.len() == DECK_SIZE
+        && values.len() == DECK_SIZE
+        && values.iter().all(|&v| v > 1 && v < PRIME)
+}
+
+/// Strength of the best five-card hand among seven cards; higher is better
+pub fn best_hand(cards: &[u8]) -> u32 {
+    let mut best = 0;
+    for left_out in 0..cards.len() {
+        for also_left_out in left_out + 1..cards.len() {
+            let hand: Vec<u8> = (0..cards.len())
+                .filter(|&i| i != left_out && i != also_left_out)
+                .map(|i| cards[i])
+                .collect();
+            best = best.max(hand_value(&hand));
+        }
+    }
+    best
+}
+
+/// Strength of a five-card hand: its category, then its ranks to break ties
+fn hand_value(hand: &[u8]) -> u32 {
+    let flush = hand.iter().all(|&card| card / 13 == hand[0] / 13);
+    let mut counts = [0u8; 13];
+    for &card in hand {
+        counts[(card % 13) as usize] += 1;
+    }
+    // Ranks ordered by how many of each are held, then highest first
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|&rank| counts[rank as usize] > 0)
+        .map(|rank| (counts[rank as usize], rank))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    let mut ranks: Vec<u8> = groups.iter().map(|&(_, rank)| rank).collect();
+    
+    let straight_high = match ranks[..] {
+        [high, .., low] if ranks.len() == 5 && high - low == 4 => Some(high),
+        // The wheel, ace to five, with the ace playing low
+        [12, 3, 2, 1, 0] => Some(3),
+        _ => None,
+    };
+    let category = match (straight_high.is_some(), flush, groups[0].0, groups[1].0) {
+        (true, true, _, _) => 8,
+        (_, _, 4, _) => 7,
+        (_, _, 3, 2) => 6,
+        (_, true, _, _) => 5,
+        (true, _, _, _) => 4,
+        (_, _, 3, _) => 3,
+        (_, _, 2, 2) => 2,
+        (_, _, 2, _) => 1,
+        _ => 0,
+    };
+    if let Some(high) = straight_high {
+        ranks = vec![high];
+    }
+    let tiebreak = ranks.iter().fold(0, |value, &rank| value << 4 | rank as u32);
+    category << 20 | tiebreak << (4 * (5 - ranks.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Each player's shuffling key
+    const KEYS: [u64; 2] = [65_537, 1_000_003];
+    
+    /// Cards written like `As Td 2c`
+    fn cards(text: &str) -> Vec<u8> {
+        text.split_whitespace()
+            .map(|card| {
+                let bytes = card.as_bytes();
+                let rank = b"23456789TJQKA".iter().position(|&r| r == bytes[0]).unwrap();
+                let suit = b"cdhs".iter().position(|&s| s == bytes[1]).unwrap();
+                (suit * 13 + rank) as u8
+            })
+            .collect()
+    }
+    
+    fn lock(values: &[u64], key: u64) -> Vec<u64> {
+        values.iter().map(|&value| apply_key(value, key)).collect()
+    }
+    
+    fn unlock(values: &[u64], player: usize) -> Vec<u64> {
+        lock(values, inverse_key(KEYS[player]).unwrap())
+    }
+    
+    /// Both players check the betting round through
+    fn check_around(game: &mut PokerState) {
+        while game.phase == PokerPhase::Betting {
+            let player = game.waiting_on().unwrap();
+            game.apply_move(player, PokerMove::Call).unwrap();
+        }
+    }
+    
+    /// Deal the first hand with `top` on top of the deck (player 0's hole
+    /// cards, player 1's, then the board) and check it down to showdown
+    fn check_down(top: &str) -> PokerState {
+        let top = cards(top);
+        let mut order = top.clone();
+        order.extend((0..DECK_SIZE as u8).filter(|card| !top.contains(card)));
+        let plain: Vec<u64> = order.into_iter().map(encode_card).collect();
+        
+        let mut game = PokerState::default();
+        game.apply_move(0, PokerMove::Shuffle { deck: lock(&plain, KEYS[0]) }).unwrap();
+        let deck = lock(&game.shuffles[0], KEYS[1]);
+        game.apply_move(1, PokerMove::Shuffle { deck: deck.clone() }).unwrap();
+        game.apply_move(0, PokerMove::Unlock { values: unlock(&deck[2..4], 0) }).unwrap();
+        game.apply_move(1, PokerMove::Unlock { values: unlock(&deck[0..2], 1) }).unwrap();
+        
+        for street in [4..7usize, 7..8, 8..9] {
+            check_around(&mut game);
+            let values = unlock(&deck[street], 0);
+            game.apply_move(0, PokerMove::Unlock { values: values.clone() }).unwrap();
+            game.apply_move(1, PokerMove::Unlock { values: unlock(&values, 1) }).unwrap();
+        }
+        check_around(&mut game);
+        assert_eq!(game.phase, PokerPhase::Showdown);
+        
+        for (player, key) in KEYS.into_iter().enumerate() {
+            game.apply_move(player, PokerMove::RevealKey { key }).unwrap();
+        }
+        game
+    }
+    
+    /// Chips each player holds, counting blinds already posted for the next hand
+    fn chips(game: &PokerState) -> [u32; 2] {
+        [game.stacks[0] + game.contributed[0], game.stacks[1] + game.contributed[1]]
+    }
+    
+    #[test]
+    fn hand_categories_rank_in_order() {
+        let hands = [
+            "2c 5d 9h Js Kc",
+            "2c 2d 9h Js Kc",
+            "2c 2d 9h 9s Kc",
+            "2c 2d 2h 9s Kc",
+            "Ac 2d 3h 4s 5c",
+            "5c 6d 7h 8s 9c",
+            "Tc Jd Qh Ks Ac",
+            "2c 5c 9c Jc Kc",
+            "2c 2d 2h 9s 9c",
+            "2c 2d 2h 2s 9c",
+            "5c 6c 7c 8c 9c",
+        ];
+        let values: Vec<u32> = hands.iter().map(|hand| hand_value(&cards(hand))).collect();
+        for pair in values.windows(2) {
+            assert!(pair[0] < pair[1], "{:?}", values);
+        }
+    }
+    
+    #[test]
+    fn kickers_break_ties() {
+        let ace_kicker = hand_value(&cards("Kc Kd Ah 7s 3c"));
+        let queen_kicker = hand_value(&cards("Kh Ks Qh 7c 3d"));
+        assert!(ace_kicker > queen_kicker);
+        
+        // Suits never matter
+        assert_eq!(hand_value(&cards("Kc Kd Ah 7s 3c")), hand_value(&cards("Kh Ks Ac 7d 3h")));
+    }
+    
+    #[test]
+    fn best_hand_picks_five_of_seven() {
+        let flush = best_hand(&cards("2h 7h 9h Jh Kd Kh 3c"));
+        assert_eq!(flush, hand_value(&cards("2h 7h 9h Jh Kh")));
+        
+        let two_pair = best_hand(&cards("Ac Ad Kh Ks Qc Qd 2s"));
+        assert_eq!(two_pair, hand_value(&cards("Ac Ad Kh Ks Qc")));
+    }
+    
+    #[test]
+    fn better_hand_takes_the_pot() {
+        let game = check_down("As Ah 2d 7h Ks Qd 9c 5h 3s");
+        assert_eq!(game.last_showdown, Some([[51, 38], [13, 31]]));
+        assert_eq!(chips(&game), [STARTING_STACK + BIG_BLIND, STARTING_STACK - BIG_BLIND]);
+        assert_eq!(game.hand, 2);
+        assert_eq!(game.dealer, 1);
+    }
+    
+    #[test]
+    fn tied_hands_split_the_pot() {
+        // Both players play the straight flush on the board
+        let game = check_down("2d 3h 4d 5h 6c 7c 8c 9c Tc");
+        assert_eq!(chips(&game), [STARTING_STACK; 2]);
+        assert_eq!(game.result, None);
+    }
+    
+    #[test]
+    fn uneven_split_returns_the_unmatched_chips() {
+        let mut game = PokerState::default();
+        game.stacks = [30, 100];
+        game.contributed = [40, 30];
+        game.settle(None);
+        assert_eq!(chips(&game), [70, 130]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Unbiasable shared randomness
+//!
+//! Each participant commits to a secret salt, and nobody reveals until every
+//! commitment is in, so no one can pick a salt after seeing the others. The
+//! game seed is the hash of all the revealed salts: one honest participant is
+//! enough to make it unpredictable, and nobody can change it afterwards.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, splitmix64, MoveError};
+
+/// A commit-reveal round among a fixed set of participants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitReveal {
+    pub commitments: Vec<Option<[u8; 32]>>,
+    pub reveals: Vec<Option<Vec<u8>>>,
+}
+
+impl CommitReveal {
+    pub fn new(participants: usize) -> Self {
+        Self {
+            commitments: vec![None; participants],
+            reveals: vec![None; participants],
+        }
+    }
+    
+    /// Submit a participant's commitment, `commitment(&[salt])`
+    pub fn commit(&mut self, participant: usize, hash: [u8; 32]) -> Result<(), MoveError> {
+        match self.commitments.get_mut(participant) {
+            Some(slot @ None) => {
+                *slot = Some(hash);
+                Ok(())
+            }
+            Some(Some(_)) => Err(MoveError::IllegalMove),
+            None => Err(MoveError::NotYourTurn),
+        }
+    }
+    
+    pub fn all_committed(&self) -> bool {
+        self.commitments.iter().all(Option::is_some)
+    }
+    
+    /// Reveal a participant's salt. Only allowed once everyone has committed.
+    pub fn reveal(&mut self, participant: usize, salt: Vec<u8>) -> Result<(), MoveError> {
+        if !self.all_committed() {
+            return Err(MoveError::IllegalMove);
+        }
+        let expected = self.commitments.get(participant).ok_or(MoveError::NotYourTurn)?;
+        if self.reveals[participant].is_some() {
+            return Err(MoveError::IllegalMove);
+        }
+        if *expected != Some(commitment(&[&salt])) {
+            return Err(MoveError::CommitmentMismatch);
+        }
+        self.reveals[participant] = Some(salt);
+        Ok(())
+    }
+    
+    /// Participants who still owe a commitment or reveal
+    pub fn pending(&self) -> Vec<usize> {
+        (0..self.commitments.len())
+            .filter(|&p| self.commitments[p].is_none() || self.reveals[p].is_none())
+            .collect()
+    }
+    
+    /// Participants holding up the current phase: those yet to commit while
+    /// commitments are collected, then those yet to reveal
+    pub fn stalled(&self) -> Vec<usize> {
+        let all_committed = self.all_committed();
+        (0..self.commitments.len())
+            .filter(|&p| {
+                if all_committed {
+                    self.reveals[p].is_none()
+                } else {
+                    self.commitments[p].is_none()
+                }
+            })
+            .collect()
+    }
+    
+    /// The shared seed, available once every salt has been revealed
+    pub fn seed(&self) -> Option<u64> {
+        let salts: Option<Vec<&[u8]>> = self.reveals.iter().map(|r| r.as_deref()).collect();
+        let hash = commitment(&salts?);
+        Some(u64::from_le_bytes(hash[..8].try_into().expect("hash has at least 8 bytes")))
+    }
+}
+
+/// Deterministic random stream derived from a seed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(1);
+        splitmix64(self.state)
+    }
+    
+    /// Uniform value in `0..bound`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        // Rejection sampling avoids modulo bias
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
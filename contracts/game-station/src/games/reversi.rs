@@ -0,0 +1,152 @@
+//! Reversi (Othello) engine
+//!
+//! A disc must be placed so it outflanks at least one line of the
+//! opponent's discs, and every outflanked line is flipped. A player with no
+//! legal placement passes automatically. The game ends once neither player
+//! can place, and whoever has more discs on the board wins.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameOutcome, MoveError};
+
+/// Width and height of the board
+pub const SIZE: usize = 8;
+
+/// Directions a line of discs can run in, as (row, col) steps
+const DIRECTIONS: [(isize, isize); 8] =
+    [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// Reversi game state. The first player plays the dark discs and moves
+/// first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReversiState {
+    /// Index of the player owning each disc, by `[row][col]`
+    pub board: [[Option<usize>; SIZE]; SIZE],
+    pub current_turn: usize,
+    pub move_count: u32,
+    /// Set when the last player to move had no legal placement and passed
+    pub passed: bool,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl Default for ReversiState {
+    fn default() -> Self {
+        let mut board = [[None; SIZE]; SIZE];
+        let mid = SIZE / 2;
+        board[mid - 1][mid - 1] = Some(1);
+        board[mid][mid] = Some(1);
+        board[mid - 1][mid] = Some(0);
+        board[mid][mid - 1] = Some(0);
+        Self {
+            board,
+            current_turn: 0,
+            move_count: 0,
+            passed: false,
+            result: None,
+        }
+    }
+}
+
+impl ReversiState {
+    /// Decode move data as `[row, col]`
+    pub fn decode_move(move_data: &[u8]) -> Result<(usize, usize), MoveError> {
+        match move_data {
+            [row, col] => Ok((*row as usize, *col as usize)),
+            _ => Err(MoveError::InvalidEncoding),
+        }
+    }
+    
+    /// Discs each player has on the board
+    pub fn disc_counts(&self) -> [u32; 2] {
+        let mut counts = [0; 2];
+        for &player in self.board.iter().flatten().flatten() {
+            counts[player] += 1;
+        }
+        counts
+    }
+    
+    /// Place a disc for `player` at (`row`, `col`) and flip every line it
+    /// outflanks
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        row: usize,
+        col: usize,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if row >= SIZE || col >= SIZE {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.board[row][col].is_some() {
+            return Err(MoveError::CellOccupied);
+        }
+        let flips = self.flips(player, row, col);
+        if flips.is_empty() {
+            return Err(MoveError::IllegalMove);
+        }
+        
+        self.board[row][col] = Some(player);
+        for (r, c) in flips {
+            self.board[r][c] = Some(player);
+        }
+        self.move_count += 1;
+        
+        let opponent = 1 - player;
+        if self.has_legal_move(opponent) {
+            self.current_turn = opponent;
+            self.passed = false;
+        } else if self.has_legal_move(player) {
+            // The opponent can't place anywhere, so they pass
+            self.passed = true;
+        } else {
+            let [first, second] = self.disc_counts();
+            let outcome = match first.cmp(&second) {
+                Ordering::Greater => GameOutcome::Winner(0),
+                Ordering::Less => GameOutcome::Winner(1),
+                Ordering::Equal => GameOutcome::Draw,
+            };
+            self.result = Some(outcome);
+        }
+        Ok(self.result.unwrap_or(GameOutcome::Ongoing))
+    }
+    
+    /// Whether `player` can place a disc anywhere
+    pub fn has_legal_move(&self, player: usize) -> bool {
+        (0..SIZE).any(|row| {
+            (0..SIZE).any(|col| {
+                self.board[row][col].is_none() && !self.flips(player, row, col).is_empty()
+            })
+        })
+    }
+    
+    /// Opponent discs a `player` disc at (`row`, `col`) would flip
+    fn flips(&self, player: usize, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut flips = Vec::new();
+        for (dr, dc) in DIRECTIONS {
+            let mut line = Vec::new();
+            let (mut r, mut c) = (row as isize + dr, col as isize + dc);
+            while (0..SIZE as isize).contains(&r) && (0..SIZE as isize).contains(&c) {
+                match self.board[r as usize][c as usize] {
+                    Some(owner) if owner != player => line.push((r as usize, c as usize)),
+                    // Outflanked only if the line ends at one of the player's own discs
+                    Some(_) => {
+                        flips.append(&mut line);
+                        break;
+                    }
+                    None => break,
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+        flips
+    }
+}
@@ -0,0 +1,117 @@
+//! Rock-Paper-Scissors engine
+//!
+//! Each round both players commit to a salted hash of their throw, and
+//! nobody reveals until both commitments are in, so neither can react to
+//! the other's throw. A player who stalls on committing or revealing after
+//! their opponent has can be claimed against by the turn timer, forfeiting
+//! the match. A tied round is thrown again, and best-of-N is the room's
+//! series.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, GameOutcome, MoveError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Throw {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Throw {
+    fn beats(self, other: Throw) -> bool {
+        matches!(
+            (self, other),
+            (Throw::Rock, Throw::Scissors)
+                | (Throw::Paper, Throw::Rock)
+                | (Throw::Scissors, Throw::Paper)
+        )
+    }
+}
+
+/// Commitment a player submits for a throw
+pub fn throw_commitment(throw: Throw, salt: &[u8]) -> [u8; 32] {
+    commitment(&[&[throw as u8], salt])
+}
+
+/// Moves a player can make in a Rock-Paper-Scissors room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RockPaperScissorsMove {
+    /// Commit to this round's throw, `throw_commitment(throw, salt)`
+    Commit { commitment: [u8; 32] },
+    /// Reveal the committed throw once both players have committed
+    Reveal { throw: Throw, salt: Vec<u8> },
+}
+
+/// State of one round
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RockPaperScissorsState {
+    pub commitments: [Option<[u8; 32]>; 2],
+    pub throws: [Option<Throw>; 2],
+    pub move_count: u32,
+}
+
+impl RockPaperScissorsState {
+    /// Decode BCS-encoded move data
+    pub fn decode_move(move_data: &[u8]) -> Result<RockPaperScissorsMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// The player holding up the round, if their opponent has already acted
+    pub fn waiting_on(&self) -> Option<usize> {
+        let pending: Vec<usize> = if self.commitments.iter().all(Option::is_some) {
+            (0..2).filter(|&p| self.throws[p].is_none()).collect()
+        } else {
+            (0..2).filter(|&p| self.commitments[p].is_none()).collect()
+        };
+        match pending[..] {
+            [player] => Some(player),
+            _ => None,
+        }
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        mv: RockPaperScissorsMove,
+    ) -> Result<GameOutcome, MoveError> {
+        if player > 1 {
+            return Err(MoveError::NotYourTurn);
+        }
+        if self.throws.iter().all(Option::is_some) {
+            return Err(MoveError::GameOver);
+        }
+        
+        match mv {
+            RockPaperScissorsMove::Commit { commitment } => {
+                if self.commitments[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.commitments[player] = Some(commitment);
+            }
+            RockPaperScissorsMove::Reveal { throw, salt } => {
+                if self.commitments.iter().any(Option::is_none) || self.throws[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                if self.commitments[player] != Some(throw_commitment(throw, &salt)) {
+                    return Err(MoveError::CommitmentMismatch);
+                }
+                self.throws[player] = Some(throw);
+            }
+        }
+        self.move_count += 1;
+        
+        match self.throws {
+            [Some(first), Some(second)] if first.beats(second) => Ok(GameOutcome::Winner(0)),
+            [Some(first), Some(second)] if second.beats(first) => Ok(GameOutcome::Winner(1)),
+            [Some(_), Some(_)] => {
+                // Tied: both throw again
+                self.commitments = [None; 2];
+                self.throws = [None; 2];
+                Ok(GameOutcome::Ongoing)
+            }
+            _ => Ok(GameOutcome::Ongoing),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Snake engine
+//!
+//! Scores are verified by replaying the game: the client submits the seed it
+//! placed food with and every turn it made, and the contract re-runs the
+//! simulation tick by tick. The rules mirror the web client.
+
+use serde::{Deserialize, Serialize};
+
+use super::{randomness::SeededRng, MoveError};
+use crate::{Direction, Position, SnakeGameState};
+
+/// Width and height of the board
+pub const GRID_SIZE: i32 = 20;
+
+/// Points for each piece of food eaten
+pub const POINTS_PER_FOOD: u32 = 10;
+
+/// Longest game that will be replayed, to bound execution cost
+pub const MAX_REPLAY_TICKS: u32 = 10_000;
+
+/// A change of direction, applied just before the snake moves on `tick`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SnakeTurn {
+    pub tick: u32,
+    pub direction: Direction,
+}
+
+impl Direction {
+    fn is_opposite(self, other: Direction) -> bool {
+        matches!(
+            (self, other),
+            (Direction::Up, Direction::Down)
+                | (Direction::Down, Direction::Up)
+                | (Direction::Left, Direction::Right)
+                | (Direction::Right, Direction::Left)
+        )
+    }
+}
+
+impl SnakeGameState {
+    /// Advance the game by one tick. Returns `false` once the snake has crashed.
+    pub fn step(&mut self, rng: &mut SeededRng) -> bool {
+        if !self.is_alive {
+            return false;
+        }
+        
+        let head = self.snake[0];
+        let next = match self.direction {
+            Direction::Up => Position { x: head.x, y: head.y - 1 },
+            Direction::Down => Position { x: head.x, y: head.y + 1 },
+            Direction::Left => Position { x: head.x - 1, y: head.y },
+            Direction::Right => Position { x: head.x + 1, y: head.y },
+        };
+        let off_board = next.x < 0 || next.x >= GRID_SIZE || next.y < 0 || next.y >= GRID_SIZE;
+        if off_board || self.snake.contains(&next) {
+            self.is_alive = false;
+            return false;
+        }
+        
+        self.snake.insert(0, next);
+        if next == self.food {
+            self.score += POINTS_PER_FOOD;
+            match place_food(&self.snake, rng) {
+                Some(food) => self.food = food,
+                // The snake fills the board; nothing is left to eat
+                None => {
+                    self.is_alive = false;
+                    return false;
+                }
+            }
+        } else {
+            self.snake.pop();
+        }
+        true
+    }
+}
+
+/// A free cell for the next piece of food, or `None` if the board is full
+fn place_food(snake: &[Position], rng: &mut SeededRng) -> Option<Position> {
+    if snake.len() >= (GRID_SIZE * GRID_SIZE) as usize {
+        return None;
+    }
+    loop {
+        let food = Position {
+            x: rng.below(GRID_SIZE as u64) as i32,
+            y: rng.below(GRID_SIZE as u64) as i32,
+        };
+        if !snake.contains(&food) {
+            return Some(food);
+        }
+    }
+}
+
+/// Replay a game from its seed and inputs and return the final state.
+///
+/// Turns must be in increasing tick order. Like the web client, turning
+/// straight back into the snake's own neck is ignored. The game ends at the
+/// first crash, or after `MAX_REPLAY_TICKS`.
+pub fn replay(seed: u64, turns: &[SnakeTurn]) -> Result<SnakeGameState, MoveError> {
+    if turns.windows(2).any(|pair| pair[0].tick >= pair[1].tick) {
+        return Err(MoveError::IllegalMove);
+    }
+    
+    let mut rng = SeededRng::new(seed);
+    let start = SnakeGameState::default();
+    let mut game = SnakeGameState {
+        food: place_food(&start.snake, &mut rng).unwrap_or(start.food),
+        ..start
+    };
+    
+    let mut turns = turns.iter().peekable();
+    for tick in 0..MAX_REPLAY_TICKS {
+        if let Some(turn) = turns.next_if(|turn| turn.tick == tick) {
+            if !turn.direction.is_opposite(game.direction) {
+                game.direction = turn.direction;
+            }
+        }
+        if !game.step(&mut rng) {
+            break;
+        }
+    }
+    Ok(game)
+}
@@ -0,0 +1,169 @@
+//! Snake & Ladders engine
+
+use serde::{Deserialize, Serialize};
+
+use super::{randomness::CommitReveal, splitmix64, GameOutcome, MoveError};
+
+/// Final square of the board
+pub const FINAL_SQUARE: u8 = 100;
+
+/// Classic board layout as (from, to) jumps. Ladders go up, snakes go down.
+pub const CLASSIC_JUMPS: [(u8, u8); 19] = [
+    // Ladders
+    (1, 38), (4, 14), (9, 31), (21, 42), (28, 84), (36, 44), (51, 67), (71, 91), (80, 100),
+    // Snakes
+    (16, 6), (47, 26), (49, 11), (56, 53), (62, 19), (64, 60), (87, 24), (93, 73), (95, 75), (98, 78),
+];
+
+/// Moves a player can make in a Snake & Ladders room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SnakeLaddersMove {
+    /// Commit to a dice salt before the game starts
+    CommitSalt { commitment: [u8; 32] },
+    /// Reveal the committed salt
+    RevealSalt { salt: Vec<u8> },
+    /// Roll the die on your turn
+    Roll,
+}
+
+/// Snake & Ladders game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnakeLaddersState {
+    /// Square each player is on, 0 before entering the board
+    pub positions: Vec<u8>,
+    /// Snakes and ladders as (from, to) squares
+    pub jumps: Vec<(u8, u8)>,
+    /// Index of the player whose turn it is
+    pub current_turn: usize,
+    pub last_roll: Option<u8>,
+    pub winner: Option<usize>,
+    pub move_count: u32,
+    /// Salts from every player seed the dice, so no one can load them
+    pub dice: CommitReveal,
+}
+
+impl SnakeLaddersState {
+    /// New game on the classic board for `players` players
+    pub fn new(players: usize) -> Self {
+        Self {
+            positions: vec![0; players],
+            jumps: CLASSIC_JUMPS.to_vec(),
+            current_turn: 0,
+            last_roll: None,
+            winner: None,
+            move_count: 0,
+            dice: CommitReveal::new(players),
+        }
+    }
+    
+    /// Whether the dice are seeded and turns are being taken
+    pub fn is_started(&self) -> bool {
+        self.dice.seed().is_some()
+    }
+    
+    /// Players holding up the dice seed. The last player to reveal sees the
+    /// seed before anyone else and could withhold their salt if they didn't
+    /// like it, so running out of time here forfeits the game.
+    pub fn stalled(&self) -> Vec<usize> {
+        if self.is_started() {
+            return Vec::new();
+        }
+        self.dice.stalled()
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<SnakeLaddersMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(&mut self, player: usize, mv: SnakeLaddersMove) -> Result<GameOutcome, MoveError> {
+        match mv {
+            SnakeLaddersMove::CommitSalt { commitment } => {
+                self.dice.commit(player, commitment)?;
+                Ok(GameOutcome::Ongoing)
+            }
+            SnakeLaddersMove::RevealSalt { salt } => {
+                self.dice.reveal(player, salt)?;
+                Ok(GameOutcome::Ongoing)
+            }
+            SnakeLaddersMove::Roll => {
+                let seed = self.dice.seed().ok_or(MoveError::IllegalMove)?;
+                let roll = roll_die(seed.wrapping_add(u64::from(self.move_count)));
+                self.apply_roll(player, roll)
+            }
+        }
+    }
+    
+    /// Move `player` by a die `roll`. Landing past the final square forfeits
+    /// the move; the final square must be reached exactly.
+    pub fn apply_roll(&mut self, player: usize, roll: u8) -> Result<GameOutcome, MoveError> {
+        if self.winner.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if !(1..=6).contains(&roll) {
+            return Err(MoveError::InvalidEncoding);
+        }
+        
+        self.last_roll = Some(roll);
+        self.move_count += 1;
+        
+        let target = self.positions[player] + roll;
+        if target <= FINAL_SQUARE {
+            self.positions[player] = self
+                .jumps
+                .iter()
+                .find(|(from, _)| *from == target)
+                .map(|(_, to)| *to)
+                .unwrap_or(target);
+        }
+        
+        if self.positions[player] == FINAL_SQUARE {
+            self.winner = Some(player);
+            return Ok(GameOutcome::Winner(player));
+        }
+        
+        self.current_turn = (self.current_turn + 1) % self.positions.len();
+        Ok(GameOutcome::Ongoing)
+    }
+}
+
+/// Roll a six-sided die from a seed
+pub fn roll_die(seed: u64) -> u8 {
+    (splitmix64(seed) % 6) as u8 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::commitment;
+    
+    #[test]
+    fn withheld_reveal_stalls_the_game() {
+        let mut game = SnakeLaddersState::new(2);
+        for player in 0..2u8 {
+            let commitment = commitment(&[&[player]]);
+            game.apply_move(player as usize, SnakeLaddersMove::CommitSalt { commitment }).unwrap();
+        }
+        assert_eq!(game.stalled(), vec![0, 1]);
+        
+        game.apply_move(0, SnakeLaddersMove::RevealSalt { salt: vec![0] }).unwrap();
+        assert!(!game.is_started());
+        assert_eq!(game.stalled(), vec![1]);
+        assert_eq!(game.apply_move(1, SnakeLaddersMove::Roll), Err(MoveError::IllegalMove));
+        
+        game.apply_move(1, SnakeLaddersMove::RevealSalt { salt: vec![1] }).unwrap();
+        assert!(game.is_started());
+        assert!(game.stalled().is_empty());
+    }
+    
+    #[test]
+    fn missing_commitment_stalls_the_game() {
+        let mut game = SnakeLaddersState::new(3);
+        let commitment = commitment(&[&[0]]);
+        game.apply_move(0, SnakeLaddersMove::CommitSalt { commitment }).unwrap();
+        assert_eq!(game.stalled(), vec![1, 2]);
+    }
+}
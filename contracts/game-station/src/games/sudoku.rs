@@ -0,0 +1,105 @@
+//! Daily Sudoku
+//!
+//! Each day's puzzle is generated from the day number alone, so every chain
+//! and client derives the same one. A solved grid is built by shuffling a
+//! known-valid pattern, then most cells are blanked. Generated puzzles can
+//! have more than one solution, so any complete grid that follows the rules
+//! and keeps the givens is accepted.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, randomness::SeededRng};
+
+/// Cells filled in at the start
+pub const CLUES: usize = 32;
+
+/// A 9x9 grid by `[row][col]`, digits 1 to 9 with 0 for an empty cell
+pub type Grid = [[u8; 9]; 9];
+
+/// A day's puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SudokuPuzzle {
+    pub givens: Grid,
+    /// The grid the givens were taken from
+    pub solution: Grid,
+}
+
+/// The puzzle for `day`, counted in days since the Unix epoch
+pub fn daily_puzzle(day: u64) -> SudokuPuzzle {
+    let hash = commitment(&[b"sudoku", &day.to_le_bytes()]);
+    let mut rng = SeededRng::new(u64::from_le_bytes(
+        hash[..8].try_into().expect("hash has at least 8 bytes"),
+    ));
+    
+    // Relabelling digits, reordering rows within a band, the bands
+    // themselves, and the same for columns all keep a grid valid
+    let digits = shuffled(&mut rng, 9);
+    let rows = band_order(&mut rng);
+    let cols = band_order(&mut rng);
+    let mut solution = [[0; 9]; 9];
+    for (r, &row) in rows.iter().enumerate() {
+        for (c, &col) in cols.iter().enumerate() {
+            let base = (3 * (row % 3) + row / 3 + col) % 9;
+            solution[r][c] = digits[base] as u8 + 1;
+        }
+    }
+    
+    let mut givens = [[0; 9]; 9];
+    for cell in shuffled(&mut rng, 81).into_iter().take(CLUES) {
+        givens[cell / 9][cell % 9] = solution[cell / 9][cell % 9];
+    }
+    SudokuPuzzle { givens, solution }
+}
+
+/// A grid from 81 digits listed row by row
+pub fn grid_from_digits(digits: &[u8]) -> Option<Grid> {
+    if digits.len() != 81 {
+        return None;
+    }
+    Some(std::array::from_fn(|row| std::array::from_fn(|col| digits[row * 9 + col])))
+}
+
+/// Whether `grid` is a complete, valid solution that keeps every given
+pub fn is_solution(givens: &Grid, grid: &Grid) -> bool {
+    let keeps_givens = (0..81).all(|cell| {
+        let given = givens[cell / 9][cell % 9];
+        given == 0 || given == grid[cell / 9][cell % 9]
+    });
+    let mut units = (0..9).flat_map(|i| {
+        [
+            // Row, column and box `i`
+            std::array::from_fn(|j| grid[i][j]),
+            std::array::from_fn(|j| grid[j][i]),
+            std::array::from_fn(|j| grid[i / 3 * 3 + j / 3][i % 3 * 3 + j % 3]),
+        ]
+    });
+    keeps_givens && units.all(|unit| is_complete_unit(&unit))
+}
+
+/// Whether a row, column or box holds each digit 1 to 9 once
+fn is_complete_unit(unit: &[u8; 9]) -> bool {
+    let mut seen = [false; 10];
+    unit.iter().all(|&digit| {
+        let fresh = (1..=9).contains(&digit) && !seen[digit as usize];
+        seen[digit.min(9) as usize] = true;
+        fresh
+    })
+}
+
+/// Indices `0..len` in a random order
+fn shuffled(rng: &mut SeededRng, len: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        order.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    order
+}
+
+/// A random order of the nine rows (or columns) that keeps each band of
+/// three together
+fn band_order(rng: &mut SeededRng) -> Vec<usize> {
+    shuffled(rng, 3)
+        .into_iter()
+        .flat_map(|band| shuffled(rng, 3).into_iter().map(move |line| band * 3 + line))
+        .collect()
+}
@@ -0,0 +1,330 @@
+//! Tetris engine
+//!
+//! Scores are verified by replaying the game: the contract picks the seed
+//! the piece sequence is drawn from when the session starts, the client
+//! submits every input with the tick it was made on, and the contract re-runs
+//! the game tick by tick. The rules mirror the web client: pieces come in
+//! shuffled bags of all seven, rotation has no wall kicks, and gravity
+//! speeds up every ten lines.
+
+use serde::{Deserialize, Serialize};
+
+use super::{randomness::SeededRng, MoveError};
+
+/// Columns on the board
+pub const WIDTH: usize = 10;
+/// Rows on the board
+pub const HEIGHT: usize = 20;
+
+/// Longest game that will be replayed, to bound execution cost
+pub const MAX_REPLAY_TICKS: u32 = 100_000;
+/// Ticks a piece takes to fall one row at level 0
+pub const INITIAL_FALL_TICKS: u32 = 48;
+/// Lines to clear for each level
+pub const LINES_PER_LEVEL: u32 = 10;
+/// Points for clearing one to four lines at once, multiplied by level + 1
+pub const LINE_POINTS: [u32; 4] = [100, 300, 500, 800];
+
+/// Cells of each piece in its spawn orientation, as (column, row) within a
+/// square box, with the box size: I, O, T, S, Z, J, L
+const SHAPES: [([(i8, i8); 4], i8); 7] = [
+    ([(0, 1), (1, 1), (2, 1), (3, 1)], 4),
+    ([(0, 0), (1, 0), (0, 1), (1, 1)], 2),
+    ([(1, 0), (0, 1), (1, 1), (2, 1)], 3),
+    ([(1, 0), (2, 0), (0, 1), (1, 1)], 3),
+    ([(0, 0), (1, 0), (1, 1), (2, 1)], 3),
+    ([(0, 0), (0, 1), (1, 1), (2, 1)], 3),
+    ([(2, 0), (0, 1), (1, 1), (2, 1)], 3),
+];
+
+/// A player input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TetrisAction {
+    Left,
+    Right,
+    RotateClockwise,
+    RotateCounterClockwise,
+    /// Move down a row for a point, locking the piece if it can't
+    SoftDrop,
+    /// Drop to the bottom for two points a row and lock
+    HardDrop,
+}
+
+/// An input, applied before gravity on `tick`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TetrisInput {
+    pub tick: u32,
+    pub action: TetrisAction,
+}
+
+/// The falling piece
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FallingPiece {
+    /// Index into I, O, T, S, Z, J, L
+    pub kind: u8,
+    /// Quarter turns clockwise from the spawn orientation
+    pub rotation: u8,
+    /// Board position of the box's top-left corner
+    pub x: i8,
+    pub y: i8,
+}
+
+impl FallingPiece {
+    fn spawn(kind: u8) -> Self {
+        let size = SHAPES[kind as usize].1;
+        Self { kind, rotation: 0, x: (WIDTH as i8 - size) / 2, y: 0 }
+    }
+    
+    /// Board cells the piece covers
+    pub fn cells(&self) -> [(i8, i8); 4] {
+        let (shape, size) = SHAPES[self.kind as usize];
+        shape.map(|(mut col, mut row)| {
+            for _ in 0..self.rotation % 4 {
+                (col, row) = (size - 1 - row, col);
+            }
+            (self.x + col, self.y + row)
+        })
+    }
+    
+    fn shifted(self, dx: i8, dy: i8) -> Self {
+        Self { x: self.x + dx, y: self.y + dy, ..self }
+    }
+    
+    fn rotated(self, turns: u8) -> Self {
+        Self { rotation: (self.rotation + turns) % 4, ..self }
+    }
+}
+
+/// Tetris game state. Row 0 is the top of the board; empty cells are 0 and
+/// settled cells hold their piece's kind plus one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TetrisState {
+    pub board: [[u8; WIDTH]; HEIGHT],
+    pub piece: FallingPiece,
+    /// Pieces left in the current bag, drawn from the back
+    pub bag: Vec<u8>,
+    pub score: u32,
+    pub lines: u32,
+    pub pieces_placed: u32,
+    /// Ticks since the piece last fell a row
+    pub fall_timer: u32,
+    pub is_over: bool,
+}
+
+impl TetrisState {
+    /// An empty board with the first piece falling
+    pub fn new(rng: &mut SeededRng) -> Self {
+        let mut game = Self {
+            board: [[0; WIDTH]; HEIGHT],
+            piece: FallingPiece::spawn(0),
+            bag: Vec::new(),
+            score: 0,
+            lines: 0,
+            pieces_placed: 0,
+            fall_timer: 0,
+            is_over: false,
+        };
+        game.spawn_piece(rng);
+        game
+    }
+    
+    pub fn level(&self) -> u32 {
+        self.lines / LINES_PER_LEVEL
+    }
+    
+    /// Ticks the piece takes to fall a row at the current level
+    pub fn fall_ticks(&self) -> u32 {
+        INITIAL_FALL_TICKS.saturating_sub(self.level() * 4).max(1)
+    }
+    
+    /// Apply an input to the falling piece
+    pub fn apply(&mut self, action: TetrisAction, rng: &mut SeededRng) {
+        if self.is_over {
+            return;
+        }
+        let piece = self.piece;
+        match action {
+            TetrisAction::Left => {
+                self.try_move(piece.shifted(-1, 0));
+            }
+            TetrisAction::Right => {
+                self.try_move(piece.shifted(1, 0));
+            }
+            TetrisAction::RotateClockwise => {
+                self.try_move(piece.rotated(1));
+            }
+            TetrisAction::RotateCounterClockwise => {
+                self.try_move(piece.rotated(3));
+            }
+            TetrisAction::SoftDrop => {
+                if self.try_move(piece.shifted(0, 1)) {
+                    self.score += 1;
+                } else {
+                    self.lock(rng);
+                }
+            }
+            TetrisAction::HardDrop => {
+                while self.try_move(self.piece.shifted(0, 1)) {
+                    self.score += 2;
+                }
+                self.lock(rng);
+            }
+        }
+    }
+    
+    /// Advance gravity by one tick. Returns `false` once the game is over.
+    pub fn step(&mut self, rng: &mut SeededRng) -> bool {
+        if self.is_over {
+            return false;
+        }
+        self.fall_timer += 1;
+        if self.fall_timer >= self.fall_ticks() {
+            self.fall_timer = 0;
+            if !self.try_move(self.piece.shifted(0, 1)) {
+                self.lock(rng);
+            }
+        }
+        !self.is_over
+    }
+    
+    fn fits(&self, piece: &FallingPiece) -> bool {
+        piece.cells().iter().all(|&(col, row)| {
+            (0..WIDTH as i8).contains(&col)
+                && (0..HEIGHT as i8).contains(&row)
+                && self.board[row as usize][col as usize] == 0
+        })
+    }
+    
+    /// Move the piece to `piece` if it fits there
+    fn try_move(&mut self, piece: FallingPiece) -> bool {
+        let fits = self.fits(&piece);
+        if fits {
+            self.piece = piece;
+        }
+        fits
+    }
+    
+    /// Settle the piece, clear any full lines and bring on the next piece
+    fn lock(&mut self, rng: &mut SeededRng) {
+        for (col, row) in self.piece.cells() {
+            self.board[row as usize][col as usize] = self.piece.kind + 1;
+        }
+        self.pieces_placed += 1;
+        
+        let remaining: Vec<[u8; WIDTH]> =
+            self.board.iter().filter(|row| row.contains(&0)).copied().collect();
+        let cleared = HEIGHT - remaining.len();
+        if cleared > 0 {
+            self.score += LINE_POINTS[cleared - 1] * (self.level() + 1);
+            self.lines += cleared as u32;
+            self.board = [[0; WIDTH]; HEIGHT];
+            self.board[cleared..].copy_from_slice(&remaining);
+        }
+        self.spawn_piece(rng);
+    }
+    
+    /// Bring on the next piece from the bag. The game is over if it doesn't fit.
+    fn spawn_piece(&mut self, rng: &mut SeededRng) {
+        if self.bag.is_empty() {
+            self.bag = (0..SHAPES.len() as u8).collect();
+            for i in (1..self.bag.len()).rev() {
+                self.bag.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+        }
+        let kind = self.bag.pop().expect("bag was just refilled");
+        self.piece = FallingPiece::spawn(kind);
+        self.fall_timer = 0;
+        self.is_over = !self.fits(&self.piece);
+    }
+}
+
+/// Replay a game from its seed and inputs and return the final state.
+///
+/// Inputs must be in tick order; several may share a tick. The game ends
+/// when a new piece can't enter the board, or after `MAX_REPLAY_TICKS`.
+pub fn replay(seed: u64, inputs: &[TetrisInput]) -> Result<TetrisState, MoveError> {
+    if inputs.windows(2).any(|pair| pair[0].tick > pair[1].tick) {
+        return Err(MoveError::IllegalMove);
+    }
+    
+    let mut rng = SeededRng::new(seed);
+    let mut game = TetrisState::new(&mut rng);
+    let mut inputs = inputs.iter().peekable();
+    for tick in 0..MAX_REPLAY_TICKS {
+        while let Some(input) = inputs.next_if(|input| input.tick == tick) {
+            game.apply(input.action, &mut rng);
+        }
+        if !game.step(&mut rng) {
+            break;
+        }
+    }
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A game with the bottom `rows` rows filled but for the rightmost
+    /// column, and an upright I piece above the gap
+    fn well(rows: usize) -> (TetrisState, SeededRng) {
+        let mut rng = SeededRng::new(7);
+        let mut game = TetrisState::new(&mut rng);
+        for row in &mut game.board[HEIGHT - rows..] {
+            row[..WIDTH - 1].fill(3);
+        }
+        game.piece = FallingPiece { kind: 0, rotation: 1, x: WIDTH as i8 - 3, y: 0 };
+        assert!(game.piece.cells().iter().all(|&(col, _)| col == WIDTH as i8 - 1));
+        (game, rng)
+    }
+    
+    #[test]
+    fn clearing_four_lines_scores_a_tetris() {
+        let (mut game, mut rng) = well(4);
+        game.apply(TetrisAction::HardDrop, &mut rng);
+        
+        // Two points for each of the 16 rows dropped, then the four lines
+        assert_eq!(game.score, 2 * 16 + LINE_POINTS[3]);
+        assert_eq!(game.lines, 4);
+        assert_eq!(game.pieces_placed, 1);
+        assert!(game.board.iter().flatten().all(|&cell| cell == 0));
+    }
+    
+    #[test]
+    fn rows_above_a_cleared_line_fall() {
+        let (mut game, mut rng) = well(1);
+        game.board[HEIGHT - 2][0] = 5;
+        game.apply(TetrisAction::HardDrop, &mut rng);
+        
+        assert_eq!(game.score, 2 * 16 + LINE_POINTS[0]);
+        assert_eq!(game.lines, 1);
+        // The rest of the I piece and the lone block each moved down a row
+        let mut expected = [[0; WIDTH]; HEIGHT];
+        expected[HEIGHT - 1][0] = 5;
+        expected[HEIGHT - 1][WIDTH - 1] = 1;
+        expected[HEIGHT - 2][WIDTH - 1] = 1;
+        expected[HEIGHT - 3][WIDTH - 1] = 1;
+        assert_eq!(game.board, expected);
+    }
+    
+    #[test]
+    fn line_points_scale_with_level() {
+        let (mut game, mut rng) = well(2);
+        game.lines = LINES_PER_LEVEL;
+        game.apply(TetrisAction::HardDrop, &mut rng);
+        
+        assert_eq!(game.score, 2 * 16 + LINE_POINTS[1] * 2);
+        assert_eq!(game.lines, LINES_PER_LEVEL + 2);
+    }
+    
+    #[test]
+    fn incomplete_rows_stay() {
+        let (mut game, mut rng) = well(2);
+        game.board[HEIGHT - 1][0] = 0;
+        game.apply(TetrisAction::HardDrop, &mut rng);
+        
+        assert_eq!(game.lines, 1);
+        assert_eq!(game.board[HEIGHT - 1][0], 0);
+        assert_eq!(game.board[HEIGHT - 1][1], 3);
+    }
+}
@@ -0,0 +1,93 @@
+//! Tic-Tac-Toe engine
+
+use super::{GameOutcome, MoveError};
+use crate::{PlayerMark, TicTacToeState};
+
+/// All eight winning lines as (row, col) triples
+const LINES: [[(usize, usize); 3]; 8] = [
+    [(0, 0), (0, 1), (0, 2)],
+    [(1, 0), (1, 1), (1, 2)],
+    [(2, 0), (2, 1), (2, 2)],
+    [(0, 0), (1, 0), (2, 0)],
+    [(0, 1), (1, 1), (2, 1)],
+    [(0, 2), (1, 2), (2, 2)],
+    [(0, 0), (1, 1), (2, 2)],
+    [(0, 2), (1, 1), (2, 0)],
+];
+
+impl PlayerMark {
+    /// Index of the player holding this mark in the room's player list
+    pub fn player_index(self) -> usize {
+        match self {
+            PlayerMark::X => 0,
+            PlayerMark::O => 1,
+        }
+    }
+    
+    /// Mark held by the player at `index`, if any
+    pub fn from_player_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(PlayerMark::X),
+            1 => Some(PlayerMark::O),
+            _ => None,
+        }
+    }
+    
+    pub fn opponent(self) -> Self {
+        match self {
+            PlayerMark::X => PlayerMark::O,
+            PlayerMark::O => PlayerMark::X,
+        }
+    }
+}
+
+impl TicTacToeState {
+    /// Decode move data as `[row, col]`
+    pub fn decode_move(move_data: &[u8]) -> Result<(usize, usize), MoveError> {
+        match move_data {
+            [row, col] => Ok((*row as usize, *col as usize)),
+            _ => Err(MoveError::InvalidEncoding),
+        }
+    }
+    
+    /// Place `mark` at (`row`, `col`), enforcing turn order and empty cells
+    pub fn apply_move(
+        &mut self,
+        mark: PlayerMark,
+        row: usize,
+        col: usize,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.winner.is_some() || self.move_count >= 9 {
+            return Err(MoveError::GameOver);
+        }
+        if mark != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        if row >= 3 || col >= 3 {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.board[row][col].is_some() {
+            return Err(MoveError::CellOccupied);
+        }
+        
+        self.board[row][col] = Some(mark);
+        self.move_count += 1;
+        
+        if self.has_line(mark) {
+            self.winner = Some(mark);
+            return Ok(GameOutcome::Winner(mark.player_index()));
+        }
+        if self.move_count == 9 {
+            return Ok(GameOutcome::Draw);
+        }
+        
+        self.current_turn = mark.opponent();
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn has_line(&self, mark: PlayerMark) -> bool {
+        LINES
+            .iter()
+            .any(|line| line.iter().all(|&(r, c)| self.board[r][c] == Some(mark)))
+    }
+}
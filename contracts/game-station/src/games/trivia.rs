@@ -0,0 +1,215 @@
+//! Trivia engine
+//!
+//! Each round shows every player the same question from the admin-curated
+//! bank. Players commit to a salted hash of their answer, and nobody
+//! reveals until everyone has committed, so an answer sitting on-chain
+//! can't be copied. A correct answer scores points plus a bonus for
+//! answering quickly, timed from when the question was shown to when the
+//! answer was committed. After the last round the highest score wins.
+
+use serde::{Deserialize, Serialize};
+
+use super::{commitment, GameOutcome, MoveError};
+
+/// Questions in a game
+pub const ROUNDS: usize = 5;
+/// Time after a question is shown, in microseconds, within which a correct
+/// answer scores
+pub const ANSWER_WINDOW: u64 = 20_000_000;
+/// Points for a correct answer
+pub const CORRECT_POINTS: u32 = 100;
+/// Bonus for an instant correct answer, shrinking to nothing by the end of
+/// the answer window
+pub const MAX_SPEED_BONUS: u32 = 100;
+
+/// Fewest choices a question can offer
+pub const MIN_CHOICES: usize = 2;
+/// Most choices a question can offer
+pub const MAX_CHOICES: usize = 6;
+/// Longest prompt or choice text, in bytes
+pub const MAX_TEXT_LEN: usize = 280;
+
+/// A question in the bank
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriviaQuestion {
+    pub prompt: String,
+    pub choices: Vec<String>,
+    /// Index of the correct choice
+    pub answer: u8,
+}
+
+impl TriviaQuestion {
+    /// Whether the question is well formed enough to go in the bank
+    pub fn is_valid(&self) -> bool {
+        (MIN_CHOICES..=MAX_CHOICES).contains(&self.choices.len())
+            && (self.answer as usize) < self.choices.len()
+            && !self.prompt.is_empty()
+            && self.prompt.len() <= MAX_TEXT_LEN
+            && self.choices.iter().all(|choice| !choice.is_empty() && choice.len() <= MAX_TEXT_LEN)
+    }
+}
+
+/// Commitment a player submits for an answer
+pub fn answer_commitment(answer: u8, salt: &[u8]) -> [u8; 32] {
+    commitment(&[&[answer], salt])
+}
+
+/// Moves a player can make in a Trivia room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TriviaMove {
+    /// Commit to an answer to the current question, `answer_commitment(answer, salt)`
+    Commit { commitment: [u8; 32] },
+    /// Reveal the committed answer once every player has committed
+    Reveal { answer: u8, salt: Vec<u8> },
+}
+
+/// A player's answer to the current question
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriviaAnswer {
+    pub commitment: [u8; 32],
+    pub committed_at: u64,
+    /// Set once revealed
+    pub answer: Option<u8>,
+}
+
+/// Trivia game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriviaState {
+    /// Questions drawn for this game, empty until the game's first move
+    pub questions: Vec<TriviaQuestion>,
+    /// Index of the question being answered
+    pub round: usize,
+    /// When the current question was shown
+    pub round_started_at: u64,
+    /// Each player's answer to the current question
+    pub answers: Vec<Option<TriviaAnswer>>,
+    pub scores: Vec<u32>,
+    pub move_count: u32,
+    /// Set once the game has ended
+    pub result: Option<GameOutcome>,
+}
+
+impl TriviaState {
+    /// New game for `players` players, waiting on its questions
+    pub fn new(players: usize) -> Self {
+        Self {
+            questions: Vec::new(),
+            round: 0,
+            round_started_at: 0,
+            answers: vec![None; players],
+            scores: vec![0; players],
+            move_count: 0,
+            result: None,
+        }
+    }
+    
+    /// Whether questions have been drawn and the first one shown
+    pub fn is_started(&self) -> bool {
+        !self.questions.is_empty()
+    }
+    
+    /// Show the first of `questions` at `now`
+    pub fn start(&mut self, questions: Vec<TriviaQuestion>, now: u64) {
+        self.questions = questions;
+        self.round_started_at = now;
+    }
+    
+    /// The question being answered
+    pub fn current_question(&self) -> Option<&TriviaQuestion> {
+        self.questions.get(self.round)
+    }
+    
+    /// First player holding up the round: one yet to commit, or once
+    /// everyone has, one yet to reveal
+    pub fn waiting_on(&self) -> Option<usize> {
+        if !self.is_started() || self.result.is_some() {
+            return None;
+        }
+        let uncommitted = self.answers.iter().position(Option::is_none);
+        uncommitted.or_else(|| {
+            self.answers.iter().position(|answer| answer.is_some_and(|a| a.answer.is_none()))
+        })
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<TriviaMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move `player` made at `now`
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        mv: TriviaMove,
+        now: u64,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        let Some(question) = self.current_question() else {
+            return Err(MoveError::IllegalMove);
+        };
+        let correct = question.answer;
+        if player >= self.answers.len() {
+            return Err(MoveError::NotYourTurn);
+        }
+        
+        match mv {
+            TriviaMove::Commit { commitment } => {
+                if self.answers[player].is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                self.answers[player] = Some(TriviaAnswer {
+                    commitment,
+                    committed_at: now,
+                    answer: None,
+                });
+            }
+            TriviaMove::Reveal { answer, salt } => {
+                if self.answers.iter().any(Option::is_none) {
+                    return Err(MoveError::IllegalMove);
+                }
+                let Some(entry) = self.answers[player].as_mut() else {
+                    return Err(MoveError::IllegalMove);
+                };
+                if entry.answer.is_some() {
+                    return Err(MoveError::IllegalMove);
+                }
+                if entry.commitment != answer_commitment(answer, &salt) {
+                    return Err(MoveError::CommitmentMismatch);
+                }
+                entry.answer = Some(answer);
+                
+                let elapsed = entry.committed_at.saturating_sub(self.round_started_at);
+                if answer == correct && elapsed < ANSWER_WINDOW {
+                    let remaining = ANSWER_WINDOW - elapsed;
+                    let bonus = (MAX_SPEED_BONUS as u64 * remaining / ANSWER_WINDOW) as u32;
+                    self.scores[player] += CORRECT_POINTS + bonus;
+                }
+            }
+        }
+        self.move_count += 1;
+        
+        let revealed = |answer: &Option<TriviaAnswer>| answer.is_some_and(|a| a.answer.is_some());
+        if !self.answers.iter().all(revealed) {
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        // Everyone has revealed, so on to the next question
+        self.round += 1;
+        self.round_started_at = now;
+        self.answers = vec![None; self.answers.len()];
+        if self.round < self.questions.len() {
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        let best = self.scores.iter().copied().max().unwrap_or(0);
+        let leaders: Vec<usize> =
+            (0..self.scores.len()).filter(|&p| self.scores[p] == best).collect();
+        let outcome = match leaders[..] {
+            [winner] => GameOutcome::Winner(winner),
+            _ => GameOutcome::Draw,
+        };
+        self.result = Some(outcome);
+        Ok(outcome)
+    }
+}
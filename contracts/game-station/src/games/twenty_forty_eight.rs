@@ -0,0 +1,128 @@
+//! 2048 engine
+//!
+//! Scores are verified by replaying the session: the contract picks the seed
+//! new tiles are spawned with when the session starts, the client submits
+//! every slide it made, and the contract re-runs the game from that seed.
+
+use serde::{Deserialize, Serialize};
+
+use super::{randomness::SeededRng, MoveError};
+use crate::Direction;
+
+/// Width and height of the board
+pub const SIZE: usize = 4;
+
+/// Longest session that will be replayed, to bound execution cost
+pub const MAX_REPLAY_MOVES: usize = 20_000;
+
+/// 2048 game state. Row 0 is the top of the board; empty cells are 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwentyFortyEightState {
+    pub board: [[u32; SIZE]; SIZE],
+    /// Sum of the tiles created by merges
+    pub score: u32,
+    /// Slides that moved at least one tile
+    pub move_count: u32,
+}
+
+impl TwentyFortyEightState {
+    /// A fresh board with its two starting tiles
+    pub fn new(rng: &mut SeededRng) -> Self {
+        let mut game = Self {
+            board: [[0; SIZE]; SIZE],
+            score: 0,
+            move_count: 0,
+        };
+        game.spawn_tile(rng);
+        game.spawn_tile(rng);
+        game
+    }
+    
+    /// Largest tile on the board
+    pub fn max_tile(&self) -> u32 {
+        self.board.iter().flatten().copied().max().unwrap_or(0)
+    }
+    
+    /// Whether any slide would still move a tile
+    pub fn can_move(&self) -> bool {
+        [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+            .into_iter()
+            .any(|direction| self.clone().slide(direction))
+    }
+    
+    /// Slide every tile towards `direction`, merging equal neighbours once.
+    /// Returns `false` if nothing moved.
+    pub fn slide(&mut self, direction: Direction) -> bool {
+        let mut moved = false;
+        for line in 0..SIZE {
+            // Cells of this line, starting from the edge tiles slide towards
+            let cells: [(usize, usize); SIZE] = std::array::from_fn(|i| match direction {
+                Direction::Left => (line, i),
+                Direction::Right => (line, SIZE - 1 - i),
+                Direction::Up => (i, line),
+                Direction::Down => (SIZE - 1 - i, line),
+            });
+            
+            let mut merged = [0; SIZE];
+            let mut len = 0;
+            let mut can_merge = false;
+            for &(row, col) in &cells {
+                let tile = self.board[row][col];
+                if tile == 0 {
+                    continue;
+                }
+                if can_merge && merged[len - 1] == tile {
+                    merged[len - 1] *= 2;
+                    self.score += tile * 2;
+                    can_merge = false;
+                } else {
+                    merged[len] = tile;
+                    len += 1;
+                    can_merge = true;
+                }
+            }
+            
+            for (&(row, col), tile) in cells.iter().zip(merged) {
+                moved |= self.board[row][col] != tile;
+                self.board[row][col] = tile;
+            }
+        }
+        moved
+    }
+    
+    /// Put a 2, or one time in ten a 4, on a random empty cell
+    fn spawn_tile(&mut self, rng: &mut SeededRng) {
+        let empty: Vec<(usize, usize)> = (0..SIZE)
+            .flat_map(|row| (0..SIZE).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.board[row][col] == 0)
+            .collect();
+        if empty.is_empty() {
+            return;
+        }
+        let (row, col) = empty[rng.below(empty.len() as u64) as usize];
+        self.board[row][col] = if rng.below(10) == 0 { 4 } else { 2 };
+    }
+}
+
+/// Replay a session from its seed and slides and return the final state.
+///
+/// Like the web client, a slide that moves nothing is ignored and spawns no
+/// tile. Slides after the board has locked up are rejected.
+pub fn replay(seed: u64, moves: &[Direction]) -> Result<TwentyFortyEightState, MoveError> {
+    if moves.len() > MAX_REPLAY_MOVES {
+        return Err(MoveError::IllegalMove);
+    }
+    
+    let mut rng = SeededRng::new(seed);
+    let mut game = TwentyFortyEightState::new(&mut rng);
+    for &direction in moves {
+        if !game.can_move() {
+            return Err(MoveError::GameOver);
+        }
+        if game.slide(direction) {
+            game.move_count += 1;
+            game.spawn_tile(&mut rng);
+        }
+    }
+    Ok(game)
+}
@@ -0,0 +1,525 @@
+//! Uno engine with hidden hands
+//!
+//! Hands are never stored in the clear. Each player commits to a secret hand
+//! seed before the deal, along with a salt for the deal entropy. Once every
+//! commitment is in, the salts are revealed and hashed into the entropy, and a
+//! player's `n`th card is drawn from the deck using their seed and that
+//! entropy. Opponents only see the commitment, so they cannot work out each
+//! other's hands. The contract checks each play against the discard pile
+//! immediately. When a player empties their hand they reveal their seed, and
+//! once their plays check out every other player must reveal theirs too, so
+//! every claimed card is audited against the derivation. A player who claimed
+//! cards they were never dealt is disqualified.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    commitment,
+    randomness::{CommitReveal, SeededRng},
+    GameOutcome, MoveError,
+};
+
+/// Cards dealt to each player at the start
+pub const STARTING_HAND: u32 = 7;
+
+/// Cards in a standard Uno deck
+pub const DECK_SIZE: u64 = 108;
+
+/// Card colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnoColor {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+}
+
+const COLORS: [UnoColor; 4] = [UnoColor::Red, UnoColor::Yellow, UnoColor::Green, UnoColor::Blue];
+
+/// An Uno card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnoCard {
+    Number(UnoColor, u8),
+    Skip(UnoColor),
+    Reverse(UnoColor),
+    DrawTwo(UnoColor),
+    Wild,
+    WildDrawFour,
+}
+
+impl UnoCard {
+    /// Card at `position` in a standard deck: for each color one 0 and two
+    /// of every other number, skip, reverse and draw two, then four wilds
+    /// and four wild draw fours
+    fn from_deck_position(position: u64) -> Self {
+        match position {
+            100..=103 => UnoCard::Wild,
+            104.. => UnoCard::WildDrawFour,
+            _ => {
+                let color = COLORS[(position / 25) as usize];
+                match position % 25 {
+                    0 => UnoCard::Number(color, 0),
+                    n @ 1..=18 => UnoCard::Number(color, n.div_ceil(2) as u8),
+                    19 | 20 => UnoCard::Skip(color),
+                    21 | 22 => UnoCard::Reverse(color),
+                    _ => UnoCard::DrawTwo(color),
+                }
+            }
+        }
+    }
+    
+    /// Derive a player's `index`th card from their hand seed and the deal entropy
+    pub fn derive(seed: &[u8], entropy: u64, index: u32) -> Self {
+        let hash = commitment(&[seed, &entropy.to_le_bytes(), &index.to_le_bytes()]);
+        let mut rng = SeededRng::new(u64::from_le_bytes(
+            hash[..8].try_into().expect("hash has at least 8 bytes"),
+        ));
+        Self::from_deck_position(rng.below(DECK_SIZE))
+    }
+    
+    pub fn color(self) -> Option<UnoColor> {
+        match self {
+            UnoCard::Number(c, _) | UnoCard::Skip(c) | UnoCard::Reverse(c) | UnoCard::DrawTwo(c) => Some(c),
+            UnoCard::Wild | UnoCard::WildDrawFour => None,
+        }
+    }
+    
+    /// Whether this card can be played on `top` while `color` is active
+    pub fn playable_on(self, top: UnoCard, color: UnoColor) -> bool {
+        if self.color().is_none() || self.color() == Some(color) {
+            return true;
+        }
+        match (self, top) {
+            (UnoCard::Number(_, a), UnoCard::Number(_, b)) => a == b,
+            (UnoCard::Skip(_), UnoCard::Skip(_))
+            | (UnoCard::Reverse(_), UnoCard::Reverse(_))
+            | (UnoCard::DrawTwo(_), UnoCard::DrawTwo(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Moves a player can make in an Uno room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UnoMove {
+    /// Commit to a secret hand seed, and a salt for the deal, before the deal
+    CommitSeed {
+        commitment: [u8; 32],
+        salt_commitment: [u8; 32],
+    },
+    /// Reveal the committed deal salt once everyone has committed
+    RevealSalt { salt: Vec<u8> },
+    /// Play the card at derivation `index`, choosing a color for wilds
+    Play {
+        index: u32,
+        card: UnoCard,
+        chosen_color: Option<UnoColor>,
+    },
+    /// Draw a card and end the turn
+    Draw,
+    /// Reveal the hand seed so claimed plays can be audited, once a player
+    /// has emptied their hand
+    RevealSeed { seed: Vec<u8> },
+}
+
+/// Uno game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnoState {
+    /// Hand seed commitment per player
+    pub seed_commitments: Vec<Option<[u8; 32]>>,
+    /// Salts from every player seed the deal entropy
+    pub deal: CommitReveal,
+    /// Deal entropy, fixed once every deal salt has been revealed
+    pub entropy: Option<u64>,
+    /// Cards currently held per player
+    pub hand_counts: Vec<u32>,
+    /// Cards derived so far per player; the next draw uses this index
+    pub cards_dealt: Vec<u32>,
+    /// Claimed plays per player as (derivation index, card), audited on reveal
+    pub played: Vec<Vec<(u32, UnoCard)>>,
+    pub top_card: Option<UnoCard>,
+    pub current_color: UnoColor,
+    pub current_turn: usize,
+    pub clockwise: bool,
+    /// Player who emptied their hand and must reveal to confirm the win
+    pub pending_winner: Option<usize>,
+    /// Players whose seed has been revealed and whose plays checked out
+    pub audited: Vec<bool>,
+    pub disqualified: Vec<bool>,
+    pub winner: Option<usize>,
+    pub move_count: u32,
+}
+
+impl UnoState {
+    pub fn new(players: usize) -> Self {
+        Self {
+            seed_commitments: vec![None; players],
+            deal: CommitReveal::new(players),
+            entropy: None,
+            hand_counts: vec![STARTING_HAND; players],
+            cards_dealt: vec![STARTING_HAND; players],
+            played: vec![Vec::new(); players],
+            top_card: None,
+            current_color: UnoColor::Red,
+            current_turn: 0,
+            clockwise: true,
+            pending_winner: None,
+            audited: vec![false; players],
+            disqualified: vec![false; players],
+            winner: None,
+            move_count: 0,
+        }
+    }
+    
+    /// Whether the deal has happened and turns are being taken
+    pub fn is_started(&self) -> bool {
+        self.entropy.is_some()
+    }
+    
+    /// The player who emptied their hand and passed the audit, while the
+    /// other players are still revealing
+    pub fn confirmed_winner(&self) -> Option<usize> {
+        self.pending_winner.filter(|&player| self.audited[player])
+    }
+    
+    /// Players holding up a phase nobody takes turns in: those yet to commit
+    /// or reveal a deal salt, a winner yet to reveal their seed, and then
+    /// everyone else yet to reveal theirs
+    pub fn stalled(&self) -> Vec<usize> {
+        if !self.is_started() {
+            return self.deal.stalled();
+        }
+        match self.pending_winner {
+            Some(player) if !self.audited[player] => vec![player],
+            Some(_) => (0..self.audited.len())
+                .filter(|&p| !self.audited[p] && !self.disqualified[p])
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<UnoMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        uno_move: UnoMove,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.winner.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        if player >= self.hand_counts.len() || self.disqualified[player] {
+            return Err(MoveError::NotYourTurn);
+        }
+        
+        match uno_move {
+            UnoMove::CommitSeed { commitment, salt_commitment } => {
+                self.commit_seed(player, commitment, salt_commitment)
+            }
+            UnoMove::RevealSalt { salt } => self.reveal_salt(player, salt),
+            UnoMove::Play { index, card, chosen_color } => self.play(player, index, card, chosen_color),
+            UnoMove::Draw => self.draw(player),
+            UnoMove::RevealSeed { seed } => self.reveal_seed(player, &seed),
+        }
+    }
+    
+    fn commit_seed(
+        &mut self,
+        player: usize,
+        commitment: [u8; 32],
+        salt_commitment: [u8; 32],
+    ) -> Result<GameOutcome, MoveError> {
+        if self.is_started() || self.seed_commitments[player].is_some() {
+            return Err(MoveError::IllegalMove);
+        }
+        self.deal.commit(player, salt_commitment)?;
+        self.seed_commitments[player] = Some(commitment);
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn reveal_salt(&mut self, player: usize, salt: Vec<u8>) -> Result<GameOutcome, MoveError> {
+        self.deal.reveal(player, salt)?;
+        
+        if let Some(entropy) = self.deal.seed() {
+            // Every salt is in; fix the deal and flip a number card to start
+            self.entropy = Some(entropy);
+            let mut index = 0;
+            let top = loop {
+                let card = UnoCard::derive(b"discard", entropy, index);
+                if matches!(card, UnoCard::Number(..)) {
+                    break card;
+                }
+                index += 1;
+            };
+            self.top_card = Some(top);
+            self.current_color = top.color().unwrap_or(UnoColor::Red);
+        }
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn play(
+        &mut self,
+        player: usize,
+        index: u32,
+        card: UnoCard,
+        chosen_color: Option<UnoColor>,
+    ) -> Result<GameOutcome, MoveError> {
+        self.check_turn(player)?;
+        if index >= self.cards_dealt[player] || self.played[player].iter().any(|(i, _)| *i == index) {
+            return Err(MoveError::IllegalMove);
+        }
+        let top = self.top_card.ok_or(MoveError::IllegalMove)?;
+        if !card.playable_on(top, self.current_color) {
+            return Err(MoveError::IllegalMove);
+        }
+        let color = match card.color() {
+            Some(color) => color,
+            None => chosen_color.ok_or(MoveError::InvalidEncoding)?,
+        };
+        
+        self.played[player].push((index, card));
+        self.hand_counts[player] -= 1;
+        self.top_card = Some(card);
+        self.current_color = color;
+        self.move_count += 1;
+        
+        if self.hand_counts[player] == 0 {
+            // The win stands once the seed is revealed and the plays check out
+            self.pending_winner = Some(player);
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        match card {
+            UnoCard::Reverse(_) if self.active_players() > 2 => {
+                self.clockwise = !self.clockwise;
+                self.current_turn = self.next_player(player);
+            }
+            UnoCard::Skip(_) | UnoCard::Reverse(_) => {
+                let skipped = self.next_player(player);
+                self.current_turn = self.next_player(skipped);
+            }
+            UnoCard::DrawTwo(_) => self.penalise_next(player, 2),
+            UnoCard::WildDrawFour => self.penalise_next(player, 4),
+            UnoCard::Number(..) | UnoCard::Wild => self.current_turn = self.next_player(player),
+        }
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn draw(&mut self, player: usize) -> Result<GameOutcome, MoveError> {
+        self.check_turn(player)?;
+        self.cards_dealt[player] += 1;
+        self.hand_counts[player] += 1;
+        self.move_count += 1;
+        self.current_turn = self.next_player(player);
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    /// Audit a player's plays against their revealed seed. The player who
+    /// emptied their hand reveals first; once their win checks out, everyone
+    /// else must reveal too before it stands.
+    fn reveal_seed(&mut self, player: usize, seed: &[u8]) -> Result<GameOutcome, MoveError> {
+        let entropy = self.entropy.ok_or(MoveError::IllegalMove)?;
+        let Some(pending_winner) = self.pending_winner else {
+            return Err(MoveError::IllegalMove);
+        };
+        if self.audited[player] || (player != pending_winner && !self.audited[pending_winner]) {
+            return Err(MoveError::IllegalMove);
+        }
+        if self.seed_commitments[player] != Some(commitment(&[seed])) {
+            return Err(MoveError::CommitmentMismatch);
+        }
+        
+        let honest = self.played[player]
+            .iter()
+            .all(|(index, card)| UnoCard::derive(seed, entropy, *index) == *card);
+        
+        if honest {
+            self.audited[player] = true;
+        } else {
+            self.disqualified[player] = true;
+        }
+        
+        if !honest && player == pending_winner {
+            // Play goes on without the cheat, unless only one player is left
+            self.pending_winner = None;
+            if self.current_turn == player {
+                self.current_turn = self.next_player(player);
+            }
+            let remaining: Vec<usize> = (0..self.disqualified.len())
+                .filter(|&p| !self.disqualified[p])
+                .collect();
+            if let [last] = remaining[..] {
+                self.winner = Some(last);
+                return Ok(GameOutcome::Winner(last));
+            }
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        if self.stalled().is_empty() {
+            self.winner = Some(pending_winner);
+            return Ok(GameOutcome::Winner(pending_winner));
+        }
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn check_turn(&self, player: usize) -> Result<(), MoveError> {
+        if !self.is_started() || self.pending_winner.is_some() {
+            return Err(MoveError::IllegalMove);
+        }
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        Ok(())
+    }
+    
+    /// Next player after `from` in the current direction, skipping disqualified players
+    fn next_player(&self, from: usize) -> usize {
+        let count = self.hand_counts.len();
+        let mut next = from;
+        for _ in 0..count {
+            next = if self.clockwise { (next + 1) % count } else { (next + count - 1) % count };
+            if !self.disqualified[next] {
+                break;
+            }
+        }
+        next
+    }
+    
+    fn active_players(&self) -> usize {
+        self.disqualified.iter().filter(|d| !**d).count()
+    }
+    
+    /// Make the next player draw `cards` and skip their turn
+    fn penalise_next(&mut self, player: usize, cards: u32) {
+        let victim = self.next_player(player);
+        self.cards_dealt[victim] += cards;
+        self.hand_counts[victim] += cards;
+        self.current_turn = self.next_player(victim);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A game whose deal salts are all in, with each player's hand seed
+    fn dealt_game(players: usize) -> (UnoState, Vec<Vec<u8>>) {
+        let mut game = UnoState::new(players);
+        let seeds: Vec<Vec<u8>> = (0..players).map(|p| vec![b's', p as u8]).collect();
+        for (player, seed) in seeds.iter().enumerate() {
+            let commit = UnoMove::CommitSeed {
+                commitment: commitment(&[seed]),
+                salt_commitment: commitment(&[&[player as u8]]),
+            };
+            game.apply_move(player, commit).unwrap();
+        }
+        for player in 0..players {
+            game.apply_move(player, UnoMove::RevealSalt { salt: vec![player as u8] }).unwrap();
+        }
+        (game, seeds)
+    }
+    
+    /// Have `player` play their last card, claiming `card` at derivation index 0
+    fn play_last_card(game: &mut UnoState, player: usize, card: UnoCard) -> GameOutcome {
+        game.hand_counts[player] = 1;
+        game.current_turn = player;
+        game.top_card = Some(card);
+        game.current_color = card.color().unwrap_or(UnoColor::Red);
+        let play = UnoMove::Play { index: 0, card, chosen_color: Some(UnoColor::Red) };
+        game.apply_move(player, play).unwrap()
+    }
+    
+    #[test]
+    fn deck_has_the_standard_108_cards() {
+        let deck: Vec<UnoCard> = (0..DECK_SIZE).map(UnoCard::from_deck_position).collect();
+        let count = |is_kind: fn(&UnoCard) -> bool| {
+            deck.iter().filter(|card| is_kind(card)).count()
+        };
+        
+        assert_eq!(count(|card| matches!(card, UnoCard::Number(_, 0))), 4);
+        assert_eq!(count(|card| matches!(card, UnoCard::Number(_, 1..=9))), 72);
+        assert_eq!(count(|card| matches!(card, UnoCard::Skip(_))), 8);
+        assert_eq!(count(|card| matches!(card, UnoCard::Reverse(_))), 8);
+        assert_eq!(count(|card| matches!(card, UnoCard::DrawTwo(_))), 8);
+        assert_eq!(count(|card| *card == UnoCard::Wild), 4);
+        assert_eq!(count(|card| *card == UnoCard::WildDrawFour), 4);
+        for color in COLORS {
+            assert_eq!(deck.iter().filter(|card| card.color() == Some(color)).count(), 25);
+        }
+    }
+    
+    #[test]
+    fn deal_waits_on_every_salt() {
+        let mut game = UnoState::new(2);
+        let commit = UnoMove::CommitSeed {
+            commitment: commitment(&[b"seed"]),
+            salt_commitment: commitment(&[b"salt"]),
+        };
+        game.apply_move(0, commit.clone()).unwrap();
+        assert_eq!(game.stalled(), vec![1]);
+        assert_eq!(
+            game.apply_move(0, UnoMove::RevealSalt { salt: b"salt".to_vec() }),
+            Err(MoveError::IllegalMove)
+        );
+        
+        game.apply_move(1, commit).unwrap();
+        game.apply_move(0, UnoMove::RevealSalt { salt: b"salt".to_vec() }).unwrap();
+        assert!(!game.is_started());
+        assert_eq!(game.stalled(), vec![1]);
+        
+        game.apply_move(1, UnoMove::RevealSalt { salt: b"salt".to_vec() }).unwrap();
+        assert!(game.is_started());
+        assert!(game.stalled().is_empty());
+        assert!(matches!(game.top_card, Some(UnoCard::Number(..))));
+    }
+    
+    #[test]
+    fn win_stands_once_everyone_is_audited() {
+        let (mut game, seeds) = dealt_game(3);
+        let entropy = game.entropy.unwrap();
+        let card = UnoCard::derive(&seeds[0], entropy, 0);
+        assert_eq!(play_last_card(&mut game, 0, card), GameOutcome::Ongoing);
+        assert_eq!(game.stalled(), vec![0]);
+        
+        // Nobody else reveals until the winner's plays check out
+        let early = UnoMove::RevealSeed { seed: seeds[1].clone() };
+        assert_eq!(game.apply_move(1, early), Err(MoveError::IllegalMove));
+        
+        let reveal = UnoMove::RevealSeed { seed: seeds[0].clone() };
+        assert_eq!(game.apply_move(0, reveal), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.confirmed_winner(), Some(0));
+        assert_eq!(game.stalled(), vec![1, 2]);
+        
+        let reveal = UnoMove::RevealSeed { seed: seeds[1].clone() };
+        assert_eq!(game.apply_move(1, reveal), Ok(GameOutcome::Ongoing));
+        assert_eq!(game.stalled(), vec![2]);
+        let reveal = UnoMove::RevealSeed { seed: seeds[2].clone() };
+        assert_eq!(game.apply_move(2, reveal), Ok(GameOutcome::Winner(0)));
+    }
+    
+    #[test]
+    fn claiming_an_undealt_card_disqualifies() {
+        let (mut game, seeds) = dealt_game(2);
+        let dealt = UnoCard::derive(&seeds[0], game.entropy.unwrap(), 0);
+        let claimed = if dealt == UnoCard::Wild { UnoCard::WildDrawFour } else { UnoCard::Wild };
+        play_last_card(&mut game, 0, claimed);
+        
+        let reveal = UnoMove::RevealSeed { seed: seeds[0].clone() };
+        assert_eq!(game.apply_move(0, reveal), Ok(GameOutcome::Winner(1)));
+        assert!(game.disqualified[0]);
+    }
+    
+    #[test]
+    fn wrong_seed_is_rejected() {
+        let (mut game, seeds) = dealt_game(2);
+        let card = UnoCard::derive(&seeds[0], game.entropy.unwrap(), 0);
+        play_last_card(&mut game, 0, card);
+        
+        let reveal = UnoMove::RevealSeed { seed: seeds[1].clone() };
+        assert_eq!(game.apply_move(0, reveal), Err(MoveError::CommitmentMismatch));
+        assert_eq!(game.stalled(), vec![0]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Yahtzee engine
+//!
+//! Players take turns rolling five dice up to three times, holding any dice
+//! between rolls, then score the dice in one unused box of their scorecard.
+//! After thirteen turns each the highest total wins. A room of one seat is
+//! a solo game and ends in a draw, so it posts a score without a win.
+//!
+//! No one can load the dice, the contract included. Each player commits to a
+//! salt, the contract fixes its own entropy once every commitment is in, and
+//! each roll is derived from the revealed salts, that entropy and the number
+//! of rolls made so far.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    commitment,
+    randomness::{CommitReveal, SeededRng},
+    GameOutcome, MoveError,
+};
+
+/// Fewest players a room can seat
+pub const MIN_PLAYERS: u8 = 1;
+/// Most players a room can seat
+pub const MAX_PLAYERS: u8 = 4;
+
+/// Dice rolled each turn
+pub const DICE: usize = 5;
+/// Rolls a player may make each turn
+pub const ROLLS_PER_TURN: u8 = 3;
+/// Upper section total that earns the upper bonus
+pub const UPPER_BONUS_THRESHOLD: u16 = 63;
+pub const UPPER_BONUS: u16 = 35;
+/// Bonus for each Yahtzee after the first, once the Yahtzee box holds 50
+pub const YAHTZEE_BONUS: u16 = 100;
+
+/// A box on the scorecard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YahtzeeCategory {
+    Ones,
+    Twos,
+    Threes,
+    Fours,
+    Fives,
+    Sixes,
+    ThreeOfAKind,
+    FourOfAKind,
+    FullHouse,
+    SmallStraight,
+    LargeStraight,
+    Yahtzee,
+    Chance,
+}
+
+impl YahtzeeCategory {
+    /// Position of the box on the scorecard
+    pub fn index(self) -> usize {
+        self as usize
+    }
+    
+    /// What `dice` score in this box. A Yahtzee rolled once the Yahtzee box
+    /// is filled is a joker, scoring full points as a full house or straight.
+    pub fn score(self, dice: &[u8; DICE], joker: bool) -> u16 {
+        let mut counts = [0u8; 7];
+        for &die in dice {
+            counts[die as usize] += 1;
+        }
+        let total: u16 = dice.iter().map(|&die| die as u16).sum();
+        let most = counts.iter().copied().max().unwrap_or(0);
+        let full_house = counts.contains(&3) && counts.contains(&2);
+        let run = |len: usize| {
+            (1..=7 - len).any(|low| (low..low + len).all(|face| counts[face] > 0))
+        };
+        
+        match self {
+            YahtzeeCategory::Ones
+            | YahtzeeCategory::Twos
+            | YahtzeeCategory::Threes
+            | YahtzeeCategory::Fours
+            | YahtzeeCategory::Fives
+            | YahtzeeCategory::Sixes => {
+                let face = self.index() + 1;
+                counts[face] as u16 * face as u16
+            }
+            YahtzeeCategory::ThreeOfAKind if most >= 3 => total,
+            YahtzeeCategory::FourOfAKind if most >= 4 => total,
+            YahtzeeCategory::FullHouse if joker || full_house => 25,
+            YahtzeeCategory::SmallStraight if joker || run(4) => 30,
+            YahtzeeCategory::LargeStraight if joker || run(5) => 40,
+            YahtzeeCategory::Yahtzee if most == 5 => 50,
+            YahtzeeCategory::Chance => total,
+            _ => 0,
+        }
+    }
+}
+
+/// A player's scorecard
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scorecard {
+    /// Score in each box by `YahtzeeCategory::index`, `None` while unused
+    pub boxes: [Option<u16>; 13],
+    /// Bonuses earned for extra Yahtzees
+    pub yahtzee_bonus: u16,
+}
+
+impl Scorecard {
+    pub fn is_full(&self) -> bool {
+        self.boxes.iter().all(Option::is_some)
+    }
+    
+    /// Total of the six upper boxes, before the bonus
+    pub fn upper_total(&self) -> u16 {
+        self.boxes[..6].iter().flatten().sum()
+    }
+    
+    /// Final score so far, with both bonuses
+    pub fn total(&self) -> u16 {
+        let upper = self.upper_total();
+        let bonus = if upper >= UPPER_BONUS_THRESHOLD { UPPER_BONUS } else { 0 };
+        upper + bonus + self.boxes[6..].iter().flatten().sum::<u16>() + self.yahtzee_bonus
+    }
+}
+
+/// Moves a player can make in a Yahtzee room, BCS-encoded in `move_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum YahtzeeMove {
+    /// Commit to a dice salt before the game starts
+    CommitSalt { commitment: [u8; 32] },
+    /// Reveal the committed salt
+    RevealSalt { salt: Vec<u8> },
+    /// Roll every die not held. The first roll of a turn rolls all five.
+    Roll { hold: [bool; DICE] },
+    /// Score the dice in an unused box, ending the turn
+    Score { category: YahtzeeCategory },
+}
+
+/// Yahtzee game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YahtzeeState {
+    pub scorecards: Vec<Scorecard>,
+    /// Index of the player whose turn it is
+    pub current_turn: usize,
+    pub dice: [u8; DICE],
+    /// Rolls made so far this turn
+    pub turn_rolls: u8,
+    /// Rolls made so far this game, which picks each roll from the seed
+    pub rolls: u32,
+    /// Contract entropy, fixed once every player has committed
+    pub entropy: Option<u64>,
+    pub move_count: u32,
+    /// Set once every scorecard is full
+    pub result: Option<GameOutcome>,
+    /// Salts from every player seed the dice along with the entropy
+    pub salts: CommitReveal,
+}
+
+impl YahtzeeState {
+    /// New game for `players` players with blank scorecards
+    pub fn new(players: usize) -> Self {
+        Self {
+            scorecards: vec![Scorecard::default(); players],
+            current_turn: 0,
+            dice: [0; DICE],
+            turn_rolls: 0,
+            rolls: 0,
+            entropy: None,
+            move_count: 0,
+            result: None,
+            salts: CommitReveal::new(players),
+        }
+    }
+    
+    /// Whether the dice are seeded and turns are being taken
+    pub fn is_started(&self) -> bool {
+        self.salts.seed().is_some()
+    }
+    
+    /// Player holding up the game: one yet to commit or reveal before it
+    /// starts, then whoever's turn it is
+    pub fn waiting_on(&self) -> Option<usize> {
+        if self.result.is_some() {
+            return None;
+        }
+        if self.is_started() {
+            return Some(self.current_turn);
+        }
+        self.salts.pending().into_iter().find(|&p| {
+            self.salts.commitments[p].is_none() || self.salts.all_committed()
+        })
+    }
+    
+    pub fn decode_move(move_data: &[u8]) -> Result<YahtzeeMove, MoveError> {
+        linera_sdk::bcs::from_bytes(move_data).map_err(|_| MoveError::InvalidEncoding)
+    }
+    
+    /// Apply a move by `player`. `entropy` is fixed as the contract's share
+    /// of the dice when the last commitment arrives and ignored otherwise.
+    pub fn apply_move(
+        &mut self,
+        player: usize,
+        mv: YahtzeeMove,
+        entropy: u64,
+    ) -> Result<GameOutcome, MoveError> {
+        if self.result.is_some() {
+            return Err(MoveError::GameOver);
+        }
+        match mv {
+            YahtzeeMove::CommitSalt { commitment } => {
+                self.salts.commit(player, commitment)?;
+                if self.salts.all_committed() {
+                    self.entropy = Some(entropy);
+                }
+            }
+            YahtzeeMove::RevealSalt { salt } => self.salts.reveal(player, salt)?,
+            YahtzeeMove::Roll { hold } => self.roll(player, hold)?,
+            YahtzeeMove::Score { category } => return self.score(player, category),
+        }
+        self.move_count += 1;
+        Ok(GameOutcome::Ongoing)
+    }
+    
+    fn roll(&mut self, player: usize, hold: [bool; DICE]) -> Result<(), MoveError> {
+        let seed = self.turn_seed(player)?;
+        if self.turn_rolls >= ROLLS_PER_TURN {
+            return Err(MoveError::IllegalMove);
+        }
+        let hash = commitment(&[
+            b"yahtzee",
+            &seed.to_le_bytes(),
+            &self.entropy.unwrap_or_default().to_le_bytes(),
+            &self.rolls.to_le_bytes(),
+        ]);
+        let mut rng = SeededRng::new(u64::from_le_bytes(
+            hash[..8].try_into().expect("hash has at least 8 bytes"),
+        ));
+        for (die, held) in self.dice.iter_mut().zip(hold) {
+            if self.turn_rolls == 0 || !held {
+                *die = rng.below(6) as u8 + 1;
+            }
+        }
+        self.turn_rolls += 1;
+        self.rolls += 1;
+        Ok(())
+    }
+    
+    fn score(
+        &mut self,
+        player: usize,
+        category: YahtzeeCategory,
+    ) -> Result<GameOutcome, MoveError> {
+        self.turn_seed(player)?;
+        if self.turn_rolls == 0 {
+            return Err(MoveError::IllegalMove);
+        }
+        let card = &mut self.scorecards[player];
+        if card.boxes[category.index()].is_some() {
+            return Err(MoveError::CellOccupied);
+        }
+        
+        let yahtzee_box = card.boxes[YahtzeeCategory::Yahtzee.index()];
+        let joker = yahtzee_box.is_some() && YahtzeeCategory::Yahtzee.score(&self.dice, false) > 0;
+        if joker && yahtzee_box == Some(50) {
+            card.yahtzee_bonus += YAHTZEE_BONUS;
+        }
+        card.boxes[category.index()] = Some(category.score(&self.dice, joker));
+        
+        self.move_count += 1;
+        self.turn_rolls = 0;
+        self.dice = [0; DICE];
+        self.current_turn = (self.current_turn + 1) % self.scorecards.len();
+        if !self.scorecards.iter().all(Scorecard::is_full) {
+            return Ok(GameOutcome::Ongoing);
+        }
+        
+        let totals: Vec<u16> = self.scorecards.iter().map(Scorecard::total).collect();
+        let best = totals.iter().copied().max().unwrap_or(0);
+        let leaders: Vec<usize> = (0..totals.len()).filter(|&p| totals[p] == best).collect();
+        let outcome = match leaders[..] {
+            [winner] if totals.len() > 1 => GameOutcome::Winner(winner),
+            _ => GameOutcome::Draw,
+        };
+        self.result = Some(outcome);
+        Ok(outcome)
+    }
+    
+    /// Check that the game is under way and it is `player`'s turn, and
+    /// return the dice seed
+    fn turn_seed(&self, player: usize) -> Result<u64, MoveError> {
+        let seed = self.salts.seed().ok_or(MoveError::IllegalMove)?;
+        if player != self.current_turn {
+            return Err(MoveError::NotYourTurn);
+        }
+        Ok(seed)
+    }
+}
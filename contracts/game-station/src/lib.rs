@@ -4,25 +4,318 @@
 //! Linera Game Station smart contract. It includes all types for operations,
 //! messages, and queries.
 
-use linera_sdk::base::{AccountOwner, Amount, Timestamp};
+pub mod cosmetics;
+pub mod error;
+pub mod games;
+pub mod nft;
+pub mod quests;
+pub mod tournament;
+
+pub use cosmetics::CosmeticSlot;
+pub use error::GameStationError;
+pub use games::battleship::BattleshipState;
+pub use games::blackjack::{BlackjackAction, BlackjackResult};
+pub use games::checkers::CheckersState;
+pub use games::chess::ChessState;
+pub use games::connect_four::ConnectFourState;
+pub use games::dots_and_boxes::DotsAndBoxesState;
+pub use games::gomoku::GomokuState;
+pub use games::ludo::LudoState;
+pub use games::memory::MemoryState;
+pub use games::minesweeper::MinesweeperClick;
+pub use games::poker::PokerState;
+pub use games::reversi::ReversiState;
+pub use games::rock_paper_scissors::RockPaperScissorsState;
+pub use games::snake::SnakeTurn;
+pub use games::snake_ladders::SnakeLaddersState;
+pub use games::tetris::TetrisInput;
+pub use games::trivia::TriviaState;
+pub use games::uno::UnoState;
+pub use games::yahtzee::YahtzeeState;
+pub use nft::NftAvatar;
+pub use tournament::TournamentFormat;
+
+use linera_sdk::base::{Account, AccountOwner, Amount, ChainId, ContractAbi, ServiceAbi, Timestamp};
 use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, ops::RangeInclusive};
 use async_graphql::{InputObject, SimpleObject};
 
 /// The ABI for the Game Station application
-linera_sdk::graphql_service_interface!(GameStationAbi, GameStationState);
+pub struct GameStationAbi;
+
+impl ContractAbi for GameStationAbi {
+    type Operation = Operation;
+    type Response = Response;
+}
+
+impl ServiceAbi for GameStationAbi {
+    type Query = async_graphql::Request;
+    type QueryResponse = async_graphql::Response;
+}
+
+/// Layout of the stored state this build reads and writes. Bump it with a
+/// step in `GameStationState::migrate` whenever stored data changes shape.
+/// Version 0 is state written by builds of this layout before versioning.
+/// The original layout, with profiles and leaderboards keyed by address
+/// strings, predates it and is not migrated: those deployments have to be
+/// redeployed.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Free-play tokens granted per daily faucet claim
+pub const DAILY_FAUCET_ALLOWANCE: Amount = Amount::from_tokens(10);
+
+/// Maximum free-play tokens a single account can ever claim from the faucet
+pub const FAUCET_LIFETIME_CAP: Amount = Amount::from_tokens(300);
+
+/// Longest chat message accepted, in bytes
+pub const MAX_CHAT_MESSAGE_LEN: usize = 280;
+
+/// Chat messages kept per room before further messages are rejected
+pub const MAX_CHAT_MESSAGES_PER_ROOM: usize = 500;
+
+/// A player counts as online if they were active this recently (microseconds)
+pub const ONLINE_WINDOW: u64 = 5 * 60 * 1_000_000;
+
+/// Starting ELO rating for new players
+pub const DEFAULT_RATING: u32 = 1200;
+
+/// ELO K-factor: the most a rating can move in one match
+pub const RATING_K_FACTOR: f64 = 32.0;
+
+/// XP bonus per day of a login streak, granted once a day
+pub const STREAK_XP_PER_DAY: u64 = 10;
+
+/// Free-play tokens per day of a login streak, granted once a day
+pub const STREAK_TOKENS_PER_DAY: Amount = Amount::from_millis(100);
+
+/// Streak length after which the daily bonus stops growing
+pub const MAX_STREAK_BONUS_DAYS: u32 = 7;
+
+/// Free-play tokens for each tier of the season pass's free track
+pub const PASS_FREE_REWARD: Amount = Amount::from_millis(250);
+
+/// Free-play tokens per tier number on the premium track, so later tiers pay more
+pub const PASS_PREMIUM_REWARD_PER_TIER: Amount = Amount::from_millis(200);
+
+/// Recent matches kept in each player's history
+pub const MAX_MATCH_HISTORY: usize = 50;
+
+/// Recent encounters kept in each pair of players' head-to-head record
+pub const MAX_HEAD_TO_HEAD_ENCOUNTERS: usize = 10;
+
+/// Finished games kept in the recent games feed
+pub const MAX_RECENT_GAMES: usize = 100;
+
+/// Microseconds in a day, used to bucket `system_time` into days
+pub const MICROS_PER_DAY: u64 = 86_400_000_000;
+
+/// Microseconds in an hour
+pub const MICROS_PER_HOUR: u64 = 3_600_000_000;
+
+/// Longest reason accepted on a player report, in bytes
+pub const MAX_REPORT_REASON_LEN: usize = 500;
+
+/// Longest username prefix indexed for player search, in characters
+pub const MAX_SEARCH_PREFIX_LEN: usize = 8;
+
+/// Highest house rake the admin can set, in basis points (20%)
+pub const MAX_RAKE_BPS: u16 = 2_000;
+
+/// Arcade credits minted for winning a multiplayer match
+pub const CREDITS_PER_WIN: u64 = 10;
+
+/// Arcade credits minted for each completed daily quest
+pub const CREDITS_PER_QUEST: u64 = 25;
+
+/// Arcade credits minted for reaching a new prestige rank
+pub const CREDITS_PER_PRESTIGE: u64 = 500;
+
+/// Arcade credits burned to feature a room in the lobby
+pub const ROOM_BOOST_COST: u64 = 50;
+
+/// How long a boost keeps a room featured (microseconds)
+pub const ROOM_BOOST_DURATION: u64 = 30 * 60 * 1_000_000;
+
+/// Arcade credit transactions kept in each player's history
+pub const MAX_CREDIT_HISTORY: usize = 100;
+
+/// Tips kept in each player's history, sent and received together
+pub const MAX_TIP_HISTORY: usize = 100;
+
+/// Longest memo accepted on a tip, in bytes
+pub const MAX_TIP_MEMO_LEN: usize = 140;
+
+/// Room games a referred player must finish before the referral pays out
+pub const REFERRAL_GAMES_REQUIRED: u32 = 5;
+
+/// XP granted to both the referrer and the referee when a referral pays out
+pub const REFERRAL_XP: u64 = 100;
+
+/// Arcade credits minted to both the referrer and the referee when a referral pays out
+pub const REFERRAL_CREDITS: u64 = 100;
+
+/// Shortest and longest clan tag, in characters
+pub const MIN_CLAN_TAG_LEN: usize = 2;
+pub const MAX_CLAN_TAG_LEN: usize = 5;
+
+/// Longest clan name accepted, in bytes
+pub const MAX_CLAN_NAME_LEN: usize = 32;
+
+/// Most members a clan can have
+pub const MAX_CLAN_SIZE: usize = 50;
+
+/// Most players one player can block, and separately mute
+pub const MAX_BLOCK_LIST: usize = 200;
+
+/// Most friend requests a player can have waiting on them
+pub const MAX_PENDING_FRIEND_REQUESTS: usize = 100;
+
+/// A player's referral code: the first bytes of a hash of their address, in hex
+pub fn referral_code(owner: &AccountOwner) -> String {
+    games::commitment(&[b"referral", owner.to_string().as_bytes()])[..4]
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+/// Invite code hash kept for a private room, salted with the room's ID so
+/// hashes of short codes can't be looked up from a precomputed table
+pub fn invite_code_hash(room_id: &str, invite_code: &str) -> [u8; 32] {
+    games::commitment(&[b"invite", room_id.as_bytes(), invite_code.as_bytes()])
+}
+
+/// What a joiner sends to show they know a private room's invite code. It is
+/// bound to the joiner, so seeing it in their block doesn't let anyone else in.
+pub fn invite_proof(invite_code_hash: &[u8; 32], player: &AccountOwner) -> [u8; 32] {
+    games::commitment(&[b"invite-proof", invite_code_hash, player.to_string().as_bytes()])
+}
 
 /// Types of games supported by the Game Station
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, async_graphql::Enum)]
 pub enum GameType {
     Snake,
     TicTacToe,
     SnakeLadders,
     Uno,
+    ConnectFour,
+    Chess,
+    Checkers,
+    TwentyFortyEight,
+    Minesweeper,
+    Battleship,
+    RockPaperScissors,
+    Reversi,
+    DotsAndBoxes,
+    Gomoku,
+    Ludo,
+    Memory,
+    Trivia,
+    Sudoku,
+    Poker,
+    Blackjack,
+    DailyWord,
+    Yahtzee,
+    Tetris,
+}
+
+impl GameType {
+    pub const ALL: [GameType; 23] = [
+        GameType::Snake,
+        GameType::TicTacToe,
+        GameType::SnakeLadders,
+        GameType::Uno,
+        GameType::ConnectFour,
+        GameType::Chess,
+        GameType::Checkers,
+        GameType::TwentyFortyEight,
+        GameType::Minesweeper,
+        GameType::Battleship,
+        GameType::RockPaperScissors,
+        GameType::Reversi,
+        GameType::DotsAndBoxes,
+        GameType::Gomoku,
+        GameType::Ludo,
+        GameType::Memory,
+        GameType::Trivia,
+        GameType::Sudoku,
+        GameType::Poker,
+        GameType::Blackjack,
+        GameType::DailyWord,
+        GameType::Yahtzee,
+        GameType::Tetris,
+    ];
+    
+    /// Two-player games with ELO ratings
+    pub const RATED: [GameType; 7] = [
+        GameType::TicTacToe,
+        GameType::ConnectFour,
+        GameType::Chess,
+        GameType::Checkers,
+        GameType::Battleship,
+        GameType::Reversi,
+        GameType::Gomoku,
+    ];
+    
+    /// The game spelled `name` as in its `Debug` output, which is how game
+    /// types were stored before they were stored as themselves
+    pub fn from_name(name: &str) -> Option<GameType> {
+        Self::ALL.into_iter().find(|game_type| format!("{:?}", game_type) == name)
+    }
+    
+    /// Whether the game has ELO ratings and a rating leaderboard
+    pub fn is_rated(self) -> bool {
+        Self::RATED.contains(&self)
+    }
+    
+    /// Games played solo rather than in rooms, matchmaking or tournaments
+    pub fn is_single_player(self) -> bool {
+        matches!(
+            self,
+            GameType::Snake
+                | GameType::TwentyFortyEight
+                | GameType::Minesweeper
+                | GameType::Sudoku
+                | GameType::Blackjack
+                | GameType::DailyWord
+                | GameType::Tetris
+        )
+    }
+    
+    /// Two-player games whose rooms may carry a head-to-head wager
+    pub fn allows_wagers(self) -> bool {
+        matches!(self, GameType::TicTacToe | GameType::RockPaperScissors)
+    }
+    
+    /// Seats a room of this game may have, for games that limit them
+    pub fn seats(self) -> Option<RangeInclusive<u8>> {
+        match self {
+            GameType::DotsAndBoxes => {
+                Some(games::dots_and_boxes::MIN_PLAYERS..=games::dots_and_boxes::MAX_PLAYERS)
+            }
+            GameType::Ludo => Some(games::ludo::MIN_PLAYERS..=games::ludo::MAX_PLAYERS),
+            GameType::Memory => Some(games::memory::MIN_PLAYERS..=games::memory::MAX_PLAYERS),
+            // Heads-up only
+            GameType::Poker => Some(2..=2),
+            // A single seat is a solo game
+            GameType::Yahtzee => Some(games::yahtzee::MIN_PLAYERS..=games::yahtzee::MAX_PLAYERS),
+            _ => None,
+        }
+    }
+    
+    /// Whether a room of this game may choose `size` as its board size
+    pub fn allows_board_size(self, size: u8) -> bool {
+        match self {
+            GameType::DotsAndBoxes => {
+                (games::dots_and_boxes::MIN_SIZE..=games::dots_and_boxes::MAX_SIZE).contains(&size)
+            }
+            GameType::Gomoku => games::gomoku::SIZES.contains(&size),
+            _ => false,
+        }
+    }
 }
 
 /// Direction for Snake game
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
 pub enum Direction {
     Up,
     Down,
@@ -37,6 +330,14 @@ pub struct Position {
     pub y: i32,
 }
 
+/// A single-player session whose random seed the contract picked when it
+/// started, so the client can't choose a favourable one
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GameSession {
+    pub seed: u64,
+    pub started_at: u64,
+}
+
 /// Snake game state
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct SnakeGameState {
@@ -62,14 +363,14 @@ impl Default for SnakeGameState {
 }
 
 /// Tic-Tac-Toe player mark
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
 pub enum PlayerMark {
     X,
     O,
 }
 
 /// Tic-Tac-Toe game state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct TicTacToeState {
     pub board: [[Option<PlayerMark>; 3]; 3],
     pub current_turn: PlayerMark,
@@ -97,32 +398,405 @@ impl Default for TicTacToeState {
 pub enum GameState {
     Snake(SnakeGameState),
     TicTacToe(TicTacToeState),
+    SnakeLadders(SnakeLaddersState),
+    Uno(UnoState),
+    ConnectFour(ConnectFourState),
+    Chess(ChessState),
+    Checkers(CheckersState),
+    Battleship(BattleshipState),
+    RockPaperScissors(RockPaperScissorsState),
+    Reversi(ReversiState),
+    DotsAndBoxes(DotsAndBoxesState),
+    Gomoku(GomokuState),
+    Ludo(LudoState),
+    Memory(MemoryState),
+    Trivia(TriviaState),
+    Poker(PokerState),
+    Yahtzee(YahtzeeState),
 }
 
 /// Status of a game room
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
 pub enum RoomStatus {
     Waiting,
+    /// Full, and waiting for every player to confirm with `Operation::Ready`
+    ReadyCheck,
     InProgress,
     Finished,
 }
 
+/// Who can join a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum RoomVisibility {
+    /// Anyone can join
+    Public,
+    /// Only allowlisted players or holders of the invite code can join
+    Private,
+}
+
+/// A chat message posted in a room
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ChatMessage {
+    pub sender: AccountOwner,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+/// A report filed against a player for the admin to review
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerReport {
+    pub reporter: AccountOwner,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+/// The leaderboards a game can have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, async_graphql::Enum)]
+pub enum LeaderboardKind {
+    /// Best scores, for games that are scored
+    Score,
+    /// ELO ratings, for rated games
+    Rating,
+}
+
+/// Orders a season leaderboard can be read in, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum LeaderboardSort {
+    /// Highest score, as `LeaderboardEntry::cmp_rank` ranks it
+    Score,
+    WinRate,
+    GamesPlayed,
+    /// Most recent score first
+    Recent,
+}
+
+impl LeaderboardSort {
+    pub const ALL: [LeaderboardSort; 4] =
+        [Self::Score, Self::WinRate, Self::GamesPlayed, Self::Recent];
+}
+
+/// Rolling time windows with their own leaderboards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeaderboardWindow {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl LeaderboardWindow {
+    pub const ALL: [LeaderboardWindow; 3] = [Self::Daily, Self::Weekly, Self::Monthly];
+    
+    /// Parse a `time_filter` value; `None` means the all-time leaderboard
+    pub fn from_filter(filter: &str) -> Option<Self> {
+        match filter.to_ascii_lowercase().as_str() {
+            "daily" | "today" => Some(Self::Daily),
+            "weekly" | "week" => Some(Self::Weekly),
+            "monthly" | "month" => Some(Self::Monthly),
+            _ => None,
+        }
+    }
+    
+    /// Storage key suffix for this window
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+    
+    /// Index of the period containing `micros`; the window rolls over when it changes
+    pub fn period(self, micros: u64) -> u64 {
+        let day = micros / MICROS_PER_DAY;
+        match self {
+            Self::Daily => day,
+            // Day 0 was a Thursday; shift so weeks start on Monday
+            Self::Weekly => (day + 3) / 7,
+            Self::Monthly => {
+                let (year, month) = civil_year_month(day);
+                year * 12 + month
+            }
+        }
+    }
+}
+
+/// What players are ranked by across the arcade, rather than in one game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum PlayerRanking {
+    /// Prestige rank, then level, then XP
+    Level,
+    Xp,
+    TokensWon,
+}
+
+impl PlayerRanking {
+    pub const ALL: [PlayerRanking; 3] = [Self::Level, Self::Xp, Self::TokensWon];
+}
+
+/// Calendar year and zero-based month for a day count since the Unix epoch
+fn civil_year_month(day: u64) -> (u64, u64) {
+    // Howard Hinnant's days-to-civil algorithm, restricted to dates after 1970
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 2 } else { mp - 10 };
+    let year = yoe + era * 400 + u64::from(month < 2);
+    (year, month)
+}
+
+/// Outcome of a match from one player's point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum MatchResult {
+    Win,
+    Loss,
+    Draw,
+    /// Single-player games have a score but no opponent to beat
+    Completed,
+}
+
+/// Why a player's arcade credits changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum CreditReason {
+    /// Minted for winning a multiplayer match
+    MatchWin,
+    /// Minted for completing a daily quest
+    QuestCompleted,
+    /// Minted for reaching a new prestige rank
+    Prestige,
+    /// Burned to feature a room in the lobby
+    RoomBoost,
+    /// Burned to buy a cosmetic from the shop
+    CosmeticPurchase,
+    /// Sent to another player as a tip
+    TipSent,
+    /// Received from another player as a tip
+    TipReceived,
+    /// Minted when a referral pays out
+    Referral,
+    /// Donated to the player's clan treasury
+    ClanDonation,
+    /// Granted from the player's clan treasury
+    ClanGrant,
+}
+
+/// A clan's points on a season's clan leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ClanStanding {
+    pub tag: String,
+    /// Clan war points scored during the season
+    pub points: u64,
+    /// Clan wars won during the season
+    pub wars_won: u32,
+}
+
+/// A member's rank within their clan, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, async_graphql::Enum)]
+pub enum ClanRole {
+    Member,
+    /// Reviews join requests and can remove members
+    Officer,
+    /// Runs the clan and its treasury; there is exactly one
+    Leader,
+}
+
+/// One change to a player's arcade credits
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CreditTransaction {
+    pub reason: CreditReason,
+    /// Credits minted (positive) or burned (negative)
+    pub delta: i64,
+    /// Balance after the transaction
+    pub balance: u64,
+    pub timestamp: u64,
+}
+
+/// What a tip is paid in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TipAmount {
+    /// Native tokens, transferred from the sender's account
+    Tokens(Amount),
+    /// Arcade credits, moved between balances
+    Credits(u64),
+}
+
+/// A tip in a player's history
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TipRecord {
+    pub sender: AccountOwner,
+    pub recipient: AccountOwner,
+    pub tokens: Amount,
+    pub credits: u64,
+    pub memo: String,
+    pub timestamp: u64,
+}
+
+/// A match in a player's history
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MatchRecord {
+    pub game_type: GameType,
+    pub opponent: Option<AccountOwner>,
+    pub result: MatchResult,
+    pub score: u64,
+    pub timestamp: u64,
+}
+
+/// A settled match between two players, in their head-to-head record
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Encounter {
+    pub game_type: GameType,
+    /// `None` for a draw
+    pub winner: Option<AccountOwner>,
+    pub timestamp: u64,
+}
+
+/// A finished room match or single-player score, for the recent games feed
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RecentGame {
+    pub game_type: GameType,
+    pub players: Vec<AccountOwner>,
+    /// Room the match was played in, `None` for a single-player score
+    pub room_id: Option<String>,
+    /// `None` for a draw or a single-player game
+    pub winner: Option<AccountOwner>,
+    /// Final score of a single-player game
+    pub score: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// A player's results in one game, counting matches that had a winner or
+/// were drawn
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GameRecord {
+    pub game_type: GameType,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Percentage of these matches won
+    pub win_rate: u32,
+}
+
+impl GameRecord {
+    /// A record of no matches yet
+    pub fn new(game_type: GameType) -> Self {
+        Self { game_type, wins: 0, losses: 0, draws: 0, win_rate: 0 }
+    }
+    
+    /// Count a finished match. Single-player results don't count.
+    pub fn record(&mut self, result: MatchResult) {
+        match result {
+            MatchResult::Win => self.wins += 1,
+            MatchResult::Loss => self.losses += 1,
+            MatchResult::Draw => self.draws += 1,
+            MatchResult::Completed => return,
+        }
+        let played = self.wins + self.losses + self.draws;
+        self.win_rate = (self.wins as u64 * 100 / played as u64) as u32;
+    }
+}
+
+/// A validated move, kept for replays
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MoveRecord {
+    pub player: AccountOwner,
+    pub move_data: Vec<u8>,
+    pub timestamp: u64,
+    /// Round of the series the move was made in, starting at 1
+    pub round: u32,
+}
+
 /// A leaderboard entry
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct LeaderboardEntry {
     pub player_name: String,
-    pub player_address: String,
+    pub player_address: AccountOwner,
     pub score: u64,
+    /// Breaks ties left by `timestamp`: fewer games ranks higher
     pub games_played: u32,
     pub win_rate: u32,
+    /// When the player reached `score`. Breaks ties on score: earlier ranks higher.
     pub timestamp: u64,
 }
 
+impl LeaderboardEntry {
+    /// Leaderboard order: higher score first, then the score reached earlier,
+    /// then fewer games played, then by address so no two entries tie
+    pub fn cmp_rank(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then(self.timestamp.cmp(&other.timestamp))
+            .then(self.games_played.cmp(&other.games_played))
+            .then_with(|| self.player_address.cmp(&other.player_address))
+    }
+    
+    /// Whether the player has played enough games to be shown publicly
+    pub fn qualifies(&self, min_games: u32) -> bool {
+        self.games_played >= min_games
+    }
+}
+
+/// A solve on a daily Sudoku's speed leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SudokuSolve {
+    pub player_name: String,
+    pub player_address: AccountOwner,
+    /// Microseconds from the puzzle's publication to the solve
+    pub solve_time: u64,
+    pub solved_at: u64,
+}
+
+/// A player's record in the daily word game, as of the last day revealed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct WordStats {
+    pub games_played: u32,
+    pub games_solved: u32,
+    /// Days solved in a row
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub last_solved_day: Option<u64>,
+    /// Games solved in one guess, in two, and so on up to six
+    pub guess_distribution: Vec<u32>,
+}
+
+impl WordStats {
+    /// Guesses per solved game, times 100
+    pub fn average_guesses(&self) -> u32 {
+        let guesses: u32 =
+            self.guess_distribution.iter().zip(1..).map(|(&games, guesses)| games * guesses).sum();
+        (guesses * 100).checked_div(self.games_solved).unwrap_or(0)
+    }
+}
+
+/// An entry on the daily word leaderboard, ranked by current streak, then
+/// by fewest guesses on average
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct WordLeaderboardEntry {
+    pub player_name: String,
+    pub player_address: AccountOwner,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub games_solved: u32,
+    /// Guesses per solved game, times 100
+    pub average_guesses: u32,
+}
+
+/// A cosmetic a player has equipped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct EquippedCosmetic {
+    pub slot: CosmeticSlot,
+    pub item_id: String,
+}
+
 /// User profile stored on-chain
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct UserProfile {
     pub username: String,
     pub avatar_id: u8,
+    /// NFT from another application shown instead of `avatar_id`
+    pub nft_avatar: Option<NftAvatar>,
     pub level: u32,
     pub xp: u64,
     pub snake_high_score: u32,
@@ -130,6 +804,80 @@ pub struct UserProfile {
     pub tictactoe_wins: u32,
     pub tictactoe_losses: u32,
     pub total_tokens_won: u64,
+    /// ELO rating from rated Tic-Tac-Toe matches
+    pub rating: u32,
+    /// Free-play tokens available to cover entry fees
+    pub free_play_balance: Amount,
+    /// Native-token winnings held by the application until withdrawn
+    pub claimable_balance: Amount,
+    /// Day number (days since epoch) of the last faucet claim, 0 if never claimed
+    pub last_faucet_day: u64,
+    /// Lifetime total claimed from the faucet
+    pub faucet_claimed_total: Amount,
+    /// Time (in microseconds) of the player's last operation
+    pub last_seen: u64,
+    /// Consecutive days, up to today, on which the player has been active
+    pub login_streak: u32,
+    pub longest_login_streak: u32,
+    /// Day number (days since epoch) of the player's last activity
+    pub last_active_day: u64,
+    /// Times the player has prestiged
+    pub prestige: u32,
+    /// Cosmetics the player has unlocked or bought: their inventory
+    pub unlocked_cosmetics: Vec<String>,
+    /// Cosmetics currently shown, at most one per slot
+    pub equipped_cosmetics: Vec<EquippedCosmetic>,
+    /// In-app currency earned from play and spent on boosts and cosmetics.
+    /// Only the contract mints or burns it; players can only pass it on as tips.
+    pub arcade_credits: u64,
+    /// Native tokens received as tips from other players
+    pub tips_received_tokens: Amount,
+    /// Arcade credits received as tips from other players
+    pub tips_received_credits: u64,
+    /// Code other players register with to name this player as their referrer
+    pub referral_code: String,
+    /// Player who referred this one
+    pub referred_by: Option<AccountOwner>,
+    /// Players who registered with this player's referral code
+    pub referral_count: u32,
+    /// Games finished in rooms, which count towards the referral payout
+    pub room_games_played: u32,
+    /// Tag of the clan the player belongs to, shown next to their username
+    pub clan_tag: Option<String>,
+    /// Players who may not join this player's rooms, send them friend
+    /// requests or be matched against them
+    pub blocked: Vec<AccountOwner>,
+    /// Players whose chat messages are hidden from this player
+    pub muted: Vec<AccountOwner>,
+    /// Results per game type, for games the player has won, lost or drawn
+    pub game_records: Vec<GameRecord>,
+}
+
+impl UserProfile {
+    /// Percentage of `game_type` matches the player has won, 0 before any
+    pub fn win_rate(&self, game_type: GameType) -> u32 {
+        self.game_records.iter().find(|r| r.game_type == game_type).map_or(0, |r| r.win_rate)
+    }
+    
+    /// Take the daily free-play allowance for `day`, reduced near the
+    /// lifetime cap. `None` if it was already claimed that day or the cap
+    /// is reached.
+    pub fn claim_faucet(&mut self, day: u64) -> Option<Amount> {
+        if self.last_faucet_day >= day {
+            return None;
+        }
+        
+        let remaining = FAUCET_LIFETIME_CAP.saturating_sub(self.faucet_claimed_total);
+        let grant = DAILY_FAUCET_ALLOWANCE.min(remaining);
+        if grant == Amount::ZERO {
+            return None;
+        }
+        
+        self.last_faucet_day = day;
+        self.free_play_balance.saturating_add_assign(grant);
+        self.faucet_claimed_total.saturating_add_assign(grant);
+        Some(grant)
+    }
 }
 
 impl Default for UserProfile {
@@ -137,6 +885,7 @@ impl Default for UserProfile {
         Self {
             username: String::new(),
             avatar_id: 0,
+            nft_avatar: None,
             level: 1,
             xp: 0,
             snake_high_score: 0,
@@ -144,38 +893,654 @@ impl Default for UserProfile {
             tictactoe_wins: 0,
             tictactoe_losses: 0,
             total_tokens_won: 0,
+            rating: DEFAULT_RATING,
+            free_play_balance: Amount::ZERO,
+            claimable_balance: Amount::ZERO,
+            last_faucet_day: 0,
+            faucet_claimed_total: Amount::ZERO,
+            last_seen: 0,
+            login_streak: 0,
+            longest_login_streak: 0,
+            last_active_day: 0,
+            prestige: 0,
+            unlocked_cosmetics: Vec::new(),
+            equipped_cosmetics: Vec::new(),
+            arcade_credits: 0,
+            tips_received_tokens: Amount::ZERO,
+            tips_received_credits: 0,
+            referral_code: String::new(),
+            referred_by: None,
+            referral_count: 0,
+            room_games_played: 0,
+            clan_tag: None,
+            blocked: Vec::new(),
+            muted: Vec::new(),
+            game_records: Vec::new(),
+        }
+    }
+}
+
+/// A user profile as stored. BCS records no field names, so a profile that
+/// gains a field gets a new variant holding the new layout, and profiles in
+/// older layouts are upgraded as they are read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VersionedUserProfile {
+    V1(UserProfileV1),
+    V2(UserProfileV2),
+}
+
+impl From<UserProfile> for VersionedUserProfile {
+    fn from(profile: UserProfile) -> Self {
+        VersionedUserProfile::V2(profile.into())
+    }
+}
+
+impl From<VersionedUserProfile> for UserProfile {
+    fn from(stored: VersionedUserProfile) -> Self {
+        match stored {
+            VersionedUserProfile::V1(profile) => profile.into(),
+            VersionedUserProfile::V2(profile) => profile.into(),
         }
     }
 }
 
+/// Layout of `UserProfile` stored as `VersionedUserProfile::V1`, frozen so
+/// that changes to `UserProfile` can't change how stored profiles decode.
+/// Once `UserProfile` changes shape, profiles are written as a new variant
+/// and only the conversion from this layout is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfileV1 {
+    pub username: String,
+    pub avatar_id: u8,
+    pub nft_avatar: Option<NftAvatar>,
+    pub level: u32,
+    pub xp: u64,
+    pub snake_high_score: u32,
+    pub snake_games: u32,
+    pub tictactoe_wins: u32,
+    pub tictactoe_losses: u32,
+    pub total_tokens_won: u64,
+    pub rating: u32,
+    pub free_play_balance: Amount,
+    pub claimable_balance: Amount,
+    pub last_faucet_day: u64,
+    pub faucet_claimed_total: Amount,
+    pub last_seen: u64,
+    pub login_streak: u32,
+    pub longest_login_streak: u32,
+    pub last_active_day: u64,
+    pub prestige: u32,
+    pub unlocked_cosmetics: Vec<String>,
+    pub equipped_cosmetics: Vec<EquippedCosmetic>,
+    pub arcade_credits: u64,
+    pub tips_received_tokens: Amount,
+    pub tips_received_credits: u64,
+    pub referral_code: String,
+    pub referred_by: Option<AccountOwner>,
+    pub referral_count: u32,
+    pub room_games_played: u32,
+    pub clan_tag: Option<String>,
+    pub blocked: Vec<AccountOwner>,
+    pub muted: Vec<AccountOwner>,
+    pub game_records: Vec<GameRecordV1>,
+}
+
+impl From<UserProfileV1> for UserProfile {
+    fn from(profile: UserProfileV1) -> Self {
+        let UserProfileV1 {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        } = profile;
+        
+        // Records of games under names no game has are dropped
+        let game_records = game_records
+            .into_iter()
+            .filter_map(|record| {
+                Some(GameRecord {
+                    game_type: GameType::from_name(&record.game_type)?,
+                    wins: record.wins,
+                    losses: record.losses,
+                    draws: record.draws,
+                    win_rate: record.win_rate,
+                })
+            })
+            .collect();
+        Self {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        }
+    }
+}
+
+/// Game record of a `UserProfileV1`, which named the game type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRecordV1 {
+    pub game_type: String,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub win_rate: u32,
+}
+
+/// Layout of `UserProfile` stored as `VersionedUserProfile::V2`, frozen like
+/// `UserProfileV1`. Game records hold the game type itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfileV2 {
+    pub username: String,
+    pub avatar_id: u8,
+    pub nft_avatar: Option<NftAvatar>,
+    pub level: u32,
+    pub xp: u64,
+    pub snake_high_score: u32,
+    pub snake_games: u32,
+    pub tictactoe_wins: u32,
+    pub tictactoe_losses: u32,
+    pub total_tokens_won: u64,
+    pub rating: u32,
+    pub free_play_balance: Amount,
+    pub claimable_balance: Amount,
+    pub last_faucet_day: u64,
+    pub faucet_claimed_total: Amount,
+    pub last_seen: u64,
+    pub login_streak: u32,
+    pub longest_login_streak: u32,
+    pub last_active_day: u64,
+    pub prestige: u32,
+    pub unlocked_cosmetics: Vec<String>,
+    pub equipped_cosmetics: Vec<EquippedCosmetic>,
+    pub arcade_credits: u64,
+    pub tips_received_tokens: Amount,
+    pub tips_received_credits: u64,
+    pub referral_code: String,
+    pub referred_by: Option<AccountOwner>,
+    pub referral_count: u32,
+    pub room_games_played: u32,
+    pub clan_tag: Option<String>,
+    pub blocked: Vec<AccountOwner>,
+    pub muted: Vec<AccountOwner>,
+    pub game_records: Vec<GameRecord>,
+}
+
+impl From<UserProfileV2> for UserProfile {
+    fn from(profile: UserProfileV2) -> Self {
+        let UserProfileV2 {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        } = profile;
+        Self {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        }
+    }
+}
+
+impl From<UserProfile> for UserProfileV2 {
+    fn from(profile: UserProfile) -> Self {
+        let UserProfile {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        } = profile;
+        Self {
+            username,
+            avatar_id,
+            nft_avatar,
+            level,
+            xp,
+            snake_high_score,
+            snake_games,
+            tictactoe_wins,
+            tictactoe_losses,
+            total_tokens_won,
+            rating,
+            free_play_balance,
+            claimable_balance,
+            last_faucet_day,
+            faucet_claimed_total,
+            last_seen,
+            login_streak,
+            longest_login_streak,
+            last_active_day,
+            prestige,
+            unlocked_cosmetics,
+            equipped_cosmetics,
+            arcade_credits,
+            tips_received_tokens,
+            tips_received_credits,
+            referral_code,
+            referred_by,
+            referral_count,
+            room_games_played,
+            clan_tag,
+            blocked,
+            muted,
+            game_records,
+        }
+    }
+}
+
+/// Deployment configuration, supplied as the instantiation argument
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[serde(default)]
+pub struct GameStationConfig {
+    /// Games a player must play before appearing on public leaderboards
+    pub min_games_for_leaderboard: u32,
+    /// How long (in microseconds) a room may wait for players before it expires
+    pub waiting_room_ttl: u64,
+    /// Time (in microseconds) a player has to make each move
+    pub turn_timeout: u64,
+    /// Time (in microseconds) players have to confirm they are ready once a room fills
+    pub ready_check_timeout: u64,
+    /// Largest rating gap matchmaking will pair across, 0 for no limit
+    pub matchmaking_rating_range: u32,
+    /// Season number the deployment starts at
+    pub first_season: u32,
+    /// Length of a leaderboard season in microseconds, 0 for one endless season
+    pub season_length: u64,
+    /// XP for winning a match
+    pub xp_per_win: u64,
+    /// XP for losing a match
+    pub xp_per_loss: u64,
+    /// Snake games that don't beat the player's high score earn the score divided by this as XP
+    pub snake_xp_divisor: u32,
+    /// Other single-player games earn their score divided by this as XP
+    pub score_xp_divisor: u32,
+    /// XP scale of the level curve: level = sqrt(xp / xp_per_level) + 1
+    pub xp_per_level: u64,
+    /// Entries kept on each global, time-windowed and daily leaderboard.
+    /// Season leaderboards keep every player.
+    pub leaderboard_size: u32,
+    /// Most players a room can be created for
+    pub max_room_size: u8,
+    /// Level at which a player may prestige
+    pub max_level: u32,
+    /// Tiers on the season pass
+    pub pass_tiers: u8,
+    /// Season XP needed for each season pass tier
+    pub pass_xp_per_tier: u64,
+    /// Price of the season pass's premium track, in native tokens
+    pub pass_premium_price: Amount,
+    /// House rake taken from native-token prize pools before payout, in
+    /// basis points. Free-play tokens are never raked.
+    pub rake_bps: u16,
+}
+
+impl Default for GameStationConfig {
+    fn default() -> Self {
+        Self {
+            min_games_for_leaderboard: 0,
+            waiting_room_ttl: MICROS_PER_DAY,
+            turn_timeout: 5 * 60 * 1_000_000,
+            ready_check_timeout: 30 * 1_000_000,
+            matchmaking_rating_range: 0,
+            first_season: 1,
+            season_length: 0,
+            xp_per_win: 50,
+            xp_per_loss: 10,
+            snake_xp_divisor: 10,
+            score_xp_divisor: 100,
+            xp_per_level: 100,
+            leaderboard_size: 100,
+            max_room_size: 8,
+            max_level: 50,
+            pass_tiers: 10,
+            pass_xp_per_tier: 500,
+            pass_premium_price: Amount::from_tokens(5),
+            rake_bps: 0,
+        }
+    }
+}
+
+/// Application parameters, shared by every chain the application runs on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameStationParameters {
+    /// Chain that merges leaderboard entries from every chain into global leaderboards
+    pub hub_chain: Option<ChainId>,
+    /// Account allowed to run the privileged admin operations
+    pub admin: Option<AccountOwner>,
+}
+
 /// Operations that can be performed on the Game Station
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Operation {
     /// Submit a Snake game score
     SubmitSnakeScore { score: u32 },
     
-    /// Submit Tic-Tac-Toe game result
+    /// Submit a Snake score with the inputs that produced it. The contract
+    /// replays the game and only accepts the score if it matches.
+    SubmitVerifiedSnakeScore {
+        score: u32,
+        /// Seed the client placed food with
+        seed: u64,
+        turns: Vec<SnakeTurn>,
+    },
+    
+    /// Start a 2048 session, replacing any unfinished one. The response
+    /// carries the seed new tiles must be spawned with.
+    StartTwentyFortyEight,
+    
+    /// Submit the score of the current 2048 session with every slide made.
+    /// The contract replays the session from its seed and only accepts the
+    /// score if it matches.
+    SubmitTwentyFortyEightScore {
+        score: u32,
+        moves: Vec<Direction>,
+    },
+    
+    /// Start a Tetris session, replacing any unfinished one. The response
+    /// carries the seed the piece sequence must be drawn from.
+    StartTetris,
+    
+    /// Submit the score of the current Tetris session with every input made.
+    /// The contract replays the game from its seed and only accepts the
+    /// score if it matches.
+    SubmitTetrisScore {
+        score: u32,
+        inputs: Vec<TetrisInput>,
+    },
+    
+    /// Start a Minesweeper session on the next board the dealer committed
+    /// to, replacing any session whose clicks have not been submitted
+    StartMinesweeper,
+    
+    /// Submit every click of the current Minesweeper session. The result is
+    /// scored once the dealer reveals the board.
+    SubmitMinesweeperClicks { clicks: Vec<MinesweeperClick> },
+    
+    /// Submit a solution to the Sudoku published on `day` (days since the
+    /// Unix epoch), as 81 digits row by row. Only a player's first solve of
+    /// each puzzle is timed.
+    SubmitSudokuSolution { day: u64, grid: Vec<u8> },
+    
+    /// Guess the word published for `day`. The guess is scored once the
+    /// word is revealed.
+    SubmitWordGuess { day: u64, guess: String },
+    
+    /// Bet on a new blackjack hand against the house. `salt` is mixed into
+    /// every card, so the house can't have chosen them in advance.
+    StartBlackjack { bet: Amount, salt: Vec<u8> },
+    
+    /// Hit, stand or double down on the current blackjack hand. Doubling
+    /// escrows a second stake equal to the bet.
+    PlayBlackjack { action: BlackjackAction },
+    
+    /// Settle a blackjack hand the house has left waiting on a card for
+    /// longer than the turn timeout, as a win
+    ClaimBlackjackTimeout,
+    
+    /// Submit Tic-Tac-Toe game result. The result is self-reported, so it
+    /// is kept out of head-to-head records, which only settled rooms write.
     SubmitTicTacToeResult { 
         won: bool,
-        opponent: Option<String>,
+        opponent: Option<AccountOwner>,
     },
     
     /// Update user profile
     UpdateProfile {
         username: String,
         avatar_id: u8,
+        /// NFT shown instead of the built-in avatar; the signer must own it
+        nft_avatar: Option<NftAvatar>,
     },
     
     /// Create a new game room
     CreateRoom {
         game_type: GameType,
         max_players: u8,
-        entry_fee: u64,
+        entry_fee: Amount,
+        /// Play a best-of-N series: rounds a player must win (1 for a single game)
+        series_target: u8,
+        visibility: RoomVisibility,
+        /// `invite_code_hash` of the room's ID and invite code for private
+        /// rooms; the `nextRoomId` query gives the ID the room will get
+        invite_code_hash: Option<[u8; 32]>,
+        /// Players who may join a private room without the invite code
+        allowlist: Vec<AccountOwner>,
+        /// Play each match on its own temporary chain, opened when the match
+        /// starts and closed once it is settled
+        dedicated_chain: bool,
+        /// Head-to-head stake each player locks on top of the entry fee.
+        /// The winner takes both stakes; a draw returns them. Only for
+        /// two-player rooms of games that allow wagers, and paid in native
+        /// tokens.
+        wager: Amount,
+        /// Board size for games that let the room choose one: boxes along
+        /// each side for Dots and Boxes, 15 or 19 lines for Gomoku. `None`
+        /// plays the game's default.
+        board_size: Option<u8>,
+        /// Open each Gomoku game with swap2. Only for Gomoku rooms.
+        swap2_opening: bool,
     },
     
     /// Join an existing room
     JoinRoom {
         room_id: String,
+        /// `invite_proof` of the room's invite code hash and the joiner,
+        /// required for private rooms unless allowlisted
+        invite_proof: Option<[u8; 32]>,
+        /// The room's wager, repeated to consent to staking it. Required
+        /// when the room has a wager.
+        accept_wager: Option<Amount>,
+    },
+    
+    /// Leave a room that has not started yet (or a finished room awaiting a
+    /// rematch), refunding the entry fee
+    LeaveRoom {
+        room_id: String,
+    },
+    
+    /// Confirm you are ready to play in a room that has filled up. The game
+    /// starts once every player is ready; players who miss the deadline are
+    /// dropped and refunded by `CleanupExpiredRooms`.
+    Ready {
+        room_id: String,
+    },
+    
+    /// Host only: remove a player from a room that has not started yet,
+    /// refunding their entry fee. They also lose any allowlist invite.
+    KickPlayer {
+        room_id: String,
+        player: AccountOwner,
+    },
+    
+    /// Queue for a match; pairs with a waiting player of similar rating
+    /// into a new room, or waits in the queue until one arrives
+    EnterMatchmaking {
+        game_type: GameType,
+    },
+    
+    /// Leave the matchmaking queue
+    LeaveMatchmaking {
+        game_type: GameType,
+    },
+    
+    /// Send a friend request, or accept one if the other player already sent theirs
+    SendFriendRequest {
+        address: AccountOwner,
+    },
+    
+    /// Accept a pending friend request
+    AcceptFriendRequest {
+        address: AccountOwner,
+    },
+    
+    /// Remove a friend, or decline a pending request
+    RemoveFriend {
+        address: AccountOwner,
+    },
+    
+    /// Post a chat message in a room you are playing in
+    SendRoomChat {
+        room_id: String,
+        text: String,
+    },
+    
+    /// Expire rooms that have been waiting for players past their TTL
+    CleanupExpiredRooms,
+    
+    /// Win by forfeit when the player to move has missed their turn deadline
+    ClaimTimeout {
+        room_id: String,
+    },
+    
+    /// Ask for (or accept) a rematch in a finished room. The rematch starts
+    /// once every player has asked, with the turn order swapped.
+    RequestRematch {
+        room_id: String,
     },
     
     /// Submit a move in a multiplayer game
@@ -183,36 +1548,356 @@ pub enum Operation {
         room_id: String,
         move_data: Vec<u8>,
     },
+    
+    /// Consent to linking two addresses as the same player. The link takes
+    /// effect once both addresses have consented, each from its own chain.
+    /// Links are one level deep: an address is linked at most once, and
+    /// never both linked and a primary.
+    LinkIdentity {
+        primary_address: AccountOwner,
+        secondary_address: AccountOwner,
+        /// Chain where the other address submits its consent
+        target_chain: ChainId,
+    },
+    
+    /// Claim the daily free-play token allowance
+    ClaimDailyTokens,
+    
+    /// Admin: stop or resume accepting operations from players
+    SetPaused { paused: bool },
+    
+    /// Admin: strike a player from a leaderboard, e.g. for a fraudulent score
+    RemoveLeaderboardEntry {
+        game_type: GameType,
+        player: AccountOwner,
+    },
+    
+    /// Admin: close a stuck room, refunding every entry fee
+    ForceCloseRoom { room_id: String },
+    
+    /// Report a player for the admin to review
+    ReportPlayer {
+        player: AccountOwner,
+        reason: String,
+    },
+    
+    /// Admin: ban a player from every operation and hide them from leaderboards
+    BanPlayer { player: AccountOwner },
+    
+    /// Admin: lift a ban
+    UnbanPlayer { player: AccountOwner },
+    
+    /// Open a tournament for registration. Its games are two-player rooms
+    /// created on this chain as each round is paired.
+    CreateTournament {
+        game_type: GameType,
+        format: TournamentFormat,
+        max_players: u32,
+        entry_fee: Amount,
+        /// How long (in microseconds) players have to check in once
+        /// registration closes, 0 to skip check-in
+        check_in_window: u64,
+        /// Refund players who fail to check in, rather than keeping their
+        /// entry fee in the prize pool
+        refund_no_shows: bool,
+    },
+    
+    /// Register for a tournament, paying its entry fee into the prize pool
+    JoinTournament { tournament_id: String },
+    
+    /// Confirm attendance while a tournament's check-in is open
+    CheckInTournament { tournament_id: String },
+    
+    /// Reset level and XP at the maximum level in exchange for a prestige
+    /// rank and its cosmetics
+    Prestige,
+    
+    /// Buy the premium track of the current season's pass
+    BuySeasonPass,
+    
+    /// Claim the reward for a season pass tier the player has reached
+    ClaimPassReward { tier: u8, premium: bool },
+    
+    /// Close registration and pair the first round (creator only). With a
+    /// check-in window, the first call opens check-in and a second call,
+    /// once it has closed or everyone has checked in, drops the no-shows
+    /// and starts play.
+    StartTournament { tournament_id: String },
+    
+    /// Admin: change the house rake, in basis points
+    SetRake { rake_bps: u16 },
+    
+    /// Admin: send collected fees from the treasury to an account
+    WithdrawTreasury { amount: Amount, target: Account },
+    
+    /// Admin: move native tokens from the signer into the house bankroll
+    /// that backs blackjack payouts
+    FundHouseBankroll { amount: Amount },
+    
+    /// Admin: send native tokens from the house bankroll to an account
+    WithdrawHouseBankroll { amount: Amount, target: Account },
+    
+    /// Transfer native-token winnings from the player's claimable balance
+    /// to an account
+    Withdraw { amount: Amount, target: Account },
+    
+    /// Spend arcade credits to feature a waiting room in the lobby
+    BoostRoom { room_id: String },
+    
+    /// Buy a cosmetic from the shop with arcade credits
+    BuyCosmetic { item_id: String },
+    
+    /// Show an owned cosmetic in its slot, or clear the slot with `None`
+    EquipCosmetic {
+        slot: CosmeticSlot,
+        item_id: Option<String>,
+    },
+    
+    /// Send another player tokens or arcade credits with a short note
+    TipPlayer {
+        recipient: AccountOwner,
+        amount: TipAmount,
+        memo: String,
+    },
+    
+    /// Name the owner of a referral code as this player's referrer. Only
+    /// players who have not finished a room game yet can register.
+    RegisterWithReferral { code: String },
+    
+    /// Found a clan and become its leader
+    CreateClan { tag: String, name: String },
+    
+    /// Ask to join a clan; its leader or an officer reviews the request
+    RequestJoinClan { tag: String },
+    
+    /// Leader or officer: accept or decline a request to join your clan
+    ReviewJoinRequest { player: AccountOwner, accept: bool },
+    
+    /// Leave your clan. A departing leader hands over to the highest-ranked
+    /// remaining member, and the last member out disbands the clan.
+    LeaveClan,
+    
+    /// Leader: change a member's role. Making someone leader hands over the
+    /// clan, and the old leader becomes an officer.
+    SetClanRole { player: AccountOwner, role: ClanRole },
+    
+    /// Remove a member who ranks below you from your clan
+    KickFromClan { player: AccountOwner },
+    
+    /// Donate arcade credits to your clan's treasury
+    DonateToClan { credits: u64 },
+    
+    /// Leader: grant arcade credits from the clan treasury to a member
+    GrantClanCredits { player: AccountOwner, credits: u64 },
+    
+    /// Admin: schedule a war between two clans. Each room game a member wins
+    /// between `starts_at` and `ends_at` scores a point for their clan, and
+    /// the clan with more points has `reward` credits minted into its treasury.
+    ScheduleClanWar {
+        clans: [String; 2],
+        starts_at: u64,
+        ends_at: u64,
+        reward: u64,
+    },
+    
+    /// Pay out a clan war once it has ended; anyone may call this
+    SettleClanWar { war_id: String },
+    
+    /// Keep a player out of your rooms, friend requests and matchmaking.
+    /// Blocking also ends any friendship between you.
+    BlockPlayer { player: AccountOwner },
+    
+    /// Lift a block
+    UnblockPlayer { player: AccountOwner },
+    
+    /// Hide a player's chat messages from you
+    MutePlayer { player: AccountOwner },
+    
+    /// Show a muted player's chat messages again
+    UnmutePlayer { player: AccountOwner },
+    
+    /// Admin: commit to Minesweeper board seeds, `commitment(&[seed])`
+    /// each, to be dealt to new sessions in order
+    CommitMinesweeperBoards { commitments: Vec<[u8; 32]> },
+    
+    /// Admin: reveal the seed of a player's submitted Minesweeper board,
+    /// scoring their session
+    RevealMinesweeperBoard { player: AccountOwner, seed: Vec<u8> },
+    
+    /// Admin: add a question to the trivia bank. `answer` is the index of
+    /// the correct choice.
+    AddTriviaQuestion { prompt: String, choices: Vec<String>, answer: u8 },
+    
+    /// Admin: publish the word for `day` as
+    /// `daily_word::word_commitment(word, salt)`, opening it for guesses
+    PublishDailyWord { day: u64, commitment: [u8; 32] },
+    
+    /// Admin: reveal the word for `day` once the day is over, closing it and
+    /// scoring every player's guesses
+    RevealDailyWord { day: u64, word: String, salt: Vec<u8> },
+    
+    /// Admin: queue the ends of hash chains to deal blackjack hands from,
+    /// one per hand
+    CommitBlackjackShoes { commitments: Vec<[u8; 32]> },
+    
+    /// Admin: deal the cards a player's blackjack hand is waiting on by
+    /// revealing the next values back along its hash chain. Values beyond
+    /// what the hand needs are ignored.
+    DealBlackjack { player: AccountOwner, preimages: Vec<[u8; 32]> },
+}
+
+/// Result of an operation, returned to the client that submitted it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Response {
+    /// The operation succeeded and has nothing further to report
+    Ok,
+    ScoreAccepted { new_high: bool },
+    RoomCreated { room_id: String },
+    /// `ready_check` is set when this player filled the room and the ready check began
+    RoomJoined { room_id: String, ready_check: bool },
+    /// `started` is set when this was the last player to confirm and play began
+    ReadyConfirmed { started: bool },
+    /// `room_closed` is set when the room was deleted because it emptied
+    RoomLeft { room_closed: bool },
+    MoveAccepted { finished: bool },
+    /// `started` is set once every player asked and the rematch began
+    RematchRequested { started: bool },
+    /// Matchmaking paired this player into a new room, which waits for both
+    /// players to confirm they are ready
+    Matched { room_id: String },
+    /// No opponent was available; the player is waiting in the queue
+    Queued,
+    /// `count` rooms expired and `ready_checks_failed` rooms dropped the
+    /// players who missed their ready check
+    RoomsExpired { count: u32, ready_checks_failed: u32 },
+    TokensClaimed { amount: Amount },
+    /// `linked` is set once both addresses have consented
+    IdentityLinkConsented { linked: bool },
+    /// The action was sent on to the temporary chain the room is played on
+    Forwarded { chain_id: ChainId },
+    TournamentCreated { tournament_id: String },
+    ClanWarScheduled { war_id: String },
+    SessionStarted { seed: u64 },
+    /// `solve_time` is in microseconds since the puzzle was published
+    SudokuSolved { solve_time: u64 },
+    /// The blackjack hand finished, returning `payout` to the player's
+    /// claimable balance
+    BlackjackSettled { result: BlackjackResult, payout: Amount },
 }
 
 /// Messages for cross-chain communication
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Message {
     /// Notify that a player joined a room
     PlayerJoined {
         room_id: String,
-        player: String,
+        player: AccountOwner,
     },
     
     /// Broadcast a game move
     GameMove {
         room_id: String,
-        player: String,
+        player: AccountOwner,
         move_data: Vec<u8>,
     },
     
     /// Game ended notification
     GameEnded {
         room_id: String,
-        winner: Option<String>,
-        scores: Vec<(String, u64)>,
+        winner: Option<AccountOwner>,
+        scores: Vec<(AccountOwner, u64)>,
+    },
+    
+    /// Start playing a room on this newly opened chain
+    HostRoom {
+        room_id: String,
+        game_type: GameType,
+        /// Players in seat order, with the chain each joined from
+        players: Vec<(AccountOwner, ChainId)>,
+        series_target: u8,
+        board_size: Option<u8>,
+        swap2_opening: bool,
+        turn_timeout: u64,
+    },
+    
+    /// A player's move, forwarded to the room's temporary chain
+    RoomMove {
+        room_id: String,
+        move_data: Vec<u8>,
+    },
+    
+    /// A player's timeout claim, forwarded to the room's temporary chain
+    RoomClaimTimeout {
+        room_id: String,
+    },
+    
+    /// Result of a match played on a temporary chain, sent back for settlement
+    RoomFinished {
+        room_id: String,
+        series_wins: Vec<(AccountOwner, u8)>,
+        round_winners: Vec<Option<AccountOwner>>,
+        /// Players who forfeited a match the others shared
+        forfeited: Vec<AccountOwner>,
+        moves: Vec<MoveRecord>,
     },
     
     /// Leaderboard update
     LeaderboardUpdate {
         game_type: GameType,
+        kind: LeaderboardKind,
         entry: LeaderboardEntry,
     },
+    
+    /// One side's consent to an identity link, forwarded to the other chain
+    LinkIdentity {
+        primary_address: AccountOwner,
+        secondary_address: AccountOwner,
+        /// Address that consented; must match the message's authenticated signer
+        proof: AccountOwner,
+    },
+}
+
+/// Events published on the application's event streams, so indexers and
+/// frontends can follow activity without polling GraphQL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GameEvent {
+    ScoreSubmitted {
+        player: AccountOwner,
+        game_type: GameType,
+        score: u64,
+        timestamp: u64,
+    },
+    RoomCreated {
+        room_id: String,
+        game_type: GameType,
+        creator: AccountOwner,
+        timestamp: u64,
+    },
+    MoveMade {
+        room_id: String,
+        player: AccountOwner,
+        move_data: Vec<u8>,
+        timestamp: u64,
+    },
+    GameFinished {
+        room_id: String,
+        game_type: GameType,
+        /// `None` for a draw
+        winner: Option<AccountOwner>,
+        timestamp: u64,
+    },
+}
+
+impl GameEvent {
+    /// Name of the stream this kind of event is published on
+    pub fn stream_name(&self) -> &'static str {
+        match self {
+            Self::ScoreSubmitted { .. } => "scores",
+            Self::RoomCreated { .. } => "rooms",
+            Self::MoveMade { .. } => "moves",
+            Self::GameFinished { .. } => "results",
+        }
+    }
 }
 
 /// Query input for leaderboard
@@ -222,3 +1907,44 @@ pub struct LeaderboardQuery {
     pub limit: Option<u32>,
     pub time_filter: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn faucet_pays_once_a_day() {
+        let mut profile = UserProfile::default();
+        
+        assert_eq!(profile.claim_faucet(1), Some(DAILY_FAUCET_ALLOWANCE));
+        assert_eq!(profile.claim_faucet(1), None);
+        assert_eq!(profile.free_play_balance, DAILY_FAUCET_ALLOWANCE);
+        
+        assert_eq!(profile.claim_faucet(2), Some(DAILY_FAUCET_ALLOWANCE));
+        // A day already passed can't be claimed late
+        assert_eq!(profile.claim_faucet(1), None);
+        assert_eq!(profile.free_play_balance, Amount::from_tokens(20));
+    }
+    
+    #[test]
+    fn faucet_stops_at_the_lifetime_cap() {
+        let mut profile = UserProfile::default();
+        let mut day = 0;
+        while let Some(grant) = profile.claim_faucet(day + 1) {
+            assert_eq!(grant, DAILY_FAUCET_ALLOWANCE);
+            day += 1;
+        }
+        assert_eq!(day, 30);
+        assert_eq!(profile.faucet_claimed_total, FAUCET_LIFETIME_CAP);
+        assert_eq!(profile.claim_faucet(day + 100), None);
+        
+        // The last claim before the cap is cut short
+        let mut profile = UserProfile {
+            faucet_claimed_total: Amount::from_tokens(295),
+            ..UserProfile::default()
+        };
+        assert_eq!(profile.claim_faucet(1), Some(Amount::from_tokens(5)));
+        assert_eq!(profile.faucet_claimed_total, FAUCET_LIFETIME_CAP);
+        assert_eq!(profile.claim_faucet(2), None);
+    }
+}
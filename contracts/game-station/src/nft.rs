@@ -0,0 +1,38 @@
+//! Linera Game Station - NFT Avatars
+//!
+//! The interface an external NFT application implements so its tokens can be
+//! shown as player avatars. The contract calls it to check who owns a token
+//! before putting the token on a profile.
+
+use async_graphql::SimpleObject;
+use linera_sdk::base::{AccountOwner, ApplicationId, ContractAbi};
+use serde::{Deserialize, Serialize};
+
+/// Contract ABI an NFT application must expose to be used for avatars
+pub struct NftAbi;
+
+impl ContractAbi for NftAbi {
+    type Operation = NftOperation;
+    type Response = NftResponse;
+}
+
+/// Calls the game station makes to an NFT application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NftOperation {
+    /// Look up the current owner of a token
+    OwnerOf { token_id: String },
+}
+
+/// Answers from an NFT application
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NftResponse {
+    /// The token's owner, or `None` if no such token exists
+    Owner(Option<AccountOwner>),
+}
+
+/// A token of an external NFT application shown as a player's avatar
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct NftAvatar {
+    pub application_id: ApplicationId,
+    pub token_id: String,
+}
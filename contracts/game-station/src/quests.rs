@@ -0,0 +1,132 @@
+//! Linera Game Station - Daily Quests
+//!
+//! Each day offers the same few quests to every player, drawn from a fixed
+//! pool by a generator seeded with the day number, so any chain or client
+//! can work out today's quests without storing them.
+
+use linera_sdk::base::Amount;
+use serde::{Deserialize, Serialize};
+
+use crate::{games::randomness::SeededRng, GameType};
+
+/// Quests offered each day
+pub const QUESTS_PER_DAY: usize = 3;
+
+/// What a quest asks the player to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestGoal {
+    /// Reach a score in a single Snake game
+    SnakeScore { at_least: u32 },
+    /// Win games of one type
+    Wins { game_type: GameType, count: u32 },
+    /// Finish games of any type
+    GamesPlayed { count: u32 },
+}
+
+impl QuestGoal {
+    /// Progress needed to complete the quest
+    pub fn target(&self) -> u32 {
+        match self {
+            Self::SnakeScore { at_least } => *at_least,
+            Self::Wins { count, .. } | Self::GamesPlayed { count } => *count,
+        }
+    }
+    
+    /// Short text for the frontend
+    pub fn description(&self) -> String {
+        match self {
+            Self::SnakeScore { at_least } => format!("Score {} in Snake", at_least),
+            Self::Wins { game_type, count } => format!("Win {} {:?} games", count, game_type),
+            Self::GamesPlayed { count } => format!("Play {} games", count),
+        }
+    }
+    
+    /// XP and free-play tokens granted on completion
+    pub fn reward(&self) -> (u64, Amount) {
+        match self {
+            Self::SnakeScore { at_least } => (*at_least as u64 / 2, Amount::from_tokens(1)),
+            Self::Wins { count, .. } => (40 * *count as u64, Amount::from_tokens(2)),
+            Self::GamesPlayed { count } => (15 * *count as u64, Amount::from_tokens(1)),
+        }
+    }
+}
+
+/// Every quest that can be offered
+const QUEST_POOL: [QuestGoal; 9] = [
+    QuestGoal::SnakeScore { at_least: 50 },
+    QuestGoal::SnakeScore { at_least: 100 },
+    QuestGoal::SnakeScore { at_least: 200 },
+    QuestGoal::Wins { game_type: GameType::TicTacToe, count: 1 },
+    QuestGoal::Wins { game_type: GameType::TicTacToe, count: 2 },
+    QuestGoal::Wins { game_type: GameType::SnakeLadders, count: 1 },
+    QuestGoal::Wins { game_type: GameType::Uno, count: 1 },
+    QuestGoal::GamesPlayed { count: 3 },
+    QuestGoal::GamesPlayed { count: 5 },
+];
+
+/// The quests offered on `day` (days since the epoch)
+pub fn daily_quests(day: u64) -> Vec<QuestGoal> {
+    let mut pool = QUEST_POOL.to_vec();
+    let mut rng = SeededRng::new(day);
+    let mut quests = Vec::with_capacity(QUESTS_PER_DAY);
+    while quests.len() < QUESTS_PER_DAY && !pool.is_empty() {
+        let idx = rng.below(pool.len() as u64) as usize;
+        quests.push(pool.swap_remove(idx));
+    }
+    quests
+}
+
+/// Something a player did that may count towards a quest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestEvent {
+    SnakeGame { score: u32 },
+    GameFinished { game_type: GameType, won: bool },
+}
+
+/// A player's progress on the day's quests
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestProgress {
+    /// Day the progress is for; progress from earlier days no longer counts
+    pub day: u64,
+    /// Progress on each of the day's quests, in order
+    pub progress: Vec<u32>,
+    /// Which of the day's quests have been completed and rewarded
+    pub completed: Vec<bool>,
+}
+
+impl QuestProgress {
+    /// Apply an event to the progress for `day`, starting afresh on a new
+    /// day. Returns the quests completed by this event.
+    pub fn record(&mut self, day: u64, event: QuestEvent) -> Vec<QuestGoal> {
+        let quests = daily_quests(day);
+        if self.day != day || self.progress.len() != quests.len() {
+            *self = QuestProgress {
+                day,
+                progress: vec![0; quests.len()],
+                completed: vec![false; quests.len()],
+            };
+        }
+        
+        let mut newly_completed = Vec::new();
+        for (idx, quest) in quests.into_iter().enumerate() {
+            let progress = &mut self.progress[idx];
+            match (quest, event) {
+                (QuestGoal::SnakeScore { .. }, QuestEvent::SnakeGame { score }) => {
+                    *progress = (*progress).max(score);
+                }
+                (QuestGoal::Wins { game_type, .. }, QuestEvent::GameFinished { game_type: played, won: true })
+                    if game_type == played =>
+                {
+                    *progress += 1;
+                }
+                (QuestGoal::GamesPlayed { .. }, _) => *progress += 1,
+                _ => continue,
+            }
+            if !self.completed[idx] && *progress >= quest.target() {
+                self.completed[idx] = true;
+                newly_completed.push(quest);
+            }
+        }
+        newly_completed
+    }
+}
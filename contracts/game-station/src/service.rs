@@ -7,13 +7,33 @@
 
 mod state;
 
-use async_graphql::{EmptySubscription, Object, Schema, SimpleObject};
-use linera_sdk::{base::WithServiceAbi, Service, ServiceRuntime};
-use game_station::{GameType, LeaderboardEntry, UserProfile};
-use state::GameStationState;
+use async_graphql::{
+    futures_util::{stream, Stream},
+    Json, Object, Schema, SimpleObject, Subscription,
+};
+use linera_sdk::{
+    base::{AccountOwner, Amount, ChainId, WithServiceAbi},
+    Service, ServiceRuntime,
+};
+use game_station::{
+    cosmetics,
+    games::{blackjack, daily_word, sudoku},
+    quests,
+    tournament::{Pairing, Standing, TournamentRound, TournamentStatus},
+    ChatMessage, ClanRole, ClanStanding, CosmeticSlot, CreditTransaction, Encounter,
+    EquippedCosmetic, GameSession, GameState, GameStationConfig, GameStationParameters, GameType,
+    LeaderboardEntry, LeaderboardKind, LeaderboardSort, LeaderboardWindow, MatchRecord, MoveRecord,
+    PlayerRanking, PlayerReport, RecentGame, RoomStatus, RoomVisibility, SnakeGameState,
+    SudokuSolve, TicTacToeState, TipRecord, UserProfile, WordLeaderboardEntry, WordStats,
+    MICROS_PER_DAY, ONLINE_WINDOW,
+};
+use state::{
+    bucket_floor, score_bucket, ClanWar, GameRoom, GameStationState, RoomListing, Tournament,
+};
 
 pub struct GameStationService {
     state: GameStationState,
+    runtime: ServiceRuntime<Self>,
 }
 
 linera_sdk::service!(GameStationService);
@@ -23,17 +43,28 @@ impl WithServiceAbi for GameStationService {
 }
 
 impl Service for GameStationService {
-    type Parameters = ();
-
+    type Parameters = GameStationParameters;
+    
     async fn new(runtime: ServiceRuntime<Self>) -> Self {
-        let state = GameStationState::load(runtime.root_view_storage_context())
+        let mut state = GameStationState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        GameStationService { state }
+        // Until the contract next runs and saves it, migrate a copy to read from
+        state.migrate().await.expect("Failed to migrate state");
+        GameStationService { state, runtime }
     }
-
+    
     async fn handle_query(&self, query: Self::Query) -> Self::QueryResponse {
-        Schema::build(QueryRoot { state: &self.state }, MutationRoot, EmptySubscription)
+        let now = self.runtime.system_time().micros();
+        let query_root = QueryRoot {
+            state: &self.state,
+            now,
+            chain_id: self.runtime.chain_id(),
+        };
+        let subscription_root = SubscriptionRoot {
+            state: &self.state,
+        };
+        Schema::build(query_root, MutationRoot, subscription_root)
             .finish()
             .execute(query)
             .await
@@ -43,30 +74,527 @@ impl Service for GameStationService {
 /// GraphQL Query Root
 struct QueryRoot<'a> {
     state: &'a GameStationState,
+    /// Current time in microseconds, for time-windowed queries
+    now: u64,
+    chain_id: ChainId,
 }
 
 #[Object]
 impl<'a> QueryRoot<'a> {
     /// Get a user's profile by wallet address
-    async fn user_profile(&self, address: String) -> Option<UserProfile> {
+    async fn user_profile(&self, address: AccountOwner) -> Option<UserProfile> {
         self.state.users.get(&address).await.ok().flatten()
     }
     
-    /// Get the leaderboard for a specific game type
+    /// Get several users' profiles in one lookup, in the order of
+    /// `addresses`, with `null` for addresses that have no profile
+    async fn user_profiles(&self, addresses: Vec<AccountOwner>) -> Vec<Option<UserProfile>> {
+        self.state
+            .users
+            .multi_get(&addresses)
+            .await
+            .unwrap_or_else(|_| vec![None; addresses.len()])
+    }
+    
+    /// Find players whose username starts with `query`, ignoring case
+    async fn search_users(&self, query: String, limit: Option<u32>) -> Vec<UserSearchResult> {
+        let limit = limit.unwrap_or(10) as usize;
+        self.state
+            .search_users(&query, limit)
+            .await
+            .into_iter()
+            .map(|(address, profile)| UserSearchResult { address, profile })
+            .collect()
+    }
+    
+    /// Players across the arcade ranked by `sort_by`, level by default
+    async fn top_players(
+        &self,
+        limit: Option<u32>,
+        sort_by: Option<PlayerRanking>,
+    ) -> Vec<TopPlayer> {
+        let ranking = sort_by.unwrap_or(PlayerRanking::Level);
+        self.state
+            .top_players(ranking, limit.unwrap_or(10))
+            .await
+            .into_iter()
+            .zip(1..)
+            .map(|((address, profile), rank)| TopPlayer { rank, address, profile })
+            .collect()
+    }
+    
+    /// Get the leaderboard for a specific game type.
+    ///
+    /// `time_filter` selects a "daily", "weekly" or "monthly" window;
+    /// anything else returns the season leaderboard. `season` picks an
+    /// archived season and defaults to the current one. `min_prestige`
+    /// keeps only players of at least that prestige rank. `sort_by` orders
+    /// a season leaderboard, by score unless given; windowed leaderboards
+    /// always rank by score.
     async fn leaderboard(
         &self, 
-        game_type: String, 
-        limit: Option<u32>
+        game_type: GameType, 
+        limit: Option<u32>,
+        time_filter: Option<String>,
+        season: Option<u32>,
+        min_prestige: Option<u32>,
+        sort_by: Option<LeaderboardSort>,
+    ) -> Vec<LeaderboardEntry> {
+        let limit = limit.unwrap_or(10);
+        // Filter the whole board, so filtering doesn't shrink the page
+        let fetch = if min_prestige.is_some() { u32::MAX } else { limit };
+        let entries = match time_filter.as_deref().and_then(LeaderboardWindow::from_filter) {
+            Some(window) => {
+                self.state
+                    .get_windowed_leaderboard(game_type, window, self.now, fetch)
+                    .await
+            }
+            None => {
+                let season = season.unwrap_or(*self.state.current_season.get());
+                let key = (season, game_type, LeaderboardKind::Score);
+                let sort = sort_by.unwrap_or(LeaderboardSort::Score);
+                self.state.get_season_leaderboard(key, sort, fetch).await
+            }
+        };
+        match min_prestige {
+            Some(min_prestige) => self
+                .state
+                .with_min_prestige(entries, min_prestige)
+                .await
+                .into_iter()
+                .take(limit as usize)
+                .collect(),
+            None => entries,
+        }
+    }
+    
+    /// A player's exact position and score on a season leaderboard, even
+    /// far outside the top entries. `season` defaults to the current one.
+    /// The position counts every player ranked higher, including any the
+    /// leaderboard query hides for having too few games.
+    async fn player_rank(
+        &self,
+        game_type: GameType,
+        address: AccountOwner,
+        season: Option<u32>,
+    ) -> Option<PlayerRank> {
+        let season = season.unwrap_or(*self.state.current_season.get());
+        let (position, entry) =
+            self.state.get_leaderboard_rank(season, game_type, &address).await?;
+        Some(PlayerRank {
+            position,
+            score: entry.score,
+            games_played: entry.games_played,
+        })
+    }
+    
+    /// Percentage of the players on a season leaderboard that `score` beats.
+    /// Scores are counted in buckets about 6% wide, and players in the same
+    /// bucket as `score` count as half beaten. `season` defaults to the
+    /// current one.
+    async fn score_percentile(&self, game_type: GameType, score: u64, season: Option<u32>) -> f64 {
+        let season = season.unwrap_or(*self.state.current_season.get());
+        let target = score_bucket(score);
+        let (mut beaten, mut total) = (0.0, 0.0);
+        for (bucket, players) in self.state.get_score_distribution(season, game_type).await {
+            let players = players as f64;
+            total += players;
+            if bucket < target {
+                beaten += players;
+            } else if bucket == target {
+                beaten += players / 2.0;
+            }
+        }
+        if total == 0.0 {
+            return 0.0;
+        }
+        100.0 * beaten / total
+    }
+    
+    /// How many players on a season leaderboard scored in each range,
+    /// lowest scores first. Empty ranges are left out. `season` defaults
+    /// to the current one.
+    async fn score_distribution(
+        &self,
+        game_type: GameType,
+        season: Option<u32>,
+    ) -> Vec<ScoreBucket> {
+        let season = season.unwrap_or(*self.state.current_season.get());
+        self.state
+            .get_score_distribution(season, game_type)
+            .await
+            .into_iter()
+            .map(|(bucket, players)| ScoreBucket {
+                min_score: bucket_floor(bucket),
+                // The top bucket's ceiling wraps round to zero
+                max_score: bucket_floor(bucket + 1).wrapping_sub(1),
+                players,
+            })
+            .collect()
+    }
+    
+    /// How much a game is played and how well players score at it, over
+    /// every game recorded. A multi-player match counts once per player.
+    async fn game_stats(&self, game_type: GameType) -> GameStatsInfo {
+        let name = format!("{:?}", game_type);
+        match self.state.game_stats.try_load_entry(&name).await {
+            Ok(Some(stats)) => GameStatsInfo {
+                game_type,
+                total_games: *stats.games.get(),
+                unique_players: *stats.player_count.get(),
+                average_score: stats.average_score(),
+                median_score: stats.median_score().await.unwrap_or_default(),
+                games_last_day: stats.games_in_last_day(self.now).await.unwrap_or_default(),
+            },
+            _ => GameStatsInfo {
+                game_type,
+                total_games: 0,
+                unique_players: 0,
+                average_score: 0.0,
+                median_score: 0.0,
+                games_last_day: 0,
+            },
+        }
+    }
+    
+    /// Get the current season and when it started (microseconds)
+    async fn current_season(&self) -> SeasonInfo {
+        SeasonInfo {
+            season: *self.state.current_season.get(),
+            started_at: *self.state.season_started_at.get(),
+        }
+    }
+    
+    /// Get the global leaderboard merged from every chain. Only the hub chain
+    /// has one. `kind` defaults to scores, or ratings for rated games.
+    async fn global_leaderboard(
+        &self,
+        game_type: GameType,
+        kind: Option<LeaderboardKind>,
+        limit: Option<u32>,
+    ) -> Vec<LeaderboardEntry> {
+        let limit = limit.unwrap_or(10) as usize;
+        let kind = kind.unwrap_or(if game_type.is_rated() {
+            LeaderboardKind::Rating
+        } else {
+            LeaderboardKind::Score
+        });
+        let entries = self
+            .state
+            .global_leaderboards
+            .get(&(game_type, kind))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        self.state.hide_banned(entries).await.into_iter().take(limit).collect()
+    }
+    
+    /// Get a player's most recent matches, newest first
+    async fn match_history(&self, address: AccountOwner, limit: Option<u32>) -> Vec<MatchRecord> {
+        let limit = limit.unwrap_or(20) as usize;
+        self.state.get_match_history(&address, limit).await
+    }
+    
+    /// Games most recently finished on this chain, newest first: room
+    /// matches and single-player scores
+    async fn recent_games(&self, limit: Option<u32>) -> Vec<RecentGame> {
+        let limit = limit.unwrap_or(20) as usize;
+        let mut games = self.state.recent_games.read_back(limit).await.unwrap_or_default();
+        games.reverse();
+        games
+    }
+    
+    /// Results between two players in matches against each other, from
+    /// `player_a`'s side, with their most recent encounters newest first
+    async fn head_to_head(&self, player_a: AccountOwner, player_b: AccountOwner) -> HeadToHeadInfo {
+        let (wins, losses, draws, recent) = self.state.get_head_to_head(player_a, player_b).await;
+        HeadToHeadInfo { player_a, player_b, wins, losses, draws, recent }
+    }
+    
+    /// Get a player's most recent tips, sent and received, newest first
+    async fn tips(&self, address: AccountOwner, limit: Option<u32>) -> Vec<TipRecord> {
+        let limit = limit.unwrap_or(20) as usize;
+        self.state.get_tip_history(&address, limit).await
+    }
+    
+    /// Get a player's friends with their profiles and online status
+    async fn friends(&self, address: AccountOwner) -> Vec<FriendInfo> {
+        let friends = self.state.friends.get(&address).await.ok().flatten().unwrap_or_default();
+        let mut result = Vec::with_capacity(friends.len());
+        for friend in friends {
+            let profile = self.state.users.get(&friend).await.ok().flatten();
+            let online = profile
+                .as_ref()
+                .is_some_and(|p| self.now.saturating_sub(p.last_seen) < ONLINE_WINDOW);
+            result.push(FriendInfo { address: friend, online, profile });
+        }
+        result
+    }
+    
+    /// Get the addresses that have sent a player a friend request
+    async fn friend_requests(&self, address: AccountOwner) -> Vec<AccountOwner> {
+        self.state.friend_requests.get(&address).await.ok().flatten().unwrap_or_default()
+    }
+    
+    /// Get a rated game's leaderboard, ranked by ELO rating. `game_type`
+    /// defaults to `TIC_TAC_TOE`.
+    async fn rating_leaderboard(
+        &self,
+        game_type: Option<GameType>,
+        limit: Option<u32>,
     ) -> Vec<LeaderboardEntry> {
+        let game_type = game_type.unwrap_or(GameType::TicTacToe);
         let limit = limit.unwrap_or(10);
-        self.state.get_leaderboard(&game_type, limit).await
+        self.state.get_leaderboard(game_type, LeaderboardKind::Rating, limit).await
+    }
+    
+    /// Whether a player has played enough games to appear on a leaderboard
+    async fn leaderboard_eligible(&self, game_type: GameType, address: AccountOwner) -> bool {
+        self.state.is_leaderboard_eligible(game_type, &address).await
+    }
+    
+    /// Get the deployment configuration
+    async fn config(&self) -> GameStationConfig {
+        self.state.config.get().clone()
+    }
+    
+    /// Whether a player is banned
+    async fn is_banned(&self, address: AccountOwner) -> bool {
+        self.state.banned.contains_key(&address).await.unwrap_or(false)
+    }
+    
+    /// Reports filed against a player, for the admin to review
+    async fn player_reports(&self, address: AccountOwner) -> Vec<PlayerReport> {
+        self.state.reports.get(&address).await.ok().flatten().unwrap_or_default()
+    }
+    
+    /// Whether the admin has paused the game station
+    async fn paused(&self) -> bool {
+        *self.state.paused.get()
+    }
+    
+    /// Every cosmetic sold in the shop
+    async fn cosmetic_catalog(&self) -> Vec<CosmeticInfo> {
+        cosmetics::CATALOG.iter().map(CosmeticInfo::from).collect()
+    }
+    
+    /// Cosmetics a player owns, with the ones they have equipped marked
+    async fn inventory(&self, address: AccountOwner) -> Vec<InventoryItem> {
+        let Ok(Some(profile)) = self.state.users.get(&address).await else {
+            return Vec::new();
+        };
+        profile
+            .unlocked_cosmetics
+            .iter()
+            .map(|item_id| InventoryItem {
+                item_id: item_id.clone(),
+                name: cosmetics::find(item_id).map(|item| item.name.to_string()),
+                slot: cosmetics::slot_of(item_id),
+                equipped: profile.equipped_cosmetics.iter().any(|e| e.item_id == *item_id),
+            })
+            .collect()
+    }
+    
+    /// Cosmetics a player has equipped, one per slot
+    async fn equipped_cosmetics(&self, address: AccountOwner) -> Vec<EquippedCosmetic> {
+        self.state
+            .users
+            .get(&address)
+            .await
+            .ok()
+            .flatten()
+            .map(|profile| profile.equipped_cosmetics)
+            .unwrap_or_default()
+    }
+    
+    /// A player's arcade credit balance
+    async fn arcade_credits(&self, address: AccountOwner) -> u64 {
+        self.state
+            .users
+            .get(&address)
+            .await
+            .ok()
+            .flatten()
+            .map_or(0, |profile| profile.arcade_credits)
+    }
+    
+    /// A player's most recent arcade credit transactions, newest first
+    async fn credit_transactions(&self, address: AccountOwner, limit: Option<u32>) -> Vec<CreditTransaction> {
+        let limit = limit.unwrap_or(20) as usize;
+        self.state.get_credit_history(&address, limit).await
+    }
+    
+    /// Native-token winnings a player can withdraw
+    async fn claimable_balance(&self, address: AccountOwner) -> Amount {
+        self.state
+            .users
+            .get(&address)
+            .await
+            .ok()
+            .flatten()
+            .map_or(Amount::ZERO, |profile| profile.claimable_balance)
+    }
+    
+    /// House rake and the fees it has collected
+    async fn treasury(&self) -> TreasuryInfo {
+        TreasuryInfo {
+            rake_bps: self.state.config.get().rake_bps,
+            balance: *self.state.treasury.get(),
+            fees_collected: *self.state.fees_collected.get(),
+        }
     }
     
     /// Get a player's Snake high score
-    async fn snake_high_score(&self, address: String) -> u32 {
+    async fn snake_high_score(&self, address: AccountOwner) -> u32 {
         self.state.snake_high_scores.get(&address).await.ok().flatten().unwrap_or(0)
     }
     
+    /// Get a player's 2048 high score
+    async fn twenty_forty_eight_high_score(&self, address: AccountOwner) -> u32 {
+        let key = (GameType::TwentyFortyEight, address);
+        self.state.high_scores.get(&key).await.ok().flatten().unwrap_or(0)
+    }
+    
+    /// Get a player's Tetris high score
+    async fn tetris_high_score(&self, address: AccountOwner) -> u32 {
+        let key = (GameType::Tetris, address);
+        self.state.high_scores.get(&key).await.ok().flatten().unwrap_or(0)
+    }
+    
+    /// Get a player's unfinished Tetris session, with the seed its pieces are drawn from
+    async fn tetris_session(&self, address: AccountOwner) -> Option<GameSession> {
+        self.state.tetris_sessions.get(&address).await.ok().flatten()
+    }
+    
+    /// Get a player's best final Yahtzee score
+    async fn yahtzee_high_score(&self, address: AccountOwner) -> u32 {
+        self.state.yahtzee_high_scores.get(&address).await.ok().flatten().unwrap_or(0)
+    }
+    
+    /// Get a player's unfinished 2048 session, with the seed its tiles spawn from
+    async fn twenty_forty_eight_session(&self, address: AccountOwner) -> Option<GameSession> {
+        self.state.twenty_forty_eight_sessions.get(&address).await.ok().flatten()
+    }
+    
+    /// Get a player's Minesweeper session, if one is waiting for clicks or
+    /// for its board to be revealed
+    async fn minesweeper_session(&self, address: AccountOwner) -> Option<MinesweeperSessionInfo> {
+        let session = self.state.minesweeper_sessions.get(&address).await.ok().flatten()?;
+        Some(MinesweeperSessionInfo {
+            board_commitment: session
+                .board_commitment
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            started_at: session.started_at,
+            clicks: session.clicks.len() as u32,
+            submitted_at: session.submitted_at,
+        })
+    }
+    
+    /// Number of committed Minesweeper boards left to deal
+    async fn minesweeper_boards_left(&self) -> u32 {
+        self.state.minesweeper_boards.count() as u32
+    }
+    
+    /// The daily word game for `day`, today's by default. The answer is
+    /// only shown once revealed.
+    async fn daily_word(&self, day: Option<u64>) -> Option<DailyWordInfo> {
+        let day = day.unwrap_or(self.now / MICROS_PER_DAY);
+        let word = self.state.daily_words.get(&day).await.ok().flatten()?;
+        Some(DailyWordInfo { day, published_at: word.published_at, answer: word.answer })
+    }
+    
+    /// A player's guesses at `day`'s word, with feedback once it is revealed
+    async fn word_guesses(&self, day: u64, address: AccountOwner) -> Vec<WordGuessInfo> {
+        let guesses = self.state.word_guesses.get(&(day, address)).await.ok().flatten();
+        let answer = self.state.daily_words.get(&day).await.ok().flatten().and_then(|w| w.answer);
+        guesses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|guess| WordGuessInfo {
+                feedback: answer.as_deref().map(|answer| {
+                    daily_word::feedback(&guess, answer).iter().map(|f| f.symbol()).collect()
+                }),
+                guess,
+            })
+            .collect()
+    }
+    
+    /// A player's daily word game record
+    async fn word_stats(&self, address: AccountOwner) -> WordStats {
+        self.state.word_stats.get(&address).await.ok().flatten().unwrap_or_default()
+    }
+    
+    /// Daily word leaderboard, by current streak and then fewest guesses
+    async fn word_leaderboard(&self, limit: Option<u32>) -> Vec<WordLeaderboardEntry> {
+        let mut entries = self.state.word_leaderboard.get().clone();
+        entries.truncate(limit.unwrap_or(10) as usize);
+        entries
+    }
+    
+    /// A player's blackjack hand in play
+    async fn blackjack_hand(&self, address: AccountOwner) -> Option<BlackjackHandInfo> {
+        let session = self.state.blackjack_hands.get(&address).await.ok().flatten()?;
+        let hand = session.hand;
+        Some(BlackjackHandInfo {
+            bet: session.bet,
+            doubled: hand.doubled,
+            player_total: blackjack::hand_total(&hand.player_cards),
+            dealer_total: blackjack::hand_total(&hand.dealer_cards),
+            awaiting_card: hand.wants_card(),
+            player_cards: hand.player_cards,
+            dealer_cards: hand.dealer_cards,
+            updated_at: session.updated_at,
+        })
+    }
+    
+    /// Native tokens the house has free to back new blackjack hands
+    async fn house_bankroll(&self) -> Amount {
+        *self.state.house_bankroll.get()
+    }
+    
+    /// Number of committed blackjack shoes left to deal
+    async fn blackjack_shoes_left(&self) -> u32 {
+        self.state.blackjack_shoes.count() as u32
+    }
+    
+    /// Number of questions in the trivia bank
+    async fn trivia_questions_available(&self) -> u32 {
+        self.state.trivia_questions.count() as u32
+    }
+    
+    /// The Sudoku published on `day` (days since the Unix epoch), today's
+    /// by default. Puzzles are not shown before they are published.
+    async fn daily_sudoku(&self, day: Option<u64>) -> Option<DailySudokuInfo> {
+        let today = self.now / MICROS_PER_DAY;
+        let day = day.unwrap_or(today);
+        if day > today {
+            return None;
+        }
+        let puzzle = sudoku::daily_puzzle(day);
+        Some(DailySudokuInfo {
+            day,
+            published_at: day * MICROS_PER_DAY,
+            givens: puzzle.givens.iter().flatten().copied().collect(),
+        })
+    }
+    
+    /// Fastest solves of the Sudoku published on `day`, today's by default
+    async fn sudoku_leaderboard(&self, day: Option<u64>, limit: Option<u32>) -> Vec<SudokuSolve> {
+        let day = day.unwrap_or(self.now / MICROS_PER_DAY);
+        let mut solves =
+            self.state.sudoku_leaderboards.get(&day).await.ok().flatten().unwrap_or_default();
+        solves.truncate(limit.unwrap_or(10) as usize);
+        solves
+    }
+    
+    /// Get the primary address an address is linked to, if any
+    async fn linked_identity(&self, address: AccountOwner) -> Option<AccountOwner> {
+        self.state.identity_links.get(&address).await.ok().flatten()
+    }
+    
     /// Get total games played across all players
     async fn total_games(&self) -> u64 {
         *self.state.total_games_played.get().unwrap_or(&0)
@@ -77,26 +605,432 @@ impl<'a> QueryRoot<'a> {
         *self.state.total_players.get().unwrap_or(&0)
     }
     
-    /// Get active game rooms
-    async fn active_rooms(&self, game_type: Option<String>) -> Vec<RoomInfo> {
-        // In a full implementation, this would iterate over rooms
-        // For now, return empty as rooms are handled differently
-        Vec::new()
+    /// Get public game rooms from the lobby listings, optionally filtered by
+    /// game type, status, entry fee range, open seats and creation time.
+    ///
+    /// `sort` is "newest", "cheapest" or "fullest"; anything else keeps
+    /// room ID order. Pass the `next_cursor` of a page as `after` to fetch
+    /// the next page; cursors stay valid after their room closes.
+    #[allow(clippy::too_many_arguments)]
+    async fn active_rooms(
+        &self,
+        game_type: Option<GameType>,
+        status: Option<RoomStatus>,
+        min_entry_fee: Option<Amount>,
+        max_entry_fee: Option<Amount>,
+        min_open_seats: Option<u8>,
+        created_after: Option<u64>,
+        sort: Option<String>,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> RoomPage {
+        let limit = limit.unwrap_or(20) as usize;
+        let mut listings = Vec::new();
+        let _ = self
+            .state
+            .room_listings
+            .for_each_index_value(|_, listing| {
+                let listing = listing.into_owned();
+                if game_type.is_none_or(|g| g == listing.game_type)
+                    && status.is_none_or(|s| s == listing.status)
+                    && min_entry_fee.is_none_or(|fee| listing.entry_fee >= fee)
+                    && max_entry_fee.is_none_or(|fee| listing.entry_fee <= fee)
+                    && min_open_seats.is_none_or(|seats| listing.open_seats() >= seats)
+                    && created_after.is_none_or(|time| listing.created_at > time)
+                {
+                    listings.push(listing);
+                }
+                Ok(())
+            })
+            .await;
+        
+        // Order by the sort rank, then by room ID, so a cursor holding both
+        // can be resumed from even after its room is gone
+        let sort_key = |listing: &RoomListing| {
+            let rank = match sort.as_deref() {
+                Some("newest") => u128::from(u64::MAX - listing.created_at),
+                Some("cheapest") => u128::from(listing.entry_fee),
+                Some("fullest") => u128::from(listing.open_seats()),
+                _ => 0,
+            };
+            (rank, listing.room_id.clone())
+        };
+        listings.sort_by_key(sort_key);
+        
+        // Resume at the first listing ordered after the cursor
+        let start = match after.as_deref().and_then(|cursor| cursor.split_once(':')) {
+            Some((rank, room_id)) => {
+                let cursor = (rank.parse::<u128>().unwrap_or(0), room_id.to_string());
+                listings.partition_point(|listing| sort_key(listing) <= cursor)
+            }
+            None => 0,
+        };
+        
+        let page = listings.iter().skip(start).take(limit);
+        let mut rooms = Vec::new();
+        for listing in page.clone() {
+            if let Ok(Some(room)) = self.state.rooms.get(&listing.room_id).await {
+                rooms.push(RoomInfo::from(room));
+            }
+        }
+        let next_cursor = if start + limit < listings.len() {
+            page.last().map(|listing| {
+                let (rank, room_id) = sort_key(listing);
+                format!("{}:{}", rank, room_id)
+            })
+        } else {
+            None
+        };
+        
+        RoomPage { rooms, next_cursor }
+    }
+    
+    /// Public waiting rooms currently boosted, longest-running boost first
+    async fn featured_rooms(&self, limit: Option<u32>) -> Vec<RoomInfo> {
+        let room_ids = self.state.rooms.indices().await.unwrap_or_default();
+        let mut rooms = Vec::new();
+        for room_id in room_ids {
+            let Ok(Some(room)) = self.state.rooms.get(&room_id).await else {
+                continue;
+            };
+            if room.visibility == RoomVisibility::Public
+                && room.status == RoomStatus::Waiting
+                && room.boosted_until.is_some_and(|until| until > self.now)
+            {
+                rooms.push(room);
+            }
+        }
+        rooms.sort_by(|a, b| b.boosted_until.cmp(&a.boosted_until));
+        rooms.truncate(limit.unwrap_or(10) as usize);
+        rooms.into_iter().map(RoomInfo::from).collect()
     }
     
     /// Get room details by ID
     async fn room(&self, room_id: String) -> Option<RoomInfo> {
-        if let Some(room) = self.state.rooms.get(&room_id).await.ok().flatten() {
-            Some(RoomInfo {
-                room_id: room.room_id,
-                game_type: format!("{:?}", room.game_type),
-                player_count: room.players.len() as u32,
-                max_players: room.max_players as u32,
-                status: format!("{:?}", room.status),
+        self.state.rooms.get(&room_id).await.ok().flatten().map(RoomInfo::from)
+    }
+    
+    /// The ID the next room created here will get, to salt a private room's
+    /// invite code hash with
+    async fn next_room_id(&self, game_type: GameType) -> String {
+        self.state.peek_room_id(game_type, self.chain_id)
+    }
+    
+    /// Unfinished rooms a player is seated in, those waiting on their move
+    /// first
+    async fn my_rooms(&self, address: AccountOwner) -> Vec<PlayerRoomInfo> {
+        let room_ids = self.state.player_rooms.get(&address).await.ok().flatten();
+        let mut rooms = Vec::new();
+        for room_id in room_ids.unwrap_or_default() {
+            if let Ok(Some(room)) = self.state.rooms.get(&room_id).await {
+                rooms.push(PlayerRoomInfo {
+                    your_turn: room.current_turn().is_some_and(|(player, _)| player == address),
+                    turn_deadline: room.turn_deadline,
+                    room: RoomInfo::from(room),
+                });
+            }
+        }
+        rooms.sort_by_key(|room| !room.your_turn);
+        rooms
+    }
+    
+    /// The game being played in a room, for rendering the match. Rooms
+    /// played on a temporary chain only have it on that chain.
+    async fn room_game_state(&self, room_id: String) -> Option<RoomGameStateInfo> {
+        let room = self.state.rooms.get(&room_id).await.ok().flatten()?;
+        let (tic_tac_toe, snake) = match &room.game_state {
+            Some(GameState::TicTacToe(game)) => (Some(game.clone()), None),
+            Some(GameState::Snake(game)) => (None, Some(game.clone())),
+            _ => (None, None),
+        };
+        Some(RoomGameStateInfo {
+            current_turn: room.current_turn().map(|(player, _)| player),
+            round: room.round_winners.len() as u32 + 1,
+            room_id: room.room_id,
+            game_type: room.game_type,
+            status: room.status,
+            players: room.players,
+            turn_deadline: room.turn_deadline,
+            tic_tac_toe,
+            snake,
+            state: room.game_state.map(Json),
+        })
+    }
+    
+    /// Get this chain's mirror of a room hosted on another chain
+    async fn room_mirror(&self, room_id: String) -> Option<RoomMirrorInfo> {
+        let mirror = self.state.room_mirrors.get(&room_id).await.ok().flatten()?;
+        Some(RoomMirrorInfo {
+            room_id: mirror.room_id,
+            players: mirror.players,
+            status: mirror.status,
+            move_count: mirror.move_count,
+            winner: mirror.winner,
+        })
+    }
+    
+    /// Get a clan by tag, with its stats
+    async fn clan(&self, tag: String) -> Option<ClanInfo> {
+        let clan = self.state.clans.get(&tag.to_uppercase()).await.ok().flatten()?;
+        let mut info = ClanInfo {
+            tag: clan.tag.clone(),
+            name: clan.name.clone(),
+            leader: clan.leader(),
+            member_count: clan.members.len() as u32,
+            pending_requests: clan.join_requests.clone(),
+            treasury: clan.treasury,
+            created_at: clan.created_at,
+            total_xp: 0,
+            room_games_played: 0,
+            tictactoe_wins: 0,
+            best_snake_score: 0,
+        };
+        for (member, _) in &clan.members {
+            if let Ok(Some(profile)) = self.state.users.get(member).await {
+                info.total_xp += profile.xp;
+                info.room_games_played += profile.room_games_played;
+                info.tictactoe_wins += profile.tictactoe_wins;
+                info.best_snake_score = info.best_snake_score.max(profile.snake_high_score);
+            }
+        }
+        Some(info)
+    }
+    
+    /// Get a clan's members, highest rank first
+    async fn clan_roster(&self, tag: String) -> Vec<ClanMemberInfo> {
+        let Ok(Some(clan)) = self.state.clans.get(&tag.to_uppercase()).await else {
+            return Vec::new();
+        };
+        let mut roster = Vec::new();
+        for (member, role) in clan.members {
+            let profile = self.state.users.get(&member).await.ok().flatten().unwrap_or_default();
+            roster.push(ClanMemberInfo {
+                address: member,
+                username: profile.username,
+                role,
+                level: profile.level,
+            });
+        }
+        roster.sort_by(|a, b| b.role.cmp(&a.role));
+        roster
+    }
+    
+    /// Get a clan war with its scores
+    async fn clan_war(&self, war_id: String) -> Option<ClanWarInfo> {
+        self.state.clan_wars.get(&war_id).await.ok().flatten().map(ClanWarInfo::from)
+    }
+    
+    /// Get the clan wars a clan is signed up for that have not been settled
+    async fn clan_wars(&self, tag: String) -> Vec<ClanWarInfo> {
+        let Ok(Some(clan)) = self.state.clans.get(&tag.to_uppercase()).await else {
+            return Vec::new();
+        };
+        let mut wars = Vec::new();
+        for war_id in &clan.wars {
+            if let Ok(Some(war)) = self.state.clan_wars.get(war_id).await {
+                wars.push(ClanWarInfo::from(war));
+            }
+        }
+        wars
+    }
+    
+    /// Get the clan leaderboard of a season, defaulting to the current one
+    async fn clan_leaderboard(&self, season: Option<u32>, limit: Option<u32>) -> Vec<ClanStanding> {
+        let season = season.unwrap_or(*self.state.current_season.get());
+        let mut standings = self
+            .state
+            .clan_leaderboards
+            .get(&season)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        standings.truncate(limit.unwrap_or(10) as usize);
+        standings
+    }
+    
+    /// Get a tournament by ID
+    async fn tournament(&self, tournament_id: String) -> Option<TournamentInfo> {
+        self.state.tournaments.get(&tournament_id).await.ok().flatten().map(TournamentInfo::from)
+    }
+    
+    /// Tournaments still taking registrations, newest first
+    async fn open_tournaments(&self, game_type: Option<GameType>) -> Vec<TournamentInfo> {
+        let mut tournaments = Vec::new();
+        let _ = self
+            .state
+            .tournaments
+            .for_each_index_value(|_, tournament| {
+                let tournament = tournament.into_owned();
+                if tournament.is_open() && game_type.is_none_or(|g| g == tournament.game_type) {
+                    tournaments.push(tournament);
+                }
+                Ok(())
             })
-        } else {
-            None
+            .await;
+        tournaments.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        tournaments.into_iter().map(TournamentInfo::from).collect()
+    }
+    
+    /// Tournaments a player has registered for, newest first
+    async fn my_tournaments(
+        &self,
+        address: AccountOwner,
+        include_finished: Option<bool>,
+    ) -> Vec<TournamentInfo> {
+        let include_finished = include_finished.unwrap_or(false);
+        let mut tournaments = Vec::new();
+        let _ = self
+            .state
+            .tournaments
+            .for_each_index_value(|_, tournament| {
+                let tournament = tournament.into_owned();
+                let over = matches!(
+                    tournament.status,
+                    TournamentStatus::Finished | TournamentStatus::Cancelled
+                );
+                if tournament.players.iter().any(|(player, _)| *player == address)
+                    && (include_finished || !over)
+                {
+                    tournaments.push(tournament);
+                }
+                Ok(())
+            })
+            .await;
+        tournaments.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        tournaments.into_iter().map(TournamentInfo::from).collect()
+    }
+    
+    /// Get a tournament's standings, ranked by points and then Buchholz
+    async fn tournament_standings(&self, tournament_id: String) -> Vec<Standing> {
+        self.state
+            .tournaments
+            .get(&tournament_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|tournament| tournament.standings())
+            .unwrap_or_default()
+    }
+    
+    /// Get a tournament's pairings, optionally for a single round
+    async fn tournament_pairings(&self, tournament_id: String, round: Option<u8>) -> Vec<Pairing> {
+        let Some(tournament) = self.state.tournaments.get(&tournament_id).await.ok().flatten() else {
+            return Vec::new();
+        };
+        tournament
+            .pairings
+            .into_iter()
+            .filter(|pairing| round.is_none_or(|round| pairing.round == round))
+            .collect()
+    }
+    
+    /// Get today's quests, with a player's progress on them if an address is given
+    async fn daily_quests(&self, address: Option<AccountOwner>) -> Vec<QuestInfo> {
+        let day = self.now / MICROS_PER_DAY;
+        let progress = match address {
+            Some(address) => self.state.quest_progress.get(&address).await.ok().flatten(),
+            None => None,
         }
+        .filter(|progress| progress.day == day)
+        .unwrap_or_default();
+        
+        quests::daily_quests(day)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, quest)| {
+                let (xp_reward, token_reward) = quest.reward();
+                QuestInfo {
+                    description: quest.description(),
+                    target: quest.target(),
+                    xp_reward,
+                    token_reward,
+                    progress: progress.progress.get(idx).copied().unwrap_or(0),
+                    completed: progress.completed.get(idx).copied().unwrap_or(false),
+                }
+            })
+            .collect()
+    }
+    
+    /// Get a player's season pass, for the current season unless one is given
+    async fn season_pass(&self, address: AccountOwner, season: Option<u32>) -> SeasonPassInfo {
+        let season = season.unwrap_or(*self.state.current_season.get());
+        let pass = self
+            .state
+            .season_passes
+            .get(&(season, address))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let config = self.state.config.get();
+        SeasonPassInfo {
+            season,
+            xp: pass.xp,
+            tier: pass.tier(config.pass_xp_per_tier, config.pass_tiers),
+            tiers: config.pass_tiers,
+            xp_per_tier: config.pass_xp_per_tier,
+            premium: pass.premium,
+            claimed_free: pass.claimed_free,
+            claimed_premium: pass.claimed_premium,
+        }
+    }
+    
+    /// Get whose move it is in a room
+    async fn room_turn(&self, room_id: String) -> Option<TurnInfo> {
+        let room = self.state.rooms.get(&room_id).await.ok().flatten()?;
+        let (current_player, move_number) = room.current_turn()?;
+        Some(TurnInfo {
+            current_player,
+            move_number,
+            deadline: room.turn_deadline,
+        })
+    }
+    
+    /// Get every validated move in a room, in order, for replays
+    async fn room_moves(&self, room_id: String) -> Vec<MoveRecord> {
+        self.state.room_moves(&room_id).await
+    }
+    
+    /// Page through a room's chat history, oldest first. Messages from
+    /// players `viewer` has muted are left out.
+    async fn room_chat(
+        &self,
+        room_id: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        viewer: Option<AccountOwner>,
+    ) -> Vec<ChatMessage> {
+        let offset = offset.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(50) as usize;
+        let mut messages = self.state.room_chat_page(&room_id, offset, limit).await;
+        
+        // Hide whoever the viewer has muted
+        if let Some(viewer) = viewer {
+            if let Ok(Some(profile)) = self.state.users.get(&viewer).await {
+                messages.retain(|message| !profile.muted.contains(&message.sender));
+            }
+        }
+        messages
+    }
+    
+    /// Games played, active players and tokens wagered on each of the last
+    /// `days` days (30 by default, at most a year), oldest first. A player
+    /// counts as active on a day they finished a game.
+    async fn daily_activity(&self, days: Option<u32>) -> Vec<DailyActivityInfo> {
+        let days = days.unwrap_or(30).clamp(1, 366) as u64;
+        let today = self.now / MICROS_PER_DAY;
+        self.state
+            .get_daily_activity(today.saturating_sub(days - 1), today)
+            .await
+            .into_iter()
+            .map(|(day, games_played, unique_players, tokens_wagered)| DailyActivityInfo {
+                day,
+                games_played,
+                unique_players,
+                tokens_wagered,
+            })
+            .collect()
     }
     
     /// Get global stats
@@ -104,19 +1038,458 @@ impl<'a> QueryRoot<'a> {
         GlobalStats {
             total_games: *self.state.total_games_played.get().unwrap_or(&0),
             total_players: *self.state.total_players.get().unwrap_or(&0),
-            games_available: 4, // Snake, TicTacToe, SnakeLadders, Uno
+            games_available: 23, // One per GameType variant
         }
     }
 }
 
+/// GraphQL Subscription Root.
+///
+/// The service is instantiated afresh for each new block, so every
+/// subscription yields the latest snapshot of what it watches; clients stay
+/// subscribed to receive the next one as the chain advances.
+struct SubscriptionRoot<'a> {
+    state: &'a GameStationState,
+}
+
+#[Subscription]
+impl<'a> SubscriptionRoot<'a> {
+    /// Follow a room's status and players as the match progresses
+    async fn room_updated(&self, room_id: String) -> impl Stream<Item = Option<RoomInfo>> {
+        let room = self.state.rooms.get(&room_id).await.ok().flatten().map(RoomInfo::from);
+        stream::once(async move { room })
+    }
+    
+    /// Follow the top of a game's all-time leaderboard
+    async fn leaderboard_changed(
+        &self,
+        game_type: GameType,
+        limit: Option<u32>,
+    ) -> impl Stream<Item = Vec<LeaderboardEntry>> {
+        let limit = limit.unwrap_or(10);
+        let entries = self.state.get_leaderboard(game_type, LeaderboardKind::Score, limit).await;
+        stream::once(async move { entries })
+    }
+}
+
 /// Room information for GraphQL
 #[derive(SimpleObject)]
 struct RoomInfo {
     room_id: String,
-    game_type: String,
+    game_type: GameType,
     player_count: u32,
     max_players: u32,
-    status: String,
+    status: RoomStatus,
+    visibility: RoomVisibility,
+    /// Fee each player pays in to join
+    entry_fee: Amount,
+    /// Entry fees held so far, which the winner takes or a draw splits
+    prize_pool: Amount,
+    /// Stake each player locks on top of the entry fee; joiners must accept it
+    wager: Amount,
+    /// Wager stakes held so far, which the winner takes or a draw refunds
+    wager_pool: Amount,
+    /// House rake on the prizes in basis points, fixed when the room was created
+    rake_bps: u16,
+    /// Rake the house would take from the prizes held so far, if the room
+    /// ended now with a winner. Free-play tokens are not raked.
+    rake: Amount,
+    /// Temporary chain the match is being played on, if it has one
+    host_chain: Option<ChainId>,
+    /// Time until which the room is featured in the lobby
+    boosted_until: Option<u64>,
+    /// Players who confirmed during a ready check
+    ready: Vec<AccountOwner>,
+    /// Time by which every player must be ready, during a ready check
+    ready_deadline: Option<u64>,
+}
+
+impl From<GameRoom> for RoomInfo {
+    fn from(room: GameRoom) -> Self {
+        RoomInfo {
+            wager_pool: room.wager_pool(),
+            rake: room.expected_rake(),
+            room_id: room.room_id,
+            game_type: room.game_type,
+            player_count: room.players.len() as u32,
+            max_players: room.max_players as u32,
+            status: room.status,
+            visibility: room.visibility,
+            entry_fee: room.entry_fee,
+            prize_pool: room.escrow,
+            wager: room.wager,
+            rake_bps: room.rake_bps,
+            host_chain: room.host_chain,
+            boosted_until: room.boosted_until,
+            ready: room.ready,
+            ready_deadline: room.ready_deadline,
+        }
+    }
+}
+
+/// Clan information and member stats for GraphQL
+#[derive(SimpleObject)]
+struct ClanInfo {
+    tag: String,
+    name: String,
+    leader: Option<AccountOwner>,
+    member_count: u32,
+    /// Players waiting for their join request to be reviewed
+    pending_requests: Vec<AccountOwner>,
+    /// Arcade credits in the clan treasury
+    treasury: u64,
+    created_at: u64,
+    /// Sum of the members' XP
+    total_xp: u64,
+    /// Room games finished by the members
+    room_games_played: u32,
+    tictactoe_wins: u32,
+    /// Best Snake score among the members
+    best_snake_score: u32,
+}
+
+/// Clan war information for GraphQL
+#[derive(SimpleObject)]
+struct ClanWarInfo {
+    war_id: String,
+    clans: Vec<String>,
+    /// Points of each clan, in the same order as `clans`
+    points: Vec<u64>,
+    starts_at: u64,
+    ends_at: u64,
+    reward: u64,
+    winner: Option<String>,
+    settled: bool,
+}
+
+impl From<ClanWar> for ClanWarInfo {
+    fn from(war: ClanWar) -> Self {
+        let (clans, points) = war.scores.into_iter().unzip();
+        ClanWarInfo {
+            war_id: war.war_id,
+            clans,
+            points,
+            starts_at: war.starts_at,
+            ends_at: war.ends_at,
+            reward: war.reward,
+            winner: war.winner,
+            settled: war.settled,
+        }
+    }
+}
+
+/// A clan member for GraphQL
+#[derive(SimpleObject)]
+struct ClanMemberInfo {
+    address: AccountOwner,
+    username: String,
+    role: ClanRole,
+    level: u32,
+}
+
+/// Tournament information for GraphQL
+#[derive(SimpleObject)]
+struct TournamentInfo {
+    tournament_id: String,
+    game_type: GameType,
+    creator: AccountOwner,
+    format: String,
+    status: TournamentStatus,
+    max_players: u32,
+    entry_fee: Amount,
+    /// Native entry fees held for the winner
+    prize_pool: Amount,
+    players: Vec<AccountOwner>,
+    current_round: u8,
+    total_rounds: u8,
+    /// When check-in closes, once it has opened
+    check_in_deadline: Option<u64>,
+    checked_in: Vec<AccountOwner>,
+    /// The bracket: every round paired so far, in order
+    rounds: Vec<TournamentRound>,
+    standings: Vec<Standing>,
+    created_at: u64,
+}
+
+impl From<Tournament> for TournamentInfo {
+    fn from(tournament: Tournament) -> Self {
+        TournamentInfo {
+            players: tournament.player_list(),
+            total_rounds: tournament.total_rounds(),
+            prize_pool: tournament.prize_pool(),
+            rounds: tournament.rounds(),
+            standings: tournament.standings(),
+            tournament_id: tournament.tournament_id,
+            game_type: tournament.game_type,
+            creator: tournament.creator,
+            format: format!("{:?}", tournament.format),
+            status: tournament.status,
+            max_players: tournament.max_players,
+            entry_fee: tournament.entry_fee,
+            current_round: tournament.current_round,
+            check_in_deadline: tournament.check_in_deadline,
+            checked_in: tournament.checked_in,
+            created_at: tournament.created_at,
+        }
+    }
+}
+
+/// A room's game for GraphQL. Games with their own field have a typed
+/// state there; `state` has every game's, as JSON.
+#[derive(SimpleObject)]
+struct RoomGameStateInfo {
+    room_id: String,
+    game_type: GameType,
+    status: RoomStatus,
+    players: Vec<AccountOwner>,
+    /// Player whose move it is, for games with a turn order
+    current_turn: Option<AccountOwner>,
+    /// Time by which the current player must move
+    turn_deadline: Option<u64>,
+    /// Round of the series being played, from 1
+    round: u32,
+    tic_tac_toe: Option<TicTacToeState>,
+    snake: Option<SnakeGameState>,
+    /// The engine state, `None` before the game starts
+    state: Option<Json<GameState>>,
+}
+
+/// A room hosted on another chain, as seen from this chain
+#[derive(SimpleObject)]
+struct RoomMirrorInfo {
+    room_id: String,
+    players: Vec<AccountOwner>,
+    status: RoomStatus,
+    move_count: u32,
+    winner: Option<AccountOwner>,
+}
+
+/// A room from the point of view of one of its players
+#[derive(SimpleObject)]
+struct PlayerRoomInfo {
+    room: RoomInfo,
+    /// Whether the game is waiting on this player's move
+    your_turn: bool,
+    /// Time by which the current player must move
+    turn_deadline: Option<u64>,
+}
+
+/// A page of rooms for lobby browsing
+#[derive(SimpleObject)]
+struct RoomPage {
+    rooms: Vec<RoomInfo>,
+    /// Cursor for the next page, `None` on the last page
+    next_cursor: Option<String>,
+}
+
+/// A friend and their online status
+#[derive(SimpleObject)]
+struct FriendInfo {
+    address: AccountOwner,
+    online: bool,
+    profile: Option<UserProfile>,
+}
+
+/// Results between two players
+#[derive(SimpleObject)]
+struct HeadToHeadInfo {
+    player_a: AccountOwner,
+    player_b: AccountOwner,
+    /// Matches `player_a` won against `player_b`
+    wins: u32,
+    /// Matches `player_a` lost to `player_b`
+    losses: u32,
+    draws: u32,
+    recent: Vec<Encounter>,
+}
+
+/// A player's place in a `topPlayers` ranking
+#[derive(SimpleObject)]
+struct TopPlayer {
+    /// 1 for the top player
+    rank: u32,
+    address: AccountOwner,
+    profile: UserProfile,
+}
+
+/// A player found by username search
+#[derive(SimpleObject)]
+struct UserSearchResult {
+    address: AccountOwner,
+    profile: UserProfile,
+}
+
+/// One of the day's quests and a player's progress on it
+#[derive(SimpleObject)]
+struct QuestInfo {
+    description: String,
+    target: u32,
+    xp_reward: u64,
+    token_reward: Amount,
+    progress: u32,
+    completed: bool,
+}
+
+/// A player's progress on a season pass
+#[derive(SimpleObject)]
+struct SeasonPassInfo {
+    season: u32,
+    xp: u64,
+    /// Highest tier reached
+    tier: u8,
+    tiers: u8,
+    xp_per_tier: u64,
+    premium: bool,
+    claimed_free: Vec<u8>,
+    claimed_premium: Vec<u8>,
+}
+
+/// Turn information for an active room
+#[derive(SimpleObject)]
+struct TurnInfo {
+    current_player: AccountOwner,
+    move_number: u32,
+    /// Time (in microseconds) by which the current player must move
+    deadline: Option<u64>,
+}
+
+/// The season leaderboards are currently recorded in
+#[derive(SimpleObject)]
+struct SeasonInfo {
+    season: u32,
+    started_at: u64,
+}
+
+/// A range of scores on a leaderboard's distribution
+#[derive(SimpleObject)]
+struct ScoreBucket {
+    min_score: u64,
+    max_score: u64,
+    players: u32,
+}
+
+/// Play volume on one day
+#[derive(SimpleObject)]
+struct DailyActivityInfo {
+    /// Days since the Unix epoch
+    day: u64,
+    games_played: u64,
+    unique_players: u64,
+    tokens_wagered: Amount,
+}
+
+/// Totals for one game type
+#[derive(SimpleObject)]
+struct GameStatsInfo {
+    game_type: GameType,
+    total_games: u64,
+    unique_players: u64,
+    average_score: f64,
+    median_score: f64,
+    /// Games played in the last 24 hours, counted by the hour
+    games_last_day: u64,
+}
+
+/// A player's place on a leaderboard
+#[derive(SimpleObject)]
+struct PlayerRank {
+    /// 1 for the top score
+    position: u32,
+    score: u64,
+    games_played: u32,
+}
+
+/// A shop item for GraphQL
+#[derive(SimpleObject)]
+struct CosmeticInfo {
+    item_id: String,
+    name: String,
+    slot: CosmeticSlot,
+    /// Price in arcade credits
+    price: u64,
+}
+
+impl From<&cosmetics::CosmeticItem> for CosmeticInfo {
+    fn from(item: &cosmetics::CosmeticItem) -> Self {
+        CosmeticInfo {
+            item_id: item.item_id.to_string(),
+            name: item.name.to_string(),
+            slot: item.slot,
+            price: item.price,
+        }
+    }
+}
+
+/// An owned cosmetic for GraphQL
+#[derive(SimpleObject)]
+struct InventoryItem {
+    item_id: String,
+    /// Shop name; `None` for items that are not sold in the shop
+    name: Option<String>,
+    slot: Option<CosmeticSlot>,
+    equipped: bool,
+}
+
+/// House rake and treasury for GraphQL
+#[derive(SimpleObject)]
+struct TreasuryInfo {
+    rake_bps: u16,
+    /// Fees collected and not yet withdrawn
+    balance: Amount,
+    /// Every fee ever collected
+    fees_collected: Amount,
+}
+
+/// A Minesweeper session for GraphQL
+#[derive(SimpleObject)]
+struct MinesweeperSessionInfo {
+    /// Hex commitment to the board seed the dealer will reveal
+    board_commitment: String,
+    started_at: u64,
+    /// Clicks submitted so far
+    clicks: u32,
+    submitted_at: Option<u64>,
+}
+
+/// A day of the daily word game for GraphQL
+#[derive(SimpleObject)]
+struct DailyWordInfo {
+    day: u64,
+    published_at: u64,
+    answer: Option<String>,
+}
+
+/// A guess at a daily word for GraphQL
+#[derive(SimpleObject)]
+struct WordGuessInfo {
+    guess: String,
+    /// One character per letter once the word is revealed: `G` right
+    /// place, `Y` elsewhere in the word, `-` not in it
+    feedback: Option<String>,
+}
+
+/// A blackjack hand for GraphQL. Cards 0 to 51 run ace to king in each suit.
+#[derive(SimpleObject)]
+struct BlackjackHandInfo {
+    bet: Amount,
+    doubled: bool,
+    player_cards: Vec<u8>,
+    dealer_cards: Vec<u8>,
+    player_total: u8,
+    dealer_total: u8,
+    /// Whether the hand is waiting on the house to deal
+    awaiting_card: bool,
+    updated_at: u64,
+}
+
+/// A daily Sudoku for GraphQL
+#[derive(SimpleObject)]
+struct DailySudokuInfo {
+    day: u64,
+    /// Start of the day, from which solve times are measured
+    published_at: u64,
+    /// 81 digits row by row, 0 for an empty cell
+    givens: Vec<u8>,
 }
 
 /// Global stats for the game station
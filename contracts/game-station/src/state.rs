@@ -2,161 +2,3445 @@
 //! 
 //! This module defines the persistent state for the Game Station contract.
 
-use linera_sdk::views::{MapView, RegisterView, RootView, ViewStorageContext};
-use crate::{GameType, LeaderboardEntry, UserProfile, GameState, RoomStatus};
+use linera_sdk::views::{
+    CollectionView, LogView, MapView, QueueView, RegisterView, RootView, View, ViewError,
+    ViewStorageContext,
+};
+use linera_sdk::base::{AccountOwner, Amount, ChainId};
+use crate::{
+    cosmetics,
+    games::{
+        blackjack::BlackjackHand,
+        daily_word, dots_and_boxes, gomoku,
+        randomness::SeededRng,
+        trivia::TriviaQuestion,
+    },
+    invite_proof,
+    quests::{QuestEvent, QuestGoal, QuestProgress},
+    referral_code,
+    tournament::{self, Pairing, Standing, TournamentFormat, TournamentRound, TournamentStatus},
+    BattleshipState, ChatMessage, CheckersState, ChessState, ClanRole, ClanStanding,
+    ConnectFourState, CosmeticSlot, CreditReason, CreditTransaction, DotsAndBoxesState, Encounter,
+    EquippedCosmetic, GameRecord, GameSession, GameState, GameStationConfig, GameType, GomokuState,
+    LeaderboardEntry, LeaderboardKind, LeaderboardSort, LeaderboardWindow, LudoState, MatchRecord,
+    MatchResult, MemoryState, MinesweeperClick, MoveRecord, PlayerRanking, PlayerReport, PokerState,
+    RecentGame, ReversiState, RockPaperScissorsState, RoomStatus, RoomVisibility, SnakeLaddersState,
+    SudokuSolve, TicTacToeState, TipRecord, TriviaState, UnoState, UserProfile, UserProfileV1,
+    VersionedUserProfile, WordLeaderboardEntry, WordStats, YahtzeeState, CREDITS_PER_QUEST,
+    MAX_CHAT_MESSAGES_PER_ROOM, MAX_CREDIT_HISTORY, MAX_HEAD_TO_HEAD_ENCOUNTERS, MAX_MATCH_HISTORY,
+    MAX_PENDING_FRIEND_REQUESTS, MAX_RECENT_GAMES, MAX_SEARCH_PREFIX_LEN, MAX_STREAK_BONUS_DAYS,
+    MAX_TIP_HISTORY, MICROS_PER_DAY, MICROS_PER_HOUR, RATING_K_FACTOR, REFERRAL_CREDITS,
+    REFERRAL_GAMES_REQUIRED, REFERRAL_XP, SCHEMA_VERSION, STREAK_TOKENS_PER_DAY, STREAK_XP_PER_DAY,
+};
 
 /// The main application state stored on-chain
 #[derive(RootView)]
 #[view(context = "ViewStorageContext")]
 pub struct GameStationState {
-    /// User profiles indexed by wallet address
-    pub users: MapView<String, UserProfile>,
+    /// Deployment configuration set at instantiation
+    pub config: RegisterView<GameStationConfig>,
     
-    /// Leaderboards indexed by game type
-    pub leaderboards: MapView<String, Vec<LeaderboardEntry>>,
+    /// User profiles stored unversioned, in the `UserProfileV1` layout,
+    /// before schema version 1. Emptied by the migration into `users`.
+    pub legacy_users: MapView<AccountOwner, UserProfileV1>,
     
-    /// Active game rooms indexed by room ID
+    /// Owner of each claimed username, indexed by the lowercased name
+    pub usernames: MapView<String, AccountOwner>,
+    
+    /// Owner of each referral code
+    pub referral_codes: MapView<String, AccountOwner>,
+    
+    /// Players whose lowercased username starts with a prefix, for search
+    pub username_prefixes: MapView<String, Vec<AccountOwner>>,
+    
+    /// Every player in the order of each `PlayerRanking`
+    pub player_rankings: PlayerRankings,
+    
+    /// Leaderboards keyed by (season, board name) before schema version 2.
+    /// The migration moves every board it recognises into `leaderboards`.
+    pub legacy_leaderboards: CollectionView<(u32, String), Leaderboard>,
+    
+    /// Season that new results are recorded in
+    pub current_season: RegisterView<u32>,
+    
+    /// When the current season started (microseconds)
+    pub season_started_at: RegisterView<u64>,
+    
+    /// Global leaderboards keyed by game name before schema version 2
+    pub legacy_global_leaderboards: MapView<String, Vec<LeaderboardEntry>>,
+    
+    /// Time-windowed leaderboards keyed by "<board name>:<window>" before
+    /// schema version 2
+    pub legacy_windowed_leaderboards: MapView<String, WindowedLeaderboard>,
+    
+    /// Active game rooms indexed by room ID. Write through `save_room` and
+    /// `delete_room` so the lobby listings and player rooms stay in step.
     pub rooms: MapView<String, GameRoom>,
     
-    /// High scores for Snake game indexed by player address
-    pub snake_high_scores: MapView<String, u32>,
+    /// Lobby listings of the public rooms, indexed by room ID
+    pub room_listings: MapView<String, RoomListing>,
+    
+    /// IDs of the unfinished rooms each player is seated in
+    pub player_rooms: MapView<AccountOwner, Vec<String>>,
+    
+    /// Rooms hosted on other chains that players here take part in, indexed by room ID
+    pub room_mirrors: MapView<String, RoomMirror>,
+    
+    /// Tournaments hosted on this chain, indexed by tournament ID
+    pub tournaments: MapView<String, Tournament>,
+    
+    /// Clans, indexed by their uppercased tag. Each member's profile names
+    /// their clan.
+    pub clans: MapView<String, Clan>,
+    
+    /// Clan wars, indexed by war ID
+    pub clan_wars: MapView<String, ClanWar>,
+    
+    /// Clan leaderboard of each season, best first
+    pub clan_leaderboards: MapView<u32, Vec<ClanStanding>>,
+    
+    /// Recent matches per player before schema version 3, which named the
+    /// game type. Emptied by the migration into `match_history`.
+    pub legacy_match_history: CollectionView<AccountOwner, QueueView<LegacyMatchRecord>>,
+    
+    /// Recent arcade credit transactions per player, newest at the back
+    pub credit_history: CollectionView<AccountOwner, QueueView<CreditTransaction>>,
+    
+    /// Recent tips sent or received per player, newest at the back
+    pub tip_history: CollectionView<AccountOwner, QueueView<TipRecord>>,
+    
+    /// Friends of each player, indexed by account owner
+    pub friends: MapView<AccountOwner, Vec<AccountOwner>>,
+    
+    /// Incoming friend requests, indexed by the recipient
+    pub friend_requests: MapView<AccountOwner, Vec<AccountOwner>>,
+    
+    /// Matchmaking queues indexed by game type
+    pub matchmaking: MapView<GameType, Vec<MatchmakingTicket>>,
+    
+    /// Validated moves per room in play order, indexed by room ID
+    pub move_logs: CollectionView<String, LogView<MoveRecord>>,
+    
+    /// Chat history per room, indexed by room ID
+    pub room_chat: CollectionView<String, LogView<ChatMessage>>,
+    
+    /// Season pass progress, indexed by (season, account owner)
+    pub season_passes: MapView<(u32, AccountOwner), SeasonPass>,
+    
+    /// Progress on the day's quests, indexed by account owner
+    pub quest_progress: MapView<AccountOwner, QuestProgress>,
+    
+    /// High scores for Snake game indexed by account owner
+    pub snake_high_scores: MapView<AccountOwner, u32>,
+    
+    /// Unfinished 2048 sessions indexed by account owner
+    pub twenty_forty_eight_sessions: MapView<AccountOwner, GameSession>,
+    
+    /// Best final Yahtzee score per player
+    pub yahtzee_high_scores: MapView<AccountOwner, u32>,
+    
+    /// Unfinished Tetris sessions indexed by account owner
+    pub tetris_sessions: MapView<AccountOwner, GameSession>,
+    
+    /// Minesweeper board commitments not dealt yet, in dealing order
+    pub minesweeper_boards: QueueView<[u8; 32]>,
+    
+    /// Minesweeper sessions waiting for clicks or for the board reveal,
+    /// indexed by account owner
+    pub minesweeper_sessions: MapView<AccountOwner, MinesweeperSession>,
+    
+    /// Words of the daily word game, indexed by day
+    pub daily_words: MapView<u64, DailyWord>,
+    
+    /// Each player's guesses at each day's word, indexed by (day, account owner)
+    pub word_guesses: MapView<(u64, AccountOwner), Vec<String>>,
+    
+    /// Players who guessed at each day's word, indexed by day
+    pub word_players: MapView<u64, Vec<AccountOwner>>,
+    
+    /// Daily word game records indexed by account owner
+    pub word_stats: MapView<AccountOwner, WordStats>,
+    
+    /// Daily word leaderboard, best first
+    pub word_leaderboard: RegisterView<Vec<WordLeaderboardEntry>>,
+    
+    /// Ends of the house's hash chains not dealt from yet, in dealing order
+    pub blackjack_shoes: QueueView<[u8; 32]>,
+    
+    /// Blackjack hands in play, indexed by account owner
+    pub blackjack_hands: MapView<AccountOwner, BlackjackSession>,
+    
+    /// Native tokens held in the application's account to pay blackjack
+    /// winnings, less what hands in play have set aside
+    pub house_bankroll: RegisterView<Amount>,
+    
+    /// Admin-curated trivia questions
+    pub trivia_questions: LogView<TriviaQuestion>,
+    
+    /// Each player's time on each daily Sudoku they solved, indexed by
+    /// (day, account owner)
+    pub sudoku_solve_times: MapView<(u64, AccountOwner), u64>,
+    
+    /// Fastest solves of each daily Sudoku, fastest first, indexed by day
+    pub sudoku_leaderboards: MapView<u64, Vec<SudokuSolve>>,
+    
+    /// Results between each pair of players, indexed by the pair in
+    /// ascending order
+    pub head_to_head: MapView<(AccountOwner, AccountOwner), HeadToHead>,
+    
+    /// Most recently finished games on this chain, oldest first
+    pub recent_games: QueueView<RecentGame>,
+    
+    /// Totals for each game type, indexed by the game type's `Debug` name
+    pub game_stats: CollectionView<String, GameStats>,
+    
+    /// Play volume on each day, indexed by days since the Unix epoch
+    pub daily_activity: CollectionView<u64, DailyActivity>,
+    
+    /// Total games played counter
+    pub total_games_played: RegisterView<u64>,
+    
+    /// Total players registered
+    pub total_players: RegisterView<u64>,
+    
+    /// Set by the admin to stop accepting operations from players
+    pub paused: RegisterView<bool>,
+    
+    /// Rake and season pass sales not yet withdrawn, held in the
+    /// application's account
+    pub treasury: RegisterView<Amount>,
+    
+    /// Total rake and season pass sales ever collected
+    pub fees_collected: RegisterView<Amount>,
+    
+    /// Banned players, with the time (microseconds) they were banned
+    pub banned: MapView<AccountOwner, u64>,
+    
+    /// Reports filed against each player, one per reporter
+    pub reports: MapView<AccountOwner, Vec<PlayerReport>>,
+    
+    /// Linked identities: secondary address -> primary address
+    pub identity_links: MapView<AccountOwner, AccountOwner>,
+    
+    /// Consents collected for pending identity links, keyed by (primary, secondary)
+    pub identity_link_consents: MapView<(AccountOwner, AccountOwner), Vec<AccountOwner>>,
+    
+    /// Leaderboards indexed by (season, game type, kind). Past seasons are
+    /// kept as archives.
+    pub leaderboards: CollectionView<LeaderboardKey, Leaderboard>,
+    
+    /// Leaderboards merged from every chain, kept on the hub chain and
+    /// indexed by game type and kind
+    pub global_leaderboards: MapView<(GameType, LeaderboardKind), Vec<LeaderboardEntry>>,
+    
+    /// Time-windowed score leaderboards indexed by game type and window
+    pub windowed_leaderboards: MapView<(GameType, LeaderboardWindow), WindowedLeaderboard>,
+    
+    /// Rooms created on this chain so far, which numbers the next room ID
+    pub room_counter: RegisterView<u64>,
+    
+    /// User profiles indexed by account owner
+    pub users: UserProfiles,
+    
+    /// `SCHEMA_VERSION` the stored state has been migrated to. Fields are
+    /// stored by position, so new ones go after it.
+    pub schema_version: RegisterView<u32>,
+    
+    /// Tournaments created on this chain so far, which numbers the next
+    /// tournament ID
+    pub tournament_counter: RegisterView<u64>,
+    
+    /// High scores of the replay-verified single-player games, indexed by
+    /// game type and account owner
+    pub high_scores: MapView<(GameType, AccountOwner), u32>,
+    
+    /// Recent matches per player, newest at the back, indexed by account owner
+    pub match_history: CollectionView<AccountOwner, QueueView<MatchRecord>>,
+}
+
+/// Key of a season's leaderboard: the season, the game and which of its
+/// leaderboards
+pub type LeaderboardKey = (u32, GameType, LeaderboardKind);
+
+/// Board names leaderboards were stored under before schema version 2
+const LEGACY_BOARDS: [(&str, GameType, LeaderboardKind); 12] = [
+    ("snake", GameType::Snake, LeaderboardKind::Score),
+    ("2048", GameType::TwentyFortyEight, LeaderboardKind::Score),
+    ("tetris", GameType::Tetris, LeaderboardKind::Score),
+    ("yahtzee", GameType::Yahtzee, LeaderboardKind::Score),
+    ("minesweeper", GameType::Minesweeper, LeaderboardKind::Score),
+    ("tictactoe_rating", GameType::TicTacToe, LeaderboardKind::Rating),
+    ("connect_four_rating", GameType::ConnectFour, LeaderboardKind::Rating),
+    ("chess_rating", GameType::Chess, LeaderboardKind::Rating),
+    ("checkers_rating", GameType::Checkers, LeaderboardKind::Rating),
+    ("battleship_rating", GameType::Battleship, LeaderboardKind::Rating),
+    ("reversi_rating", GameType::Reversi, LeaderboardKind::Rating),
+    ("gomoku_rating", GameType::Gomoku, LeaderboardKind::Rating),
+];
+
+/// Game and kind of the leaderboard stored under a legacy board name
+fn legacy_board(name: &str) -> Option<(GameType, LeaderboardKind)> {
+    LEGACY_BOARDS
+        .iter()
+        .find(|(board, ..)| *board == name)
+        .map(|&(_, game_type, kind)| (game_type, kind))
+}
+
+/// Key ordering a leaderboard's entries as `LeaderboardEntry::cmp_rank`
+/// does: the score subtracted from the maximum, the time it was reached and
+/// the games played, all in big-endian bytes, then the player. Map keys are
+/// iterated in the order of their serialized bytes, so this runs from the
+/// top of the board down.
+pub type RankKey = ([u8; 8], [u8; 8], [u8; 4], AccountOwner);
+
+/// Key ordering a leaderboard's entries under any other `LeaderboardSort`:
+/// the sorted value subtracted from the maximum in big-endian bytes, then
+/// the player.
+pub type SortKey = ([u8; 8], AccountOwner);
+
+fn sort_key(entry: &LeaderboardEntry, sort: LeaderboardSort) -> SortKey {
+    let value = match sort {
+        LeaderboardSort::Score => entry.score,
+        LeaderboardSort::WinRate => entry.win_rate as u64,
+        LeaderboardSort::GamesPlayed => entry.games_played as u64,
+        LeaderboardSort::Recent => entry.timestamp,
+    };
+    ((u64::MAX - value).to_be_bytes(), entry.player_address)
+}
+
+fn rank_key(entry: &LeaderboardEntry) -> RankKey {
+    (
+        (u64::MAX - entry.score).to_be_bytes(),
+        entry.timestamp.to_be_bytes(),
+        entry.games_played.to_be_bytes(),
+        entry.player_address,
+    )
+}
+
+/// Histogram bucket a score falls in. Scores under 16 have a bucket each,
+/// and each doubling above that is split into 16 buckets, so a bucket spans
+/// about 6% of the scores in it whatever the game's scale.
+pub fn score_bucket(score: u64) -> u32 {
+    if score < 16 {
+        return score as u32;
+    }
+    let exponent = 63 - score.leading_zeros();
+    (exponent - 3) * 16 + ((score >> (exponent - 4)) & 15) as u32
+}
+
+/// Lowest score in a histogram bucket
+pub fn bucket_floor(bucket: u32) -> u64 {
+    if bucket < 16 {
+        return bucket as u64;
+    }
+    (16 + (bucket % 16) as u64) << (bucket / 16 - 1)
+}
+
+/// A leaderboard with every player who has a score on it. Entries are
+/// indexed by rank as well as by player, so a score can be updated without
+/// touching the rest of the board and the top can be read without loading
+/// all of it.
+#[derive(View)]
+#[view(context = "ViewStorageContext")]
+pub struct Leaderboard {
+    pub entries: MapView<AccountOwner, LeaderboardEntry>,
+    pub ranking: MapView<RankKey, ()>,
+    /// Entries by each `LeaderboardSort` other than score
+    pub by_win_rate: MapView<SortKey, ()>,
+    pub by_games_played: MapView<SortKey, ()>,
+    pub by_recency: MapView<SortKey, ()>,
+    /// Players whose score falls in each `score_bucket`
+    pub histogram: MapView<u32, u32>,
+}
+
+impl Leaderboard {
+    /// Store a player's entry, moving it to the rank its score now earns
+    pub async fn set_entry(&mut self, entry: LeaderboardEntry) -> Result<(), ViewError> {
+        let player = entry.player_address;
+        let old = self.entries.get(&player).await?;
+        if let Some(old) = &old {
+            self.ranking.remove(&rank_key(old))?;
+            self.count_in_bucket(old.score, false).await?;
+        }
+        self.ranking.insert(&rank_key(&entry), ())?;
+        self.count_in_bucket(entry.score, true).await?;
+        for sort in LeaderboardSort::ALL {
+            let Some(index) = self.sort_index_mut(sort) else {
+                continue;
+            };
+            if let Some(old) = &old {
+                index.remove(&sort_key(old, sort))?;
+            }
+            index.insert(&sort_key(&entry, sort), ())?;
+        }
+        self.entries.insert(&player, entry)
+    }
+    
+    /// Take a player off the board. Returns whether they were on it.
+    pub async fn remove_entry(&mut self, player: &AccountOwner) -> Result<bool, ViewError> {
+        let Some(old) = self.entries.get(player).await? else {
+            return Ok(false);
+        };
+        self.ranking.remove(&rank_key(&old))?;
+        self.count_in_bucket(old.score, false).await?;
+        for sort in LeaderboardSort::ALL {
+            if let Some(index) = self.sort_index_mut(sort) {
+                index.remove(&sort_key(&old, sort))?;
+            }
+        }
+        self.entries.remove(player)?;
+        Ok(true)
+    }
+    
+    /// Index for `sort`, or `None` for score, which `ranking` orders
+    fn sort_index(&self, sort: LeaderboardSort) -> Option<&MapView<SortKey, ()>> {
+        match sort {
+            LeaderboardSort::Score => None,
+            LeaderboardSort::WinRate => Some(&self.by_win_rate),
+            LeaderboardSort::GamesPlayed => Some(&self.by_games_played),
+            LeaderboardSort::Recent => Some(&self.by_recency),
+        }
+    }
+    
+    fn sort_index_mut(&mut self, sort: LeaderboardSort) -> Option<&mut MapView<SortKey, ()>> {
+        match sort {
+            LeaderboardSort::Score => None,
+            LeaderboardSort::WinRate => Some(&mut self.by_win_rate),
+            LeaderboardSort::GamesPlayed => Some(&mut self.by_games_played),
+            LeaderboardSort::Recent => Some(&mut self.by_recency),
+        }
+    }
+    
+    /// Add a player to, or take one from, the histogram bucket of `score`
+    async fn count_in_bucket(&mut self, score: u64, add: bool) -> Result<(), ViewError> {
+        let bucket = score_bucket(score);
+        let count = self.histogram.get(&bucket).await?.unwrap_or(0);
+        match if add { count + 1 } else { count.saturating_sub(1) } {
+            0 => self.histogram.remove(&bucket),
+            count => self.histogram.insert(&bucket, count),
+        }
+    }
+    
+    /// Players in each nonempty histogram bucket, lowest scores first
+    pub async fn distribution(&self) -> Result<Vec<(u32, u32)>, ViewError> {
+        let mut buckets = Vec::new();
+        for bucket in self.histogram.indices().await? {
+            buckets.push((bucket, self.histogram.get(&bucket).await?.unwrap_or(0)));
+        }
+        buckets.sort_unstable();
+        Ok(buckets)
+    }
+    
+    /// A player's 1-based position on the board, counting every player
+    /// ranked above them, with their entry
+    pub async fn position(
+        &self,
+        player: &AccountOwner,
+    ) -> Result<Option<(u32, LeaderboardEntry)>, ViewError> {
+        let Some(entry) = self.entries.get(player).await? else {
+            return Ok(None);
+        };
+        // Everyone ranked higher sorts first, so count keys up to the player's own
+        let own_key = rank_key(&entry);
+        let mut ahead = 0;
+        self.ranking
+            .for_each_index_while(|key| {
+                let before = key != own_key;
+                ahead += before as u32;
+                Ok(before)
+            })
+            .await?;
+        Ok(Some((ahead + 1, entry)))
+    }
+    
+    /// Index every entry under each `LeaderboardSort` other than score
+    pub async fn rebuild_sort_indexes(&mut self) -> Result<(), ViewError> {
+        let mut entries = Vec::new();
+        self.entries
+            .for_each_index_value(|_, entry| {
+                entries.push(entry.into_owned());
+                Ok(())
+            })
+            .await?;
+        for entry in entries {
+            for sort in LeaderboardSort::ALL {
+                if let Some(index) = self.sort_index_mut(sort) {
+                    index.insert(&sort_key(&entry, sort), ())?;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Up to `count` players in `sort` order, after skipping the first `skip`
+    pub async fn ranked_players(
+        &self,
+        sort: LeaderboardSort,
+        skip: usize,
+        count: usize,
+    ) -> Result<Vec<AccountOwner>, ViewError> {
+        let mut players = Vec::new();
+        if count == 0 {
+            return Ok(players);
+        }
+        let mut position = 0;
+        let mut visit = |player| {
+            if position >= skip {
+                players.push(player);
+            }
+            position += 1;
+            players.len() < count
+        };
+        match self.sort_index(sort) {
+            Some(index) => index.for_each_index_while(|(_, player)| Ok(visit(player))).await?,
+            None => self.ranking.for_each_index_while(|(.., player)| Ok(visit(player))).await?,
+        }
+        Ok(players)
+    }
+}
+
+/// Totals for one game type over every game recorded in match histories.
+/// A multi-player match counts as a game for each of its players.
+#[derive(View)]
+#[view(context = "ViewStorageContext")]
+pub struct GameStats {
+    pub games: RegisterView<u64>,
+    pub score_total: RegisterView<u128>,
+    /// Games each player has played
+    pub players: MapView<AccountOwner, u64>,
+    pub player_count: RegisterView<u64>,
+    /// Games ending on each score, keyed by the score in big-endian bytes
+    /// so they iterate from the lowest score up
+    pub scores: MapView<[u8; 8], u64>,
+    /// Games played in each hour of the last day, by hours since the epoch
+    pub hourly_games: MapView<u64, u64>,
+}
+
+impl GameStats {
+    /// Count a game `player` finished on `score` at `now`
+    pub async fn record(
+        &mut self,
+        player: &AccountOwner,
+        score: u64,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        let games = *self.games.get();
+        self.games.set(games + 1);
+        let score_total = *self.score_total.get();
+        self.score_total.set(score_total + score as u128);
+        let played = self.players.get(player).await?.unwrap_or(0);
+        if played == 0 {
+            let player_count = *self.player_count.get();
+            self.player_count.set(player_count + 1);
+        }
+        self.players.insert(player, played + 1)?;
+        let key = score.to_be_bytes();
+        let count = self.scores.get(&key).await?.unwrap_or(0);
+        self.scores.insert(&key, count + 1)?;
+        
+        let hour = now / MICROS_PER_HOUR;
+        for old in self.hourly_games.indices().await? {
+            if old + 24 <= hour {
+                self.hourly_games.remove(&old)?;
+            }
+        }
+        let count = self.hourly_games.get(&hour).await?.unwrap_or(0);
+        self.hourly_games.insert(&hour, count + 1)
+    }
+    
+    pub fn average_score(&self) -> f64 {
+        match *self.games.get() {
+            0 => 0.0,
+            games => *self.score_total.get() as f64 / games as f64,
+        }
+    }
+    
+    /// Middle score of every game, halfway between the middle two when
+    /// the count is even
+    pub async fn median_score(&self) -> Result<f64, ViewError> {
+        let games = *self.games.get();
+        if games == 0 {
+            return Ok(0.0);
+        }
+        let (low_rank, high_rank) = ((games - 1) / 2, games / 2);
+        let (mut low, mut high) = (None, None);
+        let mut counted = 0;
+        self.scores
+            .for_each_index_value_while(|key, count| {
+                let score = u64::from_be_bytes(key);
+                counted += *count;
+                if counted > low_rank {
+                    low.get_or_insert(score);
+                }
+                if counted > high_rank {
+                    high = Some(score);
+                }
+                Ok(high.is_none())
+            })
+            .await?;
+        Ok((low.unwrap_or(0) as f64 + high.unwrap_or(0) as f64) / 2.0)
+    }
+    
+    /// Games played in the hour of `now` and the 23 before it
+    pub async fn games_in_last_day(&self, now: u64) -> Result<u64, ViewError> {
+        let hour = now / MICROS_PER_HOUR;
+        let mut games = 0;
+        self.hourly_games
+            .for_each_index_value(|played_in, count| {
+                if played_in + 24 > hour {
+                    games += *count;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(games)
+    }
+}
+
+/// Play volume on one day
+#[derive(View)]
+#[view(context = "ViewStorageContext")]
+pub struct DailyActivity {
+    /// Games recorded in match histories, one for each player in a match
+    pub games: RegisterView<u64>,
+    /// Players who finished a game
+    pub players: MapView<AccountOwner, ()>,
+    pub player_count: RegisterView<u64>,
+    /// Tokens staked on room wagers and blackjack hands
+    pub wagered: RegisterView<Amount>,
+}
+
+/// Key ordering players on a `PlayerRankings` index: the ranked value
+/// subtracted from the maximum in big-endian bytes, then the player, so the
+/// index runs from the top down.
+pub type ProfileRankKey = ([u8; 16], AccountOwner);
+
+/// The parts of a profile players are ranked on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileStanding {
+    pub prestige: u32,
+    pub level: u32,
+    pub xp: u64,
+    pub tokens_won: u64,
+}
+
+impl From<&UserProfile> for ProfileStanding {
+    fn from(profile: &UserProfile) -> Self {
+        Self {
+            prestige: profile.prestige,
+            level: profile.level,
+            xp: profile.xp,
+            tokens_won: profile.total_tokens_won,
+        }
+    }
+}
+
+impl ProfileStanding {
+    /// Value ranked on under `ranking`, higher ranking first
+    fn value(&self, ranking: PlayerRanking) -> u128 {
+        match ranking {
+            PlayerRanking::Level => {
+                ((self.prestige as u128) << 96) | ((self.level as u128) << 64) | self.xp as u128
+            }
+            PlayerRanking::Xp => self.xp as u128,
+            PlayerRanking::TokensWon => self.tokens_won as u128,
+        }
+    }
+    
+    fn rank_key(&self, ranking: PlayerRanking, player: AccountOwner) -> ProfileRankKey {
+        ((u128::MAX - self.value(ranking)).to_be_bytes(), player)
+    }
+}
+
+/// Indexes of every player by each `PlayerRanking`, so the top players can
+/// be read without loading every profile
+#[derive(View)]
+#[view(context = "ViewStorageContext")]
+pub struct PlayerRankings {
+    pub by_level: MapView<ProfileRankKey, ()>,
+    pub by_xp: MapView<ProfileRankKey, ()>,
+    pub by_tokens_won: MapView<ProfileRankKey, ()>,
+}
+
+impl PlayerRankings {
+    fn index(&self, ranking: PlayerRanking) -> &MapView<ProfileRankKey, ()> {
+        match ranking {
+            PlayerRanking::Level => &self.by_level,
+            PlayerRanking::Xp => &self.by_xp,
+            PlayerRanking::TokensWon => &self.by_tokens_won,
+        }
+    }
+    
+    fn index_mut(&mut self, ranking: PlayerRanking) -> &mut MapView<ProfileRankKey, ()> {
+        match ranking {
+            PlayerRanking::Level => &mut self.by_level,
+            PlayerRanking::Xp => &mut self.by_xp,
+            PlayerRanking::TokensWon => &mut self.by_tokens_won,
+        }
+    }
+    
+    /// Move a player from where `before` ranked them, if anywhere, to where
+    /// `after` does
+    pub fn update(
+        &mut self,
+        player: AccountOwner,
+        before: Option<ProfileStanding>,
+        after: ProfileStanding,
+    ) -> Result<(), ViewError> {
+        if before == Some(after) {
+            return Ok(());
+        }
+        for ranking in PlayerRanking::ALL {
+            let index = self.index_mut(ranking);
+            if let Some(before) = before {
+                index.remove(&before.rank_key(ranking, player))?;
+            }
+            index.insert(&after.rank_key(ranking, player), ())?;
+        }
+        Ok(())
+    }
+    
+    /// Up to `count` players in `ranking` order, after skipping the first `skip`
+    pub async fn ranked_players(
+        &self,
+        ranking: PlayerRanking,
+        skip: usize,
+        count: usize,
+    ) -> Result<Vec<AccountOwner>, ViewError> {
+        let mut players = Vec::new();
+        if count == 0 {
+            return Ok(players);
+        }
+        let mut position = 0;
+        self.index(ranking)
+            .for_each_index_while(|(_, player)| {
+                if position >= skip {
+                    players.push(player);
+                }
+                position += 1;
+                Ok(players.len() < count)
+            })
+            .await?;
+        Ok(players)
+    }
+}
+
+/// User profiles, stored as `VersionedUserProfile`s and upgraded to the
+/// current layout as they are read
+#[derive(View)]
+#[view(context = "ViewStorageContext")]
+pub struct UserProfiles {
+    pub stored: MapView<AccountOwner, VersionedUserProfile>,
+}
+
+impl UserProfiles {
+    pub async fn get(&self, address: &AccountOwner) -> Result<Option<UserProfile>, ViewError> {
+        Ok(self.stored.get(address).await?.map(UserProfile::from))
+    }
+    
+    pub async fn multi_get(
+        &self,
+        addresses: &[AccountOwner],
+    ) -> Result<Vec<Option<UserProfile>>, ViewError> {
+        let stored = self.stored.multi_get(addresses).await?;
+        Ok(stored.into_iter().map(|profile| profile.map(UserProfile::from)).collect())
+    }
+    
+    /// Store a profile in the current layout
+    pub fn insert(
+        &mut self,
+        address: &AccountOwner,
+        profile: UserProfile,
+    ) -> Result<(), ViewError> {
+        self.stored.insert(address, profile.into())
+    }
+}
+
+/// Results between two players, the first and second of their pair in
+/// ascending order
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct HeadToHead {
+    pub first_wins: u32,
+    pub second_wins: u32,
+    pub draws: u32,
+    /// Most recent encounters, oldest first
+    pub recent: Vec<Encounter>,
+}
+
+/// Leaderboard for the current period of a time window
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct WindowedLeaderboard {
+    /// Period the entries belong to, see `LeaderboardWindow::period`
+    pub period: u64,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// A match in a player's history as stored before schema version 3
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LegacyMatchRecord {
+    /// The game type's `Debug` name
+    pub game_type: String,
+    pub opponent: Option<AccountOwner>,
+    pub result: MatchResult,
+    pub score: u64,
+    pub timestamp: u64,
+}
+
+/// A player waiting in a matchmaking queue
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MatchmakingTicket {
+    pub player: AccountOwner,
+    pub chain_id: ChainId,
+    pub rating: u32,
+    pub queued_at: u64,
+}
+
+/// A Minesweeper session on a committed board
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MinesweeperSession {
+    pub board_commitment: [u8; 32],
+    pub started_at: u64,
+    pub clicks: Vec<MinesweeperClick>,
+    pub submitted_at: Option<u64>,
+}
+
+/// A day's word in the daily word game
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DailyWord {
+    /// `daily_word::word_commitment` of the word and the admin's salt
+    pub commitment: [u8; 32],
+    pub published_at: u64,
+    /// Set once revealed, after which no more guesses are taken
+    pub answer: Option<String>,
+}
+
+/// A blackjack hand against the house
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BlackjackSession {
+    /// The value last revealed from the house's hash chain, or the chain's
+    /// committed end. The next card's value must hash to it.
+    pub chain_head: [u8; 32],
+    pub salt: Vec<u8>,
+    pub bet: Amount,
+    /// House bankroll set aside to cover the hand's largest payout
+    pub reserved: Amount,
+    pub hand: BlackjackHand,
+    /// When the hand last changed
+    pub updated_at: u64,
+}
+
+/// The fields of a public room the lobby filters and sorts on
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RoomListing {
+    pub room_id: String,
+    pub game_type: GameType,
+    pub status: RoomStatus,
+    pub entry_fee: Amount,
+    pub player_count: u8,
+    pub max_players: u8,
+    pub created_at: u64,
+}
+
+impl RoomListing {
+    /// Seats still free
+    pub fn open_seats(&self) -> u8 {
+        self.max_players.saturating_sub(self.player_count)
+    }
+}
+
+/// A game room for multiplayer games
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameRoom {
+    pub room_id: String,
+    pub game_type: GameType,
+    pub creator: AccountOwner,
+    pub players: Vec<AccountOwner>,
+    pub max_players: u8,
+    pub visibility: RoomVisibility,
+    /// Hash of the invite code for private rooms, salted with the room ID
+    pub invite_code_hash: Option<[u8; 32]>,
+    /// Players who may join a private room without the invite code
+    pub allowlist: Vec<AccountOwner>,
+    pub entry_fee: Amount,
+    /// Entry fees currently held by the application for this room
+    pub escrow: Amount,
+    /// Individual escrow deposits, used for refunds and settlement
+    pub deposits: Vec<EscrowDeposit>,
+    /// Head-to-head stake each player locks on top of the entry fee
+    pub wager: Amount,
+    /// Wager stakes currently held for this room, kept apart from the
+    /// entry fees because a draw refunds them rather than splitting the pot
+    pub wager_deposits: Vec<EscrowDeposit>,
+    /// House rake on the room's prizes in basis points, fixed when the room
+    /// is created so a later change can't alter the stakes players joined for
+    pub rake_bps: u16,
+    /// Chain each player joined from, notified when the game ends
+    pub player_chains: Vec<(AccountOwner, ChainId)>,
+    pub status: RoomStatus,
+    pub game_state: Option<GameState>,
+    pub created_at: u64,
+    /// Time after which the room expires if it is still `Waiting`
+    pub expires_at: u64,
+    /// Players who confirmed during the current ready check
+    pub ready: Vec<AccountOwner>,
+    /// Time by which every player must be ready, during a ready check
+    pub ready_deadline: Option<u64>,
+    /// Time by which the current player must move, while in progress
+    pub turn_deadline: Option<u64>,
+    /// Round wins needed to take the series (1 for a single game)
+    pub series_target: u8,
+    /// Round wins per player in the current series
+    pub series_wins: Vec<(AccountOwner, u8)>,
+    /// Winner of each completed round, `None` for a draw
+    pub round_winners: Vec<Option<AccountOwner>>,
+    /// Players who asked for a rematch after the room finished
+    pub rematch_requests: Vec<AccountOwner>,
+    /// Play each match on its own temporary chain
+    pub dedicated_chain: bool,
+    /// Temporary chain the current match is being played on
+    pub host_chain: Option<ChainId>,
+    /// On a room's temporary chain, the chain that created the room and holds its escrow
+    pub home_chain: Option<ChainId>,
+    /// Tournament this room is a game of, whose standings it reports to
+    pub tournament_id: Option<String>,
+    /// Time until which the room is featured in the lobby, bought with arcade credits
+    pub boosted_until: Option<u64>,
+    /// Board size the room chose, for games that have one
+    pub board_size: Option<u8>,
+    /// Open Gomoku games with swap2
+    pub swap2_opening: bool,
+    /// Players who ran out of time in a match that others then shared
+    pub forfeited: Vec<AccountOwner>,
+}
+
+/// This chain's view of a room hosted on another chain, kept up to date
+/// from the room's event messages
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RoomMirror {
+    pub room_id: String,
+    pub players: Vec<AccountOwner>,
+    pub status: RoomStatus,
+    pub move_count: u32,
+    /// The most recent move and who made it
+    pub last_move: Option<(AccountOwner, Vec<u8>)>,
+    pub winner: Option<AccountOwner>,
+    /// Round wins per player once the game has ended
+    pub scores: Vec<(AccountOwner, u64)>,
+}
+
+/// A player's season pass for one season
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SeasonPass {
+    /// XP gained during the season
+    pub xp: u64,
+    /// Whether the premium track was bought
+    pub premium: bool,
+    /// Tiers whose free-track reward has been claimed
+    pub claimed_free: Vec<u8>,
+    /// Tiers whose premium-track reward has been claimed
+    pub claimed_premium: Vec<u8>,
+}
+
+impl SeasonPass {
+    /// Highest tier unlocked by the season's XP, 0 if none
+    pub fn tier(&self, xp_per_tier: u64, tiers: u8) -> u8 {
+        (self.xp / xp_per_tier.max(1)).min(tiers as u64) as u8
+    }
+}
+
+/// A clan: its roster, pending join requests and shared treasury
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Clan {
+    pub tag: String,
+    pub name: String,
+    /// Members in joining order, with their roles
+    pub members: Vec<(AccountOwner, ClanRole)>,
+    /// Players waiting for a leader or officer to review their request
+    pub join_requests: Vec<AccountOwner>,
+    /// Arcade credits donated by members, spent by the leader
+    pub treasury: u64,
+    pub created_at: u64,
+    /// Clan wars the clan is signed up for that have not been settled
+    pub wars: Vec<String>,
+}
+
+/// A scheduled war between two clans
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClanWar {
+    pub war_id: String,
+    /// Tag of each clan with the points it has scored
+    pub scores: Vec<(String, u64)>,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    /// Credits minted into the winning clan's treasury
+    pub reward: u64,
+    /// Set once the reward has been paid; `None` on a draw
+    pub winner: Option<String>,
+    pub settled: bool,
+}
+
+impl Clan {
+    /// A member's role, or `None` if the player is not in the clan
+    pub fn role_of(&self, player: &AccountOwner) -> Option<ClanRole> {
+        self.members.iter().find(|(member, _)| member == player).map(|(_, role)| *role)
+    }
+    
+    /// The clan's leader
+    pub fn leader(&self) -> Option<AccountOwner> {
+        self.members
+            .iter()
+            .find(|(_, role)| *role == ClanRole::Leader)
+            .map(|(member, _)| *member)
+    }
+    
+    /// Set a member's role
+    pub fn set_role(&mut self, player: &AccountOwner, role: ClanRole) {
+        if let Some(entry) = self.members.iter_mut().find(|(member, _)| member == player) {
+            entry.1 = role;
+        }
+    }
+    
+    /// Remove a member. If they led the clan, the longest-serving of the
+    /// highest-ranked remaining members takes over.
+    pub fn remove_member(&mut self, player: &AccountOwner) {
+        let was_leader = self.role_of(player) == Some(ClanRole::Leader);
+        self.members.retain(|(member, _)| member != player);
+        if was_leader {
+            let successor = self
+                .members
+                .iter()
+                .enumerate()
+                .max_by_key(|(idx, (_, role))| (*role, std::cmp::Reverse(*idx)))
+                .map(|(_, (member, _))| *member);
+            if let Some(successor) = successor {
+                self.set_role(&successor, ClanRole::Leader);
+            }
+        }
+    }
+}
+
+/// A tournament and every pairing played in it so far
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Tournament {
+    pub tournament_id: String,
+    pub game_type: GameType,
+    pub creator: AccountOwner,
+    pub format: TournamentFormat,
+    pub status: TournamentStatus,
+    pub max_players: u32,
+    pub entry_fee: Amount,
+    /// Entry fees held for the prize pool, paid out to the winner at the end
+    pub deposits: Vec<EscrowDeposit>,
+    /// Registered players in registration order, with the chain they joined from
+    pub players: Vec<(AccountOwner, ChainId)>,
+    /// How long players have to check in once registration closes, 0 for no check-in
+    pub check_in_window: u64,
+    /// When check-in closes, once it has opened
+    pub check_in_deadline: Option<u64>,
+    pub checked_in: Vec<AccountOwner>,
+    /// Refund no-shows' entry fees rather than keeping them in the prize pool
+    pub refund_no_shows: bool,
+    /// Round being played, 0 before the tournament starts
+    pub current_round: u8,
+    /// Every pairing so far. Leagues schedule all their fixtures up front.
+    pub pairings: Vec<Pairing>,
+    pub created_at: u64,
+}
+
+impl Tournament {
+    /// Registered players in registration order
+    pub fn player_list(&self) -> Vec<AccountOwner> {
+        self.players.iter().map(|(player, _)| *player).collect()
+    }
+    
+    /// Current standings, best first
+    pub fn standings(&self) -> Vec<Standing> {
+        tournament::standings(&self.player_list(), &self.pairings, self.format.points_table())
+    }
+    
+    /// Pairings so far grouped into rounds
+    pub fn rounds(&self) -> Vec<TournamentRound> {
+        tournament::rounds(&self.pairings)
+    }
+    
+    /// Native tokens held for the prize pool
+    pub fn prize_pool(&self) -> Amount {
+        native_total(&self.deposits)
+    }
+    
+    /// Whether players can still register
+    pub fn is_open(&self) -> bool {
+        self.status == TournamentStatus::Registration
+            && (self.players.len() as u32) < self.max_players
+    }
+    
+    /// Drop every registered player who did not check in, returning their
+    /// deposits if they are to be refunded. Forfeited deposits stay in the
+    /// prize pool.
+    pub fn drop_no_shows(&mut self) -> Vec<EscrowDeposit> {
+        let checked_in = &self.checked_in;
+        self.players.retain(|(player, _)| checked_in.contains(player));
+        
+        if !self.refund_no_shows {
+            return Vec::new();
+        }
+        let (kept, refunds): (Vec<_>, Vec<_>) = std::mem::take(&mut self.deposits)
+            .into_iter()
+            .partition(|deposit| self.checked_in.contains(&deposit.player));
+        self.deposits = kept;
+        refunds
+    }
+    
+    /// Schedule every fixture of a league, spreading the matchdays evenly
+    /// over its season window from `now`
+    pub fn schedule_league(&mut self, now: u64) {
+        let TournamentFormat::League { window } = self.format else {
+            return;
+        };
+        let matchday = window / self.total_rounds().max(1) as u64;
+        for (round, home, away) in tournament::round_robin_fixtures(&self.player_list()) {
+            self.pairings.push(Pairing {
+                round,
+                player_a: home,
+                player_b: Some(away),
+                room_id: None,
+                finished: false,
+                winner: None,
+                due_by: Some(now + matchday * round as u64),
+            });
+        }
+    }
+    
+    /// Whether every game of the current round has a result
+    pub fn round_complete(&self) -> bool {
+        self.pairings
+            .iter()
+            .filter(|p| p.round == self.current_round)
+            .all(|p| p.finished)
+    }
+    
+    /// Rounds the tournament is played over
+    pub fn total_rounds(&self) -> u8 {
+        match self.format {
+            TournamentFormat::Swiss { rounds } => rounds,
+            // An odd field needs one extra round so everyone can sit one out
+            TournamentFormat::League { .. } => {
+                let players = self.players.len();
+                (if players % 2 == 0 { players.saturating_sub(1) } else { players }) as u8
+            }
+        }
+    }
+}
+
+/// An entry fee paid into a room's escrow
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EscrowDeposit {
+    /// Player that made the deposit, also the account refunds are paid to
+    pub player: AccountOwner,
+    pub amount: Amount,
+    /// Paid from the player's free-play balance rather than native tokens
+    pub free_play: bool,
+}
+
+/// Native tokens held in `deposits`, leaving out free-play tokens
+pub fn native_total(deposits: &[EscrowDeposit]) -> Amount {
+    deposits
+        .iter()
+        .filter(|deposit| !deposit.free_play)
+        .fold(Amount::ZERO, |total, deposit| total.saturating_add(deposit.amount))
+}
+
+/// House rake of `rake_bps` basis points on a pot of `deposits`. Free-play
+/// tokens are never raked.
+pub fn rake_on(deposits: &[EscrowDeposit], rake_bps: u16) -> Amount {
+    Amount::from_attos(u128::from(native_total(deposits)) * u128::from(rake_bps) / 10_000)
+}
+
+impl GameRoom {
+    /// A public, free, single-game room with only its creator seated
+    pub fn new(
+        room_id: String,
+        game_type: GameType,
+        creator: AccountOwner,
+        creator_chain: ChainId,
+        max_players: u8,
+        now: u64,
+        ttl: u64,
+    ) -> Self {
+        Self {
+            room_id,
+            game_type,
+            players: vec![creator],
+            player_chains: vec![(creator, creator_chain)],
+            creator,
+            max_players,
+            visibility: RoomVisibility::Public,
+            invite_code_hash: None,
+            allowlist: Vec::new(),
+            entry_fee: Amount::ZERO,
+            escrow: Amount::ZERO,
+            deposits: Vec::new(),
+            wager: Amount::ZERO,
+            wager_deposits: Vec::new(),
+            rake_bps: 0,
+            status: RoomStatus::Waiting,
+            game_state: None,
+            created_at: now,
+            expires_at: now.saturating_add(ttl),
+            ready: Vec::new(),
+            ready_deadline: None,
+            turn_deadline: None,
+            series_target: 1,
+            series_wins: Vec::new(),
+            round_winners: Vec::new(),
+            rematch_requests: Vec::new(),
+            dedicated_chain: false,
+            host_chain: None,
+            home_chain: None,
+            tournament_id: None,
+            boosted_until: None,
+            board_size: None,
+            swap2_opening: false,
+            forfeited: Vec::new(),
+        }
+    }
+    
+    /// Wager stakes held for the room
+    pub fn wager_pool(&self) -> Amount {
+        self.wager_deposits
+            .iter()
+            .fold(Amount::ZERO, |total, stake| total.saturating_add(stake.amount))
+    }
+    
+    /// Rake the house would take if the room ended now with a winner
+    pub fn expected_rake(&self) -> Amount {
+        let stakes_rake = rake_on(&self.wager_deposits, self.rake_bps);
+        rake_on(&self.deposits, self.rake_bps).saturating_add(stakes_rake)
+    }
+    
+    /// The room's lobby listing
+    pub fn listing(&self) -> RoomListing {
+        RoomListing {
+            room_id: self.room_id.clone(),
+            game_type: self.game_type,
+            status: self.status,
+            entry_fee: self.entry_fee,
+            player_count: self.players.len() as u8,
+            max_players: self.max_players,
+            created_at: self.created_at,
+        }
+    }
+    
+    /// Begin play with a fresh game state
+    pub fn start(&mut self, turn_deadline: u64) {
+        self.status = RoomStatus::InProgress;
+        self.game_state = self.new_round_state();
+        self.turn_deadline = Some(turn_deadline);
+    }
+    
+    /// Fresh game state for a new round of this room's game type
+    pub fn new_round_state(&self) -> Option<GameState> {
+        match self.game_type {
+            GameType::TicTacToe => Some(GameState::TicTacToe(TicTacToeState::default())),
+            GameType::SnakeLadders => {
+                Some(GameState::SnakeLadders(SnakeLaddersState::new(self.players.len())))
+            }
+            GameType::Uno => Some(GameState::Uno(UnoState::new(self.players.len()))),
+            GameType::Ludo => Some(GameState::Ludo(LudoState::new(self.players.len()))),
+            GameType::Memory => Some(GameState::Memory(MemoryState::new(self.players.len()))),
+            GameType::Trivia => Some(GameState::Trivia(TriviaState::new(self.players.len()))),
+            GameType::Yahtzee => Some(GameState::Yahtzee(YahtzeeState::new(self.players.len()))),
+            GameType::ConnectFour => Some(GameState::ConnectFour(ConnectFourState::default())),
+            GameType::Chess => Some(GameState::Chess(ChessState::default())),
+            GameType::Checkers => Some(GameState::Checkers(CheckersState::default())),
+            GameType::Battleship => Some(GameState::Battleship(BattleshipState::default())),
+            GameType::Reversi => Some(GameState::Reversi(ReversiState::default())),
+            GameType::Poker => Some(GameState::Poker(PokerState::default())),
+            GameType::RockPaperScissors => {
+                Some(GameState::RockPaperScissors(RockPaperScissorsState::default()))
+            }
+            GameType::Gomoku => Some(GameState::Gomoku(GomokuState::new(
+                self.board_size.unwrap_or(gomoku::DEFAULT_SIZE),
+                self.swap2_opening,
+            ))),
+            GameType::DotsAndBoxes => Some(GameState::DotsAndBoxes(DotsAndBoxesState::new(
+                self.board_size.unwrap_or(dots_and_boxes::DEFAULT_SIZE),
+                self.players.len(),
+            ))),
+            GameType::Snake
+            | GameType::TwentyFortyEight
+            | GameType::Minesweeper
+            | GameType::Sudoku
+            | GameType::Blackjack
+            | GameType::DailyWord
+            | GameType::Tetris => None,
+        }
+    }
+    
+    /// Whether `player` may take a seat, given the room's visibility
+    pub fn admits(&self, player: &AccountOwner, proof: Option<[u8; 32]>) -> bool {
+        match self.visibility {
+            RoomVisibility::Public => true,
+            RoomVisibility::Private => {
+                self.allowlist.contains(player)
+                    || matches!(
+                        (self.invite_code_hash, proof),
+                        (Some(hash), Some(proof)) if invite_proof(&hash, player) == proof
+                    )
+            }
+        }
+    }
+    
+    /// Whether the room never filled up and has outlived its TTL
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.status == RoomStatus::Waiting && now >= self.expires_at
+    }
+    
+    /// Ask every player to confirm they are ready before play begins
+    pub fn begin_ready_check(&mut self, deadline: u64) {
+        self.status = RoomStatus::ReadyCheck;
+        self.ready.clear();
+        self.ready_deadline = Some(deadline);
+    }
+    
+    /// Whether the room's ready check ran out before everyone confirmed
+    pub fn missed_ready_check(&self, now: u64) -> bool {
+        self.status == RoomStatus::ReadyCheck
+            && self.ready_deadline.is_some_and(|deadline| now >= deadline)
+    }
+    
+    /// Remove every player who did not confirm in time and reopen the room.
+    /// Returns their deposits for the caller to refund.
+    pub fn drop_unready(&mut self) -> Vec<EscrowDeposit> {
+        let unready: Vec<AccountOwner> = self
+            .players
+            .iter()
+            .filter(|player| !self.ready.contains(player))
+            .copied()
+            .collect();
+        unready.iter().flat_map(|player| self.remove_player(player)).collect()
+    }
+    
+    /// Remove a player from the room, handing the host role to the next
+    /// player if the creator leaves and calling off any ready check.
+    /// Returns the player's escrow deposits
+    /// (entry fee and wager stake), which the caller is responsible for
+    /// refunding.
+    pub fn remove_player(&mut self, player: &AccountOwner) -> Vec<EscrowDeposit> {
+        self.players.retain(|p| p != player);
+        self.ready.retain(|p| p != player);
+        self.rematch_requests.retain(|p| p != player);
+        self.player_chains.retain(|(p, _)| p != player);
+        
+        if self.creator == *player {
+            if let Some(next_host) = self.players.first() {
+                self.creator = *next_host;
+            }
+        }
+        
+        // A seat opened up, so the ready check is off until the room fills again
+        if self.status == RoomStatus::ReadyCheck {
+            self.status = RoomStatus::Waiting;
+            self.ready.clear();
+            self.ready_deadline = None;
+        }
+        
+        let mut refunds = Vec::new();
+        if let Some(idx) = self.deposits.iter().position(|d| d.player == *player) {
+            let deposit = self.deposits.remove(idx);
+            self.escrow.saturating_sub_assign(deposit.amount);
+            refunds.push(deposit);
+        }
+        if let Some(idx) = self.wager_deposits.iter().position(|d| d.player == *player) {
+            refunds.push(self.wager_deposits.remove(idx));
+        }
+        refunds
+    }
+    
+    /// Record the outcome of a round and advance the series.
+    ///
+    /// Returns `true` once a player has reached `series_target`, at which
+    /// point the room is `Finished` and the pot can be paid out. Otherwise
+    /// the game state is reset for the next round.
+    pub fn record_round(&mut self, winner: Option<AccountOwner>) -> bool {
+        self.round_winners.push(winner);
+        
+        if let Some(winner) = winner {
+            let wins = match self.series_wins.iter_mut().find(|(p, _)| *p == winner) {
+                Some((_, wins)) => {
+                    *wins += 1;
+                    *wins
+                }
+                None => {
+                    self.series_wins.push((winner, 1));
+                    1
+                }
+            };
+            
+            if wins >= self.series_target.max(1) {
+                self.status = RoomStatus::Finished;
+                return true;
+            }
+        } else if self.series_target <= 1 {
+            // A drawn single game ends the match
+            self.status = RoomStatus::Finished;
+            return true;
+        }
+        
+        self.game_state = self.new_round_state();
+        false
+    }
+    
+    /// Players to list the room under in `player_rooms`: everyone seated,
+    /// until the room finishes
+    pub fn seated_players(&self) -> &[AccountOwner] {
+        match self.status {
+            RoomStatus::Finished => &[],
+            _ => &self.players,
+        }
+    }
+    
+    /// The player whose move it is and the number of that move.
+    ///
+    /// `None` for rooms that are not in progress or have no turn order.
+    pub fn current_turn(&self) -> Option<(AccountOwner, u32)> {
+        if self.status != RoomStatus::InProgress {
+            return None;
+        }
+        
+        match self.game_state.as_ref()? {
+            GameState::TicTacToe(game) => {
+                // X is the first player to join, O the second
+                let player = *self.players.get(game.current_turn.player_index())?;
+                Some((player, game.move_count as u32 + 1))
+            }
+            GameState::ConnectFour(game) => {
+                let player = *self.players.get(game.current_turn.player_index())?;
+                Some((player, game.move_count as u32 + 1))
+            }
+            GameState::Chess(game) => {
+                // White is the first player to join, Black the second
+                let player = *self.players.get(game.side_to_move.player_index())?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Checkers(game) => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Reversi(game) => {
+                // Dark is the first player to join, light the second
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Gomoku(game) => {
+                let player = *self.players.get(game.current_turn())?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::DotsAndBoxes(game) => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Battleship(game) if game.is_started() => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::RockPaperScissors(game) => {
+                // Only a player whose opponent has already committed or revealed can stall
+                let player = *self.players.get(game.waiting_on()?)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::SnakeLadders(game) if game.is_started() => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Uno(game) if game.is_started() && game.pending_winner.is_none() => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Ludo(game) if game.is_started() => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Memory(game) if game.is_started() => {
+                let player = *self.players.get(game.current_turn)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Trivia(game) => {
+                let player = *self.players.get(game.waiting_on()?)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Poker(game) => {
+                let player = *self.players.get(game.waiting_on()?)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::Yahtzee(game) => {
+                let player = *self.players.get(game.waiting_on()?)?;
+                Some((player, game.move_count + 1))
+            }
+            GameState::SnakeLadders(_)
+            | GameState::Uno(_)
+            | GameState::Ludo(_)
+            | GameState::Memory(_)
+            | GameState::Battleship(_)
+            | GameState::Snake(_) => None,
+        }
+    }
+    
+    /// Players holding up a phase where nobody moves in turn, such as
+    /// committing to or revealing a shared seed before the game starts
+    pub fn stalled_players(&self) -> Vec<AccountOwner> {
+        if self.status != RoomStatus::InProgress {
+            return Vec::new();
+        }
+        
+        let stalled = match self.game_state.as_ref() {
+            Some(GameState::SnakeLadders(game)) => game.stalled(),
+            Some(GameState::Ludo(game)) if !game.is_started() => game.dice.stalled(),
+            Some(GameState::Memory(game)) if !game.is_started() => game.shuffle.stalled(),
+            Some(GameState::Battleship(game)) if !game.is_started() => {
+                (0..2).filter(|&p| game.commitments[p].is_none()).collect()
+            }
+            Some(GameState::Uno(game)) => game.stalled(),
+            _ => Vec::new(),
+        };
+        stalled.into_iter().filter_map(|idx| self.players.get(idx).copied()).collect()
+    }
+    
+    /// Reset a finished room for a rematch with the same players. The turn
+    /// order rotates so the player who moved second now moves first.
+    pub fn start_rematch(&mut self) {
+        self.players.rotate_left(1);
+        self.series_wins.clear();
+        self.round_winners.clear();
+        self.rematch_requests.clear();
+        self.forfeited.clear();
+        self.status = RoomStatus::InProgress;
+        self.game_state = self.new_round_state();
+    }
+    
+    /// Whether the player to move has run out of time
+    pub fn is_turn_overdue(&self, now: u64) -> bool {
+        self.status == RoomStatus::InProgress && self.turn_deadline.is_some_and(|deadline| now > deadline)
+    }
+    
+    /// End the match in `winner`'s favour, regardless of the series score
+    pub fn forfeit_to(&mut self, winner: AccountOwner) {
+        let target = self.series_target.max(1);
+        match self.series_wins.iter_mut().find(|(p, _)| *p == winner) {
+            Some((_, wins)) => *wins = target,
+            None => self.series_wins.push((winner, target)),
+        }
+        self.turn_deadline = None;
+        self.status = RoomStatus::Finished;
+    }
+    
+    /// End the match with `stalled` forfeiting on time. A winner already
+    /// decided, or the one player left, takes the series; otherwise the rest
+    /// share the pot.
+    pub fn forfeit_stalled(&mut self, stalled: &[AccountOwner]) {
+        // An Uno winner only waits on the other players' audits
+        let decided = match self.game_state.as_ref() {
+            Some(GameState::Uno(game)) => game.confirmed_winner(),
+            _ => None,
+        };
+        if let Some(winner) = decided.and_then(|idx| self.players.get(idx).copied()) {
+            self.forfeit_to(winner);
+            return;
+        }
+        
+        let remaining: Vec<AccountOwner> =
+            self.players.iter().copied().filter(|p| !stalled.contains(p)).collect();
+        if let [winner] = remaining[..] {
+            self.forfeit_to(winner);
+            return;
+        }
+        self.forfeited = stalled.to_vec();
+        self.turn_deadline = None;
+        self.status = RoomStatus::Finished;
+    }
+    
+    /// Players who share the pot of a room that finished without a series
+    /// winner: everyone who didn't forfeit
+    pub fn drawing_players(&self) -> Vec<AccountOwner> {
+        self.players.iter().copied().filter(|p| !self.forfeited.contains(p)).collect()
+    }
+    
+    /// The player who won the series, if it has concluded
+    pub fn series_winner(&self) -> Option<AccountOwner> {
+        self.series_wins
+            .iter()
+            .find(|(_, wins)| *wins >= self.series_target.max(1))
+            .map(|(player, _)| *player)
+    }
+}
+
+impl GameStationState {
+    /// A new room ID, unique across chains: the game, this chain's ID and
+    /// the number of rooms created here before it
+    pub fn next_room_id(&mut self, game_type: GameType, chain_id: ChainId) -> String {
+        let room_id = self.peek_room_id(game_type, chain_id);
+        let count = *self.room_counter.get();
+        self.room_counter.set(count + 1);
+        room_id
+    }
+    
+    /// The ID `next_room_id` will hand out next, without taking it
+    pub fn peek_room_id(&self, game_type: GameType, chain_id: ChainId) -> String {
+        format!("{:?}-{}-{}", game_type, chain_id, *self.room_counter.get())
+    }
+    
+    /// A new tournament ID, unique across chains like `next_room_id`, since
+    /// the IDs of its rooms are built from it
+    pub fn next_tournament_id(&mut self, chain_id: ChainId) -> String {
+        let count = *self.tournament_counter.get();
+        self.tournament_counter.set(count + 1);
+        format!("Tournament-{}-{}", chain_id, count)
+    }
+    
+    /// Bring state written by an older build up to `SCHEMA_VERSION`, one
+    /// version at a time. Cheap once the state is current, so it runs on
+    /// every load.
+    pub async fn migrate(&mut self) -> Result<(), ViewError> {
+        let version = *self.schema_version.get();
+        if version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+        if version < 1 {
+            // Profiles are stored versioned
+            let mut profiles = Vec::new();
+            self.legacy_users
+                .for_each_index_value(|address, profile| {
+                    profiles.push((address, UserProfile::from(profile.into_owned())));
+                    Ok(())
+                })
+                .await?;
+            for (address, profile) in profiles {
+                self.users.insert(&address, profile)?;
+            }
+            self.legacy_users.clear();
+            
+            // Season leaderboards gained indexes for the other sort orders
+            for key in self.legacy_leaderboards.indices().await? {
+                let board = self.legacy_leaderboards.load_entry_mut(&key).await?;
+                board.rebuild_sort_indexes().await?;
+            }
+        }
+        if version < 2 {
+            self.migrate_leaderboard_keys().await?;
+        }
+        if version < 3 {
+            self.migrate_match_history().await?;
+            // Queues were keyed by the game's name; whoever waited in them
+            // enters matchmaking again
+            self.matchmaking.clear();
+        }
+        self.schema_version.set(SCHEMA_VERSION);
+        Ok(())
+    }
+    
+    /// Move every player's match history to records holding the game type.
+    /// Matches of games under names no game has are dropped.
+    async fn migrate_match_history(&mut self) -> Result<(), ViewError> {
+        for address in self.legacy_match_history.indices().await? {
+            let legacy = self.legacy_match_history.load_entry_mut(&address).await?;
+            let records = legacy.elements().await?;
+            let history = self.match_history.load_entry_mut(&address).await?;
+            for record in records {
+                let Some(game_type) = GameType::from_name(&record.game_type) else {
+                    continue;
+                };
+                history.push_back(MatchRecord {
+                    game_type,
+                    opponent: record.opponent,
+                    result: record.result,
+                    score: record.score,
+                    timestamp: record.timestamp,
+                });
+            }
+        }
+        self.legacy_match_history.clear();
+        Ok(())
+    }
+    
+    /// Move every leaderboard from its board name to its game type and kind.
+    /// Boards under names no game had are left where they are.
+    async fn migrate_leaderboard_keys(&mut self) -> Result<(), ViewError> {
+        for (season, name) in self.legacy_leaderboards.indices().await? {
+            let Some((game_type, kind)) = legacy_board(&name) else {
+                log::warn!("Leaving unrecognised leaderboard {:?} of season {}", name, season);
+                continue;
+            };
+            let legacy_key = (season, name);
+            let mut entries = Vec::new();
+            if let Some(board) = self.legacy_leaderboards.try_load_entry(&legacy_key).await? {
+                board
+                    .entries
+                    .for_each_index_value(|_, entry| {
+                        entries.push(entry.into_owned());
+                        Ok(())
+                    })
+                    .await?;
+            }
+            let board = self.leaderboards.load_entry_mut(&(season, game_type, kind)).await?;
+            for entry in entries {
+                board.set_entry(entry).await?;
+            }
+            self.legacy_leaderboards.remove_entry(&legacy_key)?;
+        }
+        
+        // Global leaderboards were keyed by the game's name, and a rated
+        // game only ever reported its ratings
+        for name in self.legacy_global_leaderboards.indices().await? {
+            let game_type = LEGACY_BOARDS
+                .iter()
+                .map(|&(_, game_type, _)| game_type)
+                .find(|game_type| format!("{:?}", game_type) == name);
+            let Some(game_type) = game_type else {
+                log::warn!("Leaving unrecognised global leaderboard {:?}", name);
+                continue;
+            };
+            let entries = self.legacy_global_leaderboards.get(&name).await?.unwrap_or_default();
+            let kind = if game_type.is_rated() {
+                LeaderboardKind::Rating
+            } else {
+                LeaderboardKind::Score
+            };
+            self.global_leaderboards.insert(&(game_type, kind), entries)?;
+            self.legacy_global_leaderboards.remove(&name)?;
+        }
+        
+        for key in self.legacy_windowed_leaderboards.indices().await? {
+            let board = key
+                .split_once(':')
+                .and_then(|(name, window)| Some((legacy_board(name)?, window)))
+                .and_then(|((game_type, _), window)| {
+                    Some((game_type, LeaderboardWindow::from_filter(window)?))
+                });
+            let Some(board) = board else {
+                log::warn!("Leaving unrecognised windowed leaderboard {:?}", key);
+                continue;
+            };
+            let entries = self.legacy_windowed_leaderboards.get(&key).await?;
+            self.windowed_leaderboards.insert(&board, entries.unwrap_or_default())?;
+            self.legacy_windowed_leaderboards.remove(&key)?;
+        }
+        Ok(())
+    }
+    
+    /// Get or create a user profile
+    pub async fn get_or_create_user(&mut self, address: &AccountOwner) -> Result<UserProfile, ViewError> {
+        if let Some(profile) = self.users.get(address).await? {
+            Ok(profile)
+        } else {
+            let new_profile = UserProfile {
+                referral_code: referral_code(address),
+                ..UserProfile::default()
+            };
+            self.users.insert(address, new_profile.clone())?;
+            self.referral_codes.insert(&new_profile.referral_code, *address)?;
+            self.player_rankings.update(*address, None, ProfileStanding::from(&new_profile))?;
+            
+            // Increment total players
+            let current = *self.total_players.get();
+            self.total_players.set(current + 1);
+            
+            Ok(new_profile)
+        }
+    }
+    
+    /// Record activity for online status and login streaks, if the player has
+    /// a profile.
+    ///
+    /// The first activity of a day extends the streak if the player was also
+    /// active the day before, and starts a new one otherwise. It grants a
+    /// bonus that grows with the streak, up to `MAX_STREAK_BONUS_DAYS`.
+    pub async fn touch_user(&mut self, address: &AccountOwner, now: u64) -> Result<(), ViewError> {
+        let Some(mut profile) = self.users.get(address).await? else {
+            return Ok(());
+        };
+        profile.last_seen = now;
+        
+        let today = now / MICROS_PER_DAY;
+        if profile.last_active_day < today {
+            profile.login_streak = if profile.last_active_day + 1 == today {
+                profile.login_streak + 1
+            } else {
+                1
+            };
+            profile.longest_login_streak = profile.longest_login_streak.max(profile.login_streak);
+            profile.last_active_day = today;
+            
+            let bonus_days = profile.login_streak.min(MAX_STREAK_BONUS_DAYS);
+            self.award_xp(address, &mut profile, STREAK_XP_PER_DAY * bonus_days as u64).await?;
+            profile
+                .free_play_balance
+                .saturating_add_assign(STREAK_TOKENS_PER_DAY.saturating_mul(bonus_days as u128));
+        }
+        self.users.insert(address, profile)
+    }
+    
+    /// Add XP to a profile, updating its level and the player's season pass.
+    /// The caller stores the profile.
+    async fn award_xp(&mut self, address: &AccountOwner, profile: &mut UserProfile, xp: u64) -> Result<(), ViewError> {
+        let before = ProfileStanding::from(&*profile);
+        profile.xp += xp;
+        profile.level = Self::calculate_level(profile.xp, self.config.get().xp_per_level);
+        self.player_rankings.update(*address, Some(before), ProfileStanding::from(&*profile))?;
+        
+        let key = (*self.current_season.get(), *address);
+        let mut pass = self.season_passes.get(&key).await?.unwrap_or_default();
+        pass.xp += xp;
+        self.season_passes.insert(&key, pass)
+    }
+    
+    /// Update a user's Snake high score
+    pub async fn update_snake_score(
+        &mut self,
+        address: &AccountOwner,
+        score: u32,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        let current_high = self.snake_high_scores.get(address).await?.unwrap_or(0);
+        let xp_divisor = self.config.get().snake_xp_divisor.max(1);
+        
+        if score > current_high {
+            self.snake_high_scores.insert(address, score)?;
+            
+            // Update user profile
+            if let Some(mut profile) = self.users.get(address).await? {
+                profile.snake_high_score = score;
+                profile.snake_games += 1;
+                self.award_xp(address, &mut profile, score as u64).await?;
+                self.users.insert(address, profile)?;
+            }
+            
+            // Update leaderboard
+            self.update_leaderboard(GameType::Snake, address, score as u64, now).await?;
+            
+            Ok(true)
+        } else {
+            // Count the game towards leaderboard eligibility
+            self.update_leaderboard(GameType::Snake, address, score as u64, now).await?;
+            
+            // Still update games played
+            if let Some(mut profile) = self.users.get(address).await? {
+                profile.snake_games += 1;
+                self.award_xp(address, &mut profile, (score / xp_divisor) as u64).await?;
+                self.users.insert(address, profile)?;
+            }
+            Ok(false)
+        }
+    }
+    
+    /// Record a replay-verified score of a single-player game. Returns
+    /// whether it is a new high score.
+    pub async fn update_session_score(
+        &mut self,
+        game_type: GameType,
+        address: &AccountOwner,
+        score: u32,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        let key = (game_type, *address);
+        let current_high = self.high_scores.get(&key).await?.unwrap_or(0);
+        let is_new_high = score > current_high;
+        if is_new_high {
+            self.high_scores.insert(&key, score)?;
+        }
+        self.update_leaderboard(game_type, address, score as u64, now).await?;
+        
+        let xp_divisor = self.config.get().score_xp_divisor.max(1);
+        if let Some(mut profile) = self.users.get(address).await? {
+            self.award_xp(address, &mut profile, (score / xp_divisor) as u64).await?;
+            self.users.insert(address, profile)?;
+        }
+        Ok(is_new_high)
+    }
+    
+    /// Record a player's final score in a finished Yahtzee game. Returns
+    /// whether it is a new high score.
+    pub async fn update_yahtzee_score(
+        &mut self,
+        address: &AccountOwner,
+        score: u32,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        let current_high = self.yahtzee_high_scores.get(address).await?.unwrap_or(0);
+        let is_new_high = score > current_high;
+        if is_new_high {
+            self.yahtzee_high_scores.insert(address, score)?;
+        }
+        self.update_leaderboard(GameType::Yahtzee, address, score as u64, now).await?;
+        Ok(is_new_high)
+    }
+    
+    /// Record a scored Minesweeper session on the leaderboard
+    pub async fn update_minesweeper_result(
+        &mut self,
+        address: &AccountOwner,
+        won: bool,
+        score: u32,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        self.update_leaderboard(GameType::Minesweeper, address, score as u64, now).await?;
+        if let Some(mut profile) = self.users.get(address).await? {
+            let xp = if won { self.config.get().xp_per_win } else { self.config.get().xp_per_loss };
+            self.award_xp(address, &mut profile, xp).await?;
+            self.users.insert(address, profile)?;
+        }
+        Ok(())
+    }
+    
+    /// Record a player's solve of the Sudoku published on `day`, placing it
+    /// on that day's speed leaderboard. Returns `false` without recording
+    /// anything if they had already solved it.
+    pub async fn record_sudoku_solve(
+        &mut self,
+        address: &AccountOwner,
+        day: u64,
+        solve_time: u64,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        if self.sudoku_solve_times.contains_key(&(day, *address)).await? {
+            return Ok(false);
+        }
+        self.sudoku_solve_times.insert(&(day, *address), solve_time)?;
+        
+        let Some(mut profile) = self.users.get(address).await? else {
+            return Ok(true);
+        };
+        let player_name = if profile.username.is_empty() {
+            address.to_string().chars().take(8).collect()
+        } else {
+            profile.username.clone()
+        };
+        let mut board = self.sudoku_leaderboards.get(&day).await?.unwrap_or_default();
+        board.push(SudokuSolve {
+            player_name,
+            player_address: *address,
+            solve_time,
+            solved_at: now,
+        });
+        board.sort_by_key(|solve| solve.solve_time);
+        board.truncate(self.leaderboard_size());
+        self.sudoku_leaderboards.insert(&day, board)?;
+        
+        let xp = self.config.get().xp_per_win;
+        self.award_xp(address, &mut profile, xp).await?;
+        self.users.insert(address, profile)?;
+        Ok(true)
+    }
+    
+    /// Record a guess at `day`'s word. Returns `false` if the player has no
+    /// guesses left.
+    pub async fn record_word_guess(
+        &mut self,
+        address: &AccountOwner,
+        day: u64,
+        guess: String,
+    ) -> Result<bool, ViewError> {
+        let mut guesses = self.word_guesses.get(&(day, *address)).await?.unwrap_or_default();
+        if guesses.len() >= daily_word::MAX_GUESSES {
+            return Ok(false);
+        }
+        if guesses.is_empty() {
+            let mut players = self.word_players.get(&day).await?.unwrap_or_default();
+            players.push(*address);
+            self.word_players.insert(&day, players)?;
+        }
+        guesses.push(guess);
+        self.word_guesses.insert(&(day, *address), guesses)?;
+        Ok(true)
+    }
+    
+    /// Score every player's guesses at `day`'s word against the revealed
+    /// `answer`, updating their stats and the leaderboard
+    pub async fn score_daily_word(
+        &mut self,
+        day: u64,
+        answer: &str,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        let mut leaderboard = self.word_leaderboard.get().clone();
+        for address in self.word_players.get(&day).await?.unwrap_or_default() {
+            let guesses = self.word_guesses.get(&(day, address)).await?.unwrap_or_default();
+            let solved_in = guesses.iter().position(|guess| guess == answer).map(|i| i + 1);
+            
+            let mut stats = self.word_stats.get(&address).await?.unwrap_or_default();
+            stats.guess_distribution.resize(daily_word::MAX_GUESSES, 0);
+            stats.games_played += 1;
+            match solved_in {
+                Some(guess_count) => {
+                    stats.games_solved += 1;
+                    stats.guess_distribution[guess_count - 1] += 1;
+                    let continues = day.checked_sub(1).is_some_and(|yesterday| {
+                        stats.last_solved_day == Some(yesterday)
+                    });
+                    stats.current_streak = if continues { stats.current_streak + 1 } else { 1 };
+                    stats.best_streak = stats.best_streak.max(stats.current_streak);
+                    stats.last_solved_day = Some(day);
+                }
+                None => stats.current_streak = 0,
+            }
+            
+            let profile = self.get_or_create_user(&address).await?;
+            let player_name = if profile.username.is_empty() {
+                address.to_string().chars().take(8).collect()
+            } else {
+                profile.username
+            };
+            leaderboard.retain(|entry| entry.player_address != address);
+            leaderboard.push(WordLeaderboardEntry {
+                player_name,
+                player_address: address,
+                current_streak: stats.current_streak,
+                best_streak: stats.best_streak,
+                games_solved: stats.games_solved,
+                average_guesses: stats.average_guesses(),
+            });
+            self.word_stats.insert(&address, stats)?;
+            
+            let record = MatchRecord {
+                game_type: GameType::DailyWord,
+                opponent: None,
+                result: if solved_in.is_some() { MatchResult::Win } else { MatchResult::Loss },
+                score: guesses.len() as u64,
+                timestamp: now,
+            };
+            self.record_match(&address, record).await?;
+        }
+        
+        leaderboard.sort_by(|a, b| {
+            b.current_streak
+                .cmp(&a.current_streak)
+                .then(a.average_guesses.cmp(&b.average_guesses))
+                .then(b.games_solved.cmp(&a.games_solved))
+        });
+        leaderboard.truncate(self.leaderboard_size());
+        self.word_leaderboard.set(leaderboard);
+        Ok(())
+    }
+    
+    /// Draw up to `count` different questions from the trivia bank
+    pub async fn draw_trivia_questions(
+        &self,
+        seed: u64,
+        count: usize,
+    ) -> Result<Vec<TriviaQuestion>, ViewError> {
+        let total = self.trivia_questions.count();
+        let mut rng = SeededRng::new(seed);
+        let mut indices = Vec::new();
+        while indices.len() < count.min(total) {
+            let index = rng.below(total as u64) as usize;
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+        let mut questions = Vec::with_capacity(indices.len());
+        for index in indices {
+            questions.extend(self.trivia_questions.get(index).await?);
+        }
+        Ok(questions)
+    }
+    
+    /// Update Tic-Tac-Toe stats
+    pub async fn update_tictactoe_result(&mut self, address: &AccountOwner, won: bool) -> Result<(), ViewError> {
+        if let Some(mut profile) = self.users.get(address).await? {
+            let xp = if won {
+                profile.tictactoe_wins += 1;
+                self.config.get().xp_per_win
+            } else {
+                profile.tictactoe_losses += 1;
+                self.config.get().xp_per_loss
+            };
+            self.award_xp(address, &mut profile, xp).await?;
+            self.users.insert(address, profile)?;
+        }
+        
+        // Increment total games
+        let current = *self.total_games_played.get();
+        self.total_games_played.set(current + 1);
+        Ok(())
+    }
+    
+    /// Count an event towards a player's quests for `day`, granting the
+    /// rewards of every quest it completes. Returns the completed quests.
+    pub async fn record_quest_event(
+        &mut self,
+        address: &AccountOwner,
+        now: u64,
+        event: QuestEvent,
+    ) -> Result<Vec<QuestGoal>, ViewError> {
+        let mut progress = self.quest_progress.get(address).await?.unwrap_or_default();
+        let completed = progress.record(now / MICROS_PER_DAY, event);
+        self.quest_progress.insert(address, progress)?;
+        
+        if !completed.is_empty() {
+            let mut profile = self.get_or_create_user(address).await?;
+            let mut xp = 0;
+            for quest in &completed {
+                let (quest_xp, tokens) = quest.reward();
+                xp += quest_xp;
+                profile.free_play_balance.saturating_add_assign(tokens);
+            }
+            self.award_xp(address, &mut profile, xp).await?;
+            self.users.insert(address, profile)?;
+            
+            let credits = CREDITS_PER_QUEST * completed.len() as u64;
+            self.mint_credits(address, credits, CreditReason::QuestCompleted, now).await?;
+        }
+        Ok(completed)
+    }
+    
+    /// Score a room game win for the player's clan in every clan war running
+    /// at `now`, and on the season's clan leaderboard
+    pub async fn record_clan_war_win(&mut self, address: &AccountOwner, now: u64) -> Result<(), ViewError> {
+        let Some(tag) = self.users.get(address).await?.and_then(|profile| profile.clan_tag) else {
+            return Ok(());
+        };
+        let Some(clan) = self.clans.get(&tag).await? else {
+            return Ok(());
+        };
+        
+        let mut points = 0;
+        for war_id in &clan.wars {
+            let Some(mut war) = self.clan_wars.get(war_id).await? else {
+                continue;
+            };
+            if now < war.starts_at || now >= war.ends_at {
+                continue;
+            }
+            if let Some((_, score)) = war.scores.iter_mut().find(|(clan_tag, _)| *clan_tag == tag) {
+                *score += 1;
+                points += 1;
+            }
+            self.clan_wars.insert(war_id, war)?;
+        }
+        if points > 0 {
+            self.update_clan_standing(&tag, points, 0).await?;
+        }
+        Ok(())
+    }
+    
+    /// Add points and war wins to a clan's standing for the current season
+    pub async fn update_clan_standing(&mut self, tag: &str, points: u64, wars_won: u32) -> Result<(), ViewError> {
+        let season = *self.current_season.get();
+        let mut standings = self.clan_leaderboards.get(&season).await?.unwrap_or_default();
+        match standings.iter_mut().find(|standing| standing.tag == tag) {
+            Some(standing) => {
+                standing.points += points;
+                standing.wars_won += wars_won;
+            }
+            None => standings.push(ClanStanding { tag: tag.to_string(), points, wars_won }),
+        }
+        standings.sort_by(|a, b| b.points.cmp(&a.points).then(b.wars_won.cmp(&a.wars_won)));
+        self.clan_leaderboards.insert(&season, standings)
+    }
+    
+    /// Set or clear the clan tag on a player's profile
+    pub async fn set_clan_tag(&mut self, address: &AccountOwner, tag: Option<String>) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        profile.clan_tag = tag;
+        self.users.insert(address, profile)
+    }
+    
+    /// Count a finished room game towards the player's referral, paying out
+    /// to both sides once the referee has played enough
+    pub async fn record_room_game(&mut self, address: &AccountOwner, now: u64) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        profile.room_games_played += 1;
+        let referrer = profile
+            .referred_by
+            .filter(|_| profile.room_games_played == REFERRAL_GAMES_REQUIRED);
+        self.users.insert(address, profile)?;
+        
+        if let Some(referrer) = referrer {
+            for player in [*address, referrer] {
+                let mut profile = self.get_or_create_user(&player).await?;
+                self.award_xp(&player, &mut profile, REFERRAL_XP).await?;
+                self.users.insert(&player, profile)?;
+                self.mint_credits(&player, REFERRAL_CREDITS, CreditReason::Referral, now).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Mint arcade credits for a player and log the transaction
+    pub async fn mint_credits(
+        &mut self,
+        address: &AccountOwner,
+        amount: u64,
+        reason: CreditReason,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        profile.arcade_credits += amount;
+        let transaction = CreditTransaction {
+            reason,
+            delta: amount as i64,
+            balance: profile.arcade_credits,
+            timestamp: now,
+        };
+        self.users.insert(address, profile)?;
+        self.log_credits(address, transaction).await
+    }
+    
+    /// Burn arcade credits if the player has enough. Returns whether they did.
+    pub async fn burn_credits(
+        &mut self,
+        address: &AccountOwner,
+        amount: u64,
+        reason: CreditReason,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        if profile.arcade_credits < amount {
+            return Ok(false);
+        }
+        profile.arcade_credits -= amount;
+        let transaction = CreditTransaction {
+            reason,
+            delta: -(amount as i64),
+            balance: profile.arcade_credits,
+            timestamp: now,
+        };
+        self.users.insert(address, profile)?;
+        self.log_credits(address, transaction).await?;
+        Ok(true)
+    }
+    
+    /// Add a credit transaction to a player's history, dropping the oldest beyond the limit
+    async fn log_credits(&mut self, address: &AccountOwner, transaction: CreditTransaction) -> Result<(), ViewError> {
+        let history = self.credit_history.load_entry_mut(address).await?;
+        history.push_back(transaction);
+        while history.count() > MAX_CREDIT_HISTORY {
+            history.delete_front();
+        }
+        Ok(())
+    }
+    
+    /// Credit a tip to its recipient's profile and log it for both players
+    pub async fn record_tip(&mut self, tip: TipRecord) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(&tip.recipient).await?;
+        profile.tips_received_tokens.saturating_add_assign(tip.tokens);
+        profile.tips_received_credits += tip.credits;
+        self.users.insert(&tip.recipient, profile)?;
+        
+        for player in [tip.sender, tip.recipient] {
+            let history = self.tip_history.load_entry_mut(&player).await?;
+            history.push_back(tip.clone());
+            while history.count() > MAX_TIP_HISTORY {
+                history.delete_front();
+            }
+        }
+        Ok(())
+    }
+    
+    /// A player's most recent tips, sent and received, newest first
+    pub async fn get_tip_history(&self, address: &AccountOwner, limit: usize) -> Vec<TipRecord> {
+        let Ok(Some(history)) = self.tip_history.try_load_entry(address).await else {
+            return Vec::new();
+        };
+        let mut tips = history.read_back(limit).await.unwrap_or_default();
+        tips.reverse();
+        tips
+    }
+    
+    /// A player's most recent credit transactions, newest first
+    pub async fn get_credit_history(&self, address: &AccountOwner, limit: usize) -> Vec<CreditTransaction> {
+        let Ok(Some(history)) = self.credit_history.try_load_entry(address).await else {
+            return Vec::new();
+        };
+        let mut transactions = history.read_back(limit).await.unwrap_or_default();
+        transactions.reverse();
+        transactions
+    }
+    
+    /// Grant the daily free-play allowance if not yet claimed on `day`.
+    ///
+    /// Returns the amount granted, which is reduced near the lifetime cap,
+    /// or `None` if the player already claimed today or hit the cap.
+    pub async fn claim_daily_tokens(&mut self, address: &AccountOwner, day: u64) -> Result<Option<Amount>, ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        let grant = profile.claim_faucet(day);
+        if grant.is_some() {
+            self.users.insert(address, profile)?;
+        }
+        Ok(grant)
+    }
+    
+    /// Spend free-play tokens on an entry fee, if the player has enough
+    pub async fn spend_free_play(&mut self, address: &AccountOwner, amount: Amount) -> Result<bool, ViewError> {
+        let Some(mut profile) = self.users.get(address).await? else {
+            return Ok(false);
+        };
+        if profile.free_play_balance < amount {
+            return Ok(false);
+        }
+        profile.free_play_balance.saturating_sub_assign(amount);
+        self.users.insert(address, profile)?;
+        Ok(true)
+    }
+    
+    /// The mirror of a room hosted elsewhere, or an empty one if this is the
+    /// first event seen. `None` if the room belongs to this chain.
+    pub async fn room_mirror(&self, room_id: &str) -> Result<Option<RoomMirror>, ViewError> {
+        if self.rooms.contains_key(room_id).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.room_mirrors.get(room_id).await?.unwrap_or_else(|| RoomMirror {
+            room_id: room_id.to_string(),
+            players: Vec::new(),
+            status: RoomStatus::Waiting,
+            move_count: 0,
+            last_move: None,
+            winner: None,
+            scores: Vec::new(),
+        })))
+    }
+    
+    /// Rooms that are still `Waiting` past their expiry time
+    pub async fn expired_rooms(&self, now: u64) -> Result<Vec<GameRoom>, ViewError> {
+        let room_ids = self.rooms.indices().await?;
+        let mut expired = Vec::new();
+        for room_id in room_ids {
+            if let Some(room) = self.rooms.get(&room_id).await? {
+                if room.is_expired(now) {
+                    expired.push(room);
+                }
+            }
+        }
+        Ok(expired)
+    }
+    
+    /// Store a room, listing it in the lobby if it is public and under
+    /// each player seated in it until it finishes
+    pub async fn save_room(&mut self, room: GameRoom) -> Result<(), ViewError> {
+        let previous = self.rooms.get(&room.room_id).await?;
+        let seated_before = previous.as_ref().map_or(&[][..], GameRoom::seated_players);
+        for player in seated_before {
+            if !room.seated_players().contains(player) {
+                self.unlist_player_room(player, &room.room_id).await?;
+            }
+        }
+        for player in room.seated_players() {
+            if !seated_before.contains(player) {
+                let mut room_ids = self.player_rooms.get(player).await?.unwrap_or_default();
+                room_ids.push(room.room_id.clone());
+                self.player_rooms.insert(player, room_ids)?;
+            }
+        }
+        
+        if room.visibility == RoomVisibility::Public {
+            self.room_listings.insert(&room.room_id, room.listing())?;
+        } else {
+            self.room_listings.remove(&room.room_id)?;
+        }
+        self.rooms.insert(&room.room_id.clone(), room)
+    }
     
-    /// Total games played counter
-    pub total_games_played: RegisterView<u64>,
+    /// Remove a room, its lobby listing and its place in its players' rooms
+    pub async fn delete_room(&mut self, room_id: &String) -> Result<(), ViewError> {
+        if let Some(room) = self.rooms.get(room_id).await? {
+            for player in room.seated_players() {
+                self.unlist_player_room(player, room_id).await?;
+            }
+        }
+        self.room_listings.remove(room_id)?;
+        self.rooms.remove(room_id)
+    }
     
-    /// Total players registered
-    pub total_players: RegisterView<u64>,
-}
-
-/// A game room for multiplayer games
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct GameRoom {
-    pub room_id: String,
-    pub game_type: GameType,
-    pub creator: String,
-    pub players: Vec<String>,
-    pub max_players: u8,
-    pub entry_fee: u64,
-    pub status: RoomStatus,
-    pub game_state: Option<GameState>,
-    pub created_at: u64,
-}
-
-impl GameStationState {
-    /// Get or create a user profile
-    pub async fn get_or_create_user(&mut self, address: &str) -> UserProfile {
-        if let Some(profile) = self.users.get(address).await.ok().flatten() {
-            profile
+    async fn unlist_player_room(
+        &mut self,
+        player: &AccountOwner,
+        room_id: &str,
+    ) -> Result<(), ViewError> {
+        let mut room_ids = self.player_rooms.get(player).await?.unwrap_or_default();
+        room_ids.retain(|id| id != room_id);
+        if room_ids.is_empty() {
+            self.player_rooms.remove(player)
         } else {
-            let new_profile = UserProfile::default();
-            let _ = self.users.insert(address, new_profile.clone());
-            
-            // Increment total players
-            let current = self.total_players.get().unwrap_or(&0);
-            let _ = self.total_players.set(*current + 1);
-            
-            new_profile
+            self.player_rooms.insert(player, room_ids)
         }
     }
     
-    /// Update a user's Snake high score
-    pub async fn update_snake_score(&mut self, address: &str, score: u32) -> bool {
-        let current_high = self.snake_high_scores.get(address).await.ok().flatten().unwrap_or(0);
+    /// Rooms whose ready check ran out before every player confirmed
+    pub async fn missed_ready_checks(&self, now: u64) -> Result<Vec<GameRoom>, ViewError> {
+        let room_ids = self.rooms.indices().await?;
+        let mut missed = Vec::new();
+        for room_id in room_ids {
+            if let Some(room) = self.rooms.get(&room_id).await? {
+                if room.missed_ready_check(now) {
+                    missed.push(room);
+                }
+            }
+        }
+        Ok(missed)
+    }
+    
+    /// Find an opponent for `ticket` in the queue for `game_type`.
+    ///
+    /// The longest-waiting player within `rating_range` (0 for any) is taken
+    /// out of the queue and returned. If nobody fits, `ticket` is queued.
+    pub async fn match_or_enqueue(
+        &mut self,
+        game_type: GameType,
+        ticket: MatchmakingTicket,
+        rating_range: u32,
+    ) -> Result<Option<MatchmakingTicket>, ViewError> {
+        let mut queue = self.matchmaking.get(&game_type).await?.unwrap_or_default();
+        if queue.iter().any(|t| t.player == ticket.player) {
+            return Ok(None);
+        }
         
-        if score > current_high {
-            let _ = self.snake_high_scores.insert(address, score);
-            
-            // Update user profile
-            if let Some(mut profile) = self.users.get(address).await.ok().flatten() {
-                profile.snake_high_score = score;
-                profile.snake_games += 1;
-                profile.xp += score as u64;
-                profile.level = Self::calculate_level(profile.xp);
-                let _ = self.users.insert(address, profile);
+        let mut opponent_idx = None;
+        for (idx, t) in queue.iter().enumerate() {
+            if (rating_range == 0 || t.rating.abs_diff(ticket.rating) <= rating_range)
+                && !self.is_blocked_between(&t.player, &ticket.player).await?
+            {
+                opponent_idx = Some(idx);
+                break;
             }
-            
-            // Update leaderboard
-            self.update_leaderboard("snake", address, score as u64).await;
-            
-            true
-        } else {
-            // Still update games played
-            if let Some(mut profile) = self.users.get(address).await.ok().flatten() {
-                profile.snake_games += 1;
-                profile.xp += (score / 10) as u64;
-                profile.level = Self::calculate_level(profile.xp);
-                let _ = self.users.insert(address, profile);
+        }
+        let opponent = match opponent_idx {
+            Some(idx) => Some(queue.remove(idx)),
+            None => {
+                queue.push(ticket);
+                None
+            }
+        };
+        
+        self.matchmaking.insert(&game_type, queue)?;
+        Ok(opponent)
+    }
+    
+    /// Remove a player from the queue for `game_type`
+    pub async fn leave_matchmaking(
+        &mut self,
+        game_type: GameType,
+        player: &AccountOwner,
+    ) -> Result<(), ViewError> {
+        let mut queue = self.matchmaking.get(&game_type).await?.unwrap_or_default();
+        queue.retain(|t| t.player != *player);
+        self.matchmaking.insert(&game_type, queue)
+    }
+    
+    /// Send a friend request from `from` to `to`. If `to` already asked to be
+    /// friends with `from`, the request is accepted instead. Returns `false`
+    /// if `to` already has `MAX_PENDING_FRIEND_REQUESTS` waiting.
+    pub async fn send_friend_request(
+        &mut self,
+        from: &AccountOwner,
+        to: &AccountOwner,
+    ) -> Result<bool, ViewError> {
+        if from == to || self.are_friends(from, to).await {
+            return Ok(true);
+        }
+        
+        let incoming = self.friend_requests.get(from).await?.unwrap_or_default();
+        if incoming.iter().any(|r| r == to) {
+            self.accept_friend_request(from, to).await?;
+            return Ok(true);
+        }
+        
+        let mut requests = self.friend_requests.get(to).await?.unwrap_or_default();
+        if !requests.iter().any(|r| r == from) {
+            if requests.len() >= MAX_PENDING_FRIEND_REQUESTS {
+                return Ok(false);
             }
-            false
+            requests.push(*from);
+            self.friend_requests.insert(to, requests)?;
         }
+        Ok(true)
     }
     
-    /// Update Tic-Tac-Toe stats
-    pub async fn update_tictactoe_result(&mut self, address: &str, won: bool) {
-        if let Some(mut profile) = self.users.get(address).await.ok().flatten() {
-            if won {
-                profile.tictactoe_wins += 1;
-                profile.xp += 50;
+    /// Accept `requester`'s pending friend request to `player`
+    pub async fn accept_friend_request(&mut self, player: &AccountOwner, requester: &AccountOwner) -> Result<(), ViewError> {
+        let mut requests = self.friend_requests.get(player).await?.unwrap_or_default();
+        let Some(idx) = requests.iter().position(|r| r == requester) else {
+            return Ok(());
+        };
+        requests.remove(idx);
+        self.friend_requests.insert(player, requests)?;
+        
+        for (a, b) in [(player, requester), (requester, player)] {
+            let mut friends = self.friends.get(a).await?.unwrap_or_default();
+            if !friends.iter().any(|f| f == b) {
+                friends.push(*b);
+                self.friends.insert(a, friends)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Whether either player has blocked the other
+    pub async fn is_blocked_between(&self, a: &AccountOwner, b: &AccountOwner) -> Result<bool, ViewError> {
+        for (player, other) in [(a, b), (b, a)] {
+            if let Some(profile) = self.users.get(player).await? {
+                if profile.blocked.contains(other) {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+    
+    /// End a friendship, or decline/cancel a pending request between two players
+    pub async fn remove_friend(&mut self, player: &AccountOwner, other: &AccountOwner) -> Result<(), ViewError> {
+        for (a, b) in [(player, other), (other, player)] {
+            let mut friends = self.friends.get(a).await?.unwrap_or_default();
+            friends.retain(|f| f != b);
+            self.friends.insert(a, friends)?;
+            
+            let mut requests = self.friend_requests.get(a).await?.unwrap_or_default();
+            requests.retain(|r| r != b);
+            self.friend_requests.insert(a, requests)?;
+        }
+        Ok(())
+    }
+    
+    pub async fn are_friends(&self, a: &AccountOwner, b: &AccountOwner) -> bool {
+        self.friends
+            .get(a)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|friends| friends.iter().any(|f| f == b))
+    }
+    
+    /// Move a player from the search index entries of their old username to
+    /// those of their new one. Both names are expected lowercased.
+    pub async fn reindex_username(
+        &mut self,
+        address: &AccountOwner,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<(), ViewError> {
+        for prefix in name_prefixes(old_name) {
+            let mut owners = self.username_prefixes.get(&prefix).await?.unwrap_or_default();
+            owners.retain(|owner| owner != address);
+            if owners.is_empty() {
+                self.username_prefixes.remove(&prefix)?;
             } else {
-                profile.tictactoe_losses += 1;
-                profile.xp += 10;
+                self.username_prefixes.insert(&prefix, owners)?;
             }
-            profile.level = Self::calculate_level(profile.xp);
-            let _ = self.users.insert(address, profile);
         }
+        for prefix in name_prefixes(new_name) {
+            let mut owners = self.username_prefixes.get(&prefix).await?.unwrap_or_default();
+            owners.push(*address);
+            self.username_prefixes.insert(&prefix, owners)?;
+        }
+        Ok(())
+    }
+    
+    /// Players whose username starts with `query`, ignoring case
+    pub async fn search_users(&self, query: &str, limit: usize) -> Vec<(AccountOwner, UserProfile)> {
+        let query = query.to_lowercase();
+        let Some(bucket) = name_prefixes(&query).last() else {
+            return Vec::new();
+        };
+        let owners = self.username_prefixes.get(&bucket).await.ok().flatten().unwrap_or_default();
         
-        // Increment total games
-        let current = self.total_games_played.get().unwrap_or(&0);
-        let _ = self.total_games_played.set(*current + 1);
+        let mut results = Vec::new();
+        for owner in owners {
+            if results.len() == limit {
+                break;
+            }
+            // Queries longer than the indexed prefix still need a full check
+            let Some(profile) = self.users.get(&owner).await.ok().flatten() else {
+                continue;
+            };
+            if profile.username.to_lowercase().starts_with(&query) {
+                results.push((owner, profile));
+            }
+        }
+        results
+    }
+    
+    /// Add a match to a player's history, dropping the oldest beyond the
+    /// limit, and count its result towards their record and the totals
+    /// for that game
+    pub async fn record_match(&mut self, address: &AccountOwner, record: MatchRecord) -> Result<(), ViewError> {
+        if record.result != MatchResult::Completed {
+            let mut profile = self.get_or_create_user(address).await?;
+            match profile.game_records.iter_mut().find(|r| r.game_type == record.game_type) {
+                Some(game_record) => game_record.record(record.result),
+                None => {
+                    let mut game_record = GameRecord::new(record.game_type);
+                    game_record.record(record.result);
+                    profile.game_records.push(game_record);
+                }
+            }
+            self.users.insert(address, profile)?;
+        }
+        self.game_stats
+            .load_entry_mut(&format!("{:?}", record.game_type))
+            .await?
+            .record(address, record.score, record.timestamp)
+            .await?;
+        
+        let day = record.timestamp / MICROS_PER_DAY;
+        let activity = self.daily_activity.load_entry_mut(&day).await?;
+        let games = *activity.games.get();
+        activity.games.set(games + 1);
+        if !activity.players.contains_key(address).await? {
+            activity.players.insert(address, ())?;
+            let player_count = *activity.player_count.get();
+            activity.player_count.set(player_count + 1);
+        }
+        
+        let history = self.match_history.load_entry_mut(address).await?;
+        history.push_back(record);
+        while history.count() > MAX_MATCH_HISTORY {
+            history.delete_front();
+        }
+        Ok(())
+    }
+    
+    /// Count a settled match towards two players' head-to-head record
+    pub async fn record_head_to_head(
+        &mut self,
+        player_a: AccountOwner,
+        player_b: AccountOwner,
+        encounter: Encounter,
+    ) -> Result<(), ViewError> {
+        let pair = (player_a.min(player_b), player_a.max(player_b));
+        let mut record = self.head_to_head.get(&pair).await?.unwrap_or_default();
+        match encounter.winner {
+            Some(winner) if winner == pair.0 => record.first_wins += 1,
+            Some(_) => record.second_wins += 1,
+            None => record.draws += 1,
+        }
+        record.recent.push(encounter);
+        if record.recent.len() > MAX_HEAD_TO_HEAD_ENCOUNTERS {
+            record.recent.remove(0);
+        }
+        self.head_to_head.insert(&pair, record)
+    }
+    
+    /// Two players' head-to-head record as (wins of `player_a`, wins of
+    /// `player_b`, draws, recent encounters newest first)
+    pub async fn get_head_to_head(
+        &self,
+        player_a: AccountOwner,
+        player_b: AccountOwner,
+    ) -> (u32, u32, u32, Vec<Encounter>) {
+        let pair = (player_a.min(player_b), player_a.max(player_b));
+        let record = self.head_to_head.get(&pair).await.ok().flatten().unwrap_or_default();
+        let mut recent = record.recent;
+        recent.reverse();
+        if player_a == pair.0 {
+            (record.first_wins, record.second_wins, record.draws, recent)
+        } else {
+            (record.second_wins, record.first_wins, record.draws, recent)
+        }
+    }
+    
+    /// Add a finished game to the recent games feed, dropping the oldest
+    /// beyond the limit
+    pub fn record_recent_game(&mut self, game: RecentGame) {
+        self.recent_games.push_back(game);
+        while self.recent_games.count() > MAX_RECENT_GAMES {
+            self.recent_games.delete_front();
+        }
+    }
+    
+    /// A player's most recent matches, newest first
+    pub async fn get_match_history(&self, address: &AccountOwner, limit: usize) -> Vec<MatchRecord> {
+        let Ok(Some(history)) = self.match_history.try_load_entry(address).await else {
+            return Vec::new();
+        };
+        let mut records = history.read_back(limit).await.unwrap_or_default();
+        records.reverse();
+        records
+    }
+    
+    /// Append a validated move to a room's replay log
+    pub async fn log_move(&mut self, room_id: &str, record: MoveRecord) -> Result<(), ViewError> {
+        self.move_logs.load_entry_mut(room_id).await?.push(record);
+        Ok(())
+    }
+    
+    /// Every move made in a room, in order
+    pub async fn room_moves(&self, room_id: &str) -> Vec<MoveRecord> {
+        let Ok(Some(log)) = self.move_logs.try_load_entry(room_id).await else {
+            return Vec::new();
+        };
+        log.read(0..log.count()).await.unwrap_or_default()
+    }
+    
+    /// Append a chat message to a room's history, unless it is full
+    pub async fn post_room_chat(&mut self, room_id: &str, message: ChatMessage) -> Result<bool, ViewError> {
+        let log = self.room_chat.load_entry_mut(room_id).await?;
+        if log.count() >= MAX_CHAT_MESSAGES_PER_ROOM {
+            return Ok(false);
+        }
+        log.push(message);
+        Ok(true)
+    }
+    
+    /// Page through a room's chat history, oldest first
+    pub async fn room_chat_page(&self, room_id: &str, offset: usize, limit: usize) -> Vec<ChatMessage> {
+        let Ok(Some(log)) = self.room_chat.try_load_entry(room_id).await else {
+            return Vec::new();
+        };
+        let end = offset.saturating_add(limit).min(log.count());
+        if offset >= end {
+            return Vec::new();
+        }
+        log.read(offset..end).await.unwrap_or_default()
+    }
+    
+    /// Credit a prize share to a player's profile. Native tokens go to the
+    /// claimable balance, to be withdrawn with `Operation::Withdraw`.
+    pub async fn credit_winnings(
+        &mut self,
+        address: &AccountOwner,
+        native: Amount,
+        free_play: Amount,
+        tokens_won: u64,
+    ) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        let before = ProfileStanding::from(&profile);
+        profile.claimable_balance.saturating_add_assign(native);
+        profile.free_play_balance.saturating_add_assign(free_play);
+        profile.total_tokens_won += tokens_won;
+        self.player_rankings.update(*address, Some(before), ProfileStanding::from(&profile))?;
+        self.users.insert(address, profile)
+    }
+    
+    /// Deduct a withdrawal from a player's claimable balance, if it covers it
+    pub async fn take_claimable(&mut self, address: &AccountOwner, amount: Amount) -> Result<bool, ViewError> {
+        let Some(mut profile) = self.users.get(address).await? else {
+            return Ok(false);
+        };
+        if profile.claimable_balance < amount {
+            return Ok(false);
+        }
+        profile.claimable_balance.saturating_sub_assign(amount);
+        self.users.insert(address, profile)?;
+        Ok(true)
+    }
+    
+    /// Open the first season, at instantiation
+    pub fn start_seasons(&mut self, now: u64) {
+        let first_season = self.config.get().first_season;
+        self.current_season.set(first_season);
+        self.season_started_at.set(now);
     }
     
-    /// Update the leaderboard for a game type
-    async fn update_leaderboard(&mut self, game_type: &str, address: &str, score: u64) {
-        let mut entries = self.leaderboards.get(game_type).await.ok().flatten().unwrap_or_default();
+    /// Move on to a new season once the current one has run its length.
+    /// Leaderboards of past seasons stay in place as archives.
+    pub fn roll_season(&mut self, now: u64) {
+        let length = self.config.get().season_length;
+        let started_at = *self.season_started_at.get();
+        if length == 0 || now < started_at.saturating_add(length) {
+            return;
+        }
+        // Skip over any seasons in which nothing happened
+        let elapsed = (now - started_at) / length;
+        let season = *self.current_season.get();
+        self.current_season.set(season + elapsed as u32);
+        self.season_started_at.set(started_at + elapsed * length);
+    }
+    
+    /// Key of a leaderboard in the current season
+    fn season_key(&self, game_type: GameType, kind: LeaderboardKind) -> LeaderboardKey {
+        (*self.current_season.get(), game_type, kind)
+    }
+    
+    /// Update a game type's score leaderboard with a score reached at `now`
+    async fn update_leaderboard(
+        &mut self,
+        game_type: GameType,
+        address: &AccountOwner,
+        score: u64,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        let key = self.season_key(game_type, LeaderboardKind::Score);
+        let profile = self.users.get(address).await?;
+        let win_rate = profile.as_ref().map_or(0, |p| p.win_rate(game_type));
+        let board = self.leaderboards.load_entry_mut(&key).await?;
         
         // Check if player already exists
-        let existing_idx = entries.iter().position(|e| e.player_address == address);
+        let existing = board.entries.get(address).await?;
         
-        if let Some(idx) = existing_idx {
-            if entries[idx].score < score {
-                entries[idx].score = score;
-                entries[idx].games_played += 1;
-                entries[idx].timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+        let entry = if let Some(mut entry) = existing {
+            entry.games_played += 1;
+            entry.win_rate = win_rate;
+            if entry.score < score {
+                entry.score = score;
+                entry.timestamp = now;
             }
+            entry
         } else {
-            let profile = self.users.get(address).await.ok().flatten();
-            entries.push(LeaderboardEntry {
-                player_name: profile.map(|p| p.username).unwrap_or_else(|| address[..8].to_string()),
-                player_address: address.to_string(),
+            LeaderboardEntry {
+                player_name: profile.map(|p| p.username).unwrap_or_else(|| address.to_string().chars().take(8).collect()),
+                player_address: *address,
                 score,
                 games_played: 1,
-                win_rate: 100,
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-            });
+                win_rate,
+                timestamp: now,
+            }
+        };
+        
+        board.set_entry(entry).await
+    }
+    
+    /// Record a score on every time-windowed leaderboard for a game type,
+    /// starting afresh whenever a window has rolled into a new period
+    pub async fn update_windowed_leaderboards(
+        &mut self,
+        game_type: GameType,
+        address: &AccountOwner,
+        score: u64,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        let profile = self.users.get(address).await?;
+        let win_rate = profile.as_ref().map_or(0, |p| p.win_rate(game_type));
+        let player_name = profile
+            .map(|p| p.username)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| address.to_string().chars().take(8).collect());
+        
+        for window in LeaderboardWindow::ALL {
+            let key = (game_type, window);
+            let period = window.period(now);
+            let mut board = self.windowed_leaderboards.get(&key).await?.unwrap_or_default();
+            if board.period != period {
+                board = WindowedLeaderboard { period, entries: Vec::new() };
+            }
+            
+            match board.entries.iter_mut().find(|e| e.player_address == *address) {
+                Some(entry) => {
+                    entry.games_played += 1;
+                    entry.win_rate = win_rate;
+                    if entry.score < score {
+                        entry.score = score;
+                        entry.timestamp = now;
+                    }
+                }
+                None => board.entries.push(LeaderboardEntry {
+                    player_name: player_name.clone(),
+                    player_address: *address,
+                    score,
+                    games_played: 1,
+                    win_rate,
+                    timestamp: now,
+                }),
+            }
+            
+            board.entries.sort_by(LeaderboardEntry::cmp_rank);
+            board.entries.truncate(self.leaderboard_size());
+            self.windowed_leaderboards.insert(&key, board)?;
+        }
+        Ok(())
+    }
+    
+    /// Get the leaderboard for the current period of a time window
+    pub async fn get_windowed_leaderboard(
+        &self,
+        game_type: GameType,
+        window: LeaderboardWindow,
+        now: u64,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        let min_games = self.config.get().min_games_for_leaderboard;
+        let board = self.windowed_leaderboards.get(&(game_type, window)).await.ok().flatten();
+        let entries = match board {
+            // A stale period means nobody has played yet in the current one
+            Some(board) if board.period == window.period(now) => board.entries,
+            _ => return Vec::new(),
+        };
+        self.hide_banned(entries)
+            .await
+            .into_iter()
+            .filter(|e| e.qualifies(min_games))
+            .take(limit as usize)
+            .collect()
+    }
+    
+    /// Apply an ELO update for a rated match between two players.
+    ///
+    /// `winner` is `None` for a draw. Both ratings and the ratings
+    /// leaderboard for `game_type` are updated.
+    pub async fn record_rated_match(
+        &mut self,
+        game_type: GameType,
+        player_a: &AccountOwner,
+        player_b: &AccountOwner,
+        winner: Option<AccountOwner>,
+        now: u64,
+    ) -> Result<(), ViewError> {
+        if !game_type.is_rated() {
+            return Ok(());
         }
+        let mut profile_a = self.get_or_create_user(player_a).await?;
+        let mut profile_b = self.get_or_create_user(player_b).await?;
         
-        // Sort by score descending and keep top 100
-        entries.sort_by(|a, b| b.score.cmp(&a.score));
-        entries.truncate(100);
+        let score_a = match winner {
+            Some(w) if w == *player_a => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        };
+        let (rating_a, rating_b) = Self::elo_update(profile_a.rating, profile_b.rating, score_a);
+        profile_a.rating = rating_a;
+        profile_b.rating = rating_b;
         
-        let _ = self.leaderboards.insert(game_type, entries);
+        self.users.insert(player_a, profile_a.clone())?;
+        self.users.insert(player_b, profile_b.clone())?;
+        
+        let key = self.season_key(game_type, LeaderboardKind::Rating);
+        let board = self.leaderboards.load_entry_mut(&key).await?;
+        for (address, profile) in [(player_a, profile_a), (player_b, profile_b)] {
+            let win_rate = profile.win_rate(game_type);
+            let entry = match board.entries.get(address).await? {
+                Some(mut entry) => {
+                    entry.score = profile.rating as u64;
+                    entry.games_played += 1;
+                    entry.win_rate = win_rate;
+                    entry.timestamp = now;
+                    entry
+                }
+                None => LeaderboardEntry {
+                    player_name: if profile.username.is_empty() {
+                        address.to_string().chars().take(8).collect()
+                    } else {
+                        profile.username
+                    },
+                    player_address: *address,
+                    score: profile.rating as u64,
+                    games_played: 1,
+                    win_rate,
+                    timestamp: now,
+                },
+            };
+            board.set_entry(entry).await?;
+        }
+        Ok(())
+    }
+    
+    /// New ratings after a match where player A scored `score_a` (1, 0.5 or 0)
+    fn elo_update(rating_a: u32, rating_b: u32, score_a: f64) -> (u32, u32) {
+        let expected_a = 1.0 / (1.0 + 10f64.powf((rating_b as f64 - rating_a as f64) / 400.0));
+        let delta = (RATING_K_FACTOR * (score_a - expected_a)).round();
+        let new_a = (rating_a as f64 + delta).max(0.0) as u32;
+        let new_b = (rating_b as f64 - delta).max(0.0) as u32;
+        (new_a, new_b)
     }
     
     /// Calculate level from XP
-    fn calculate_level(xp: u64) -> u32 {
-        // Level formula: level = sqrt(xp / 100) + 1
-        ((xp as f64 / 100.0).sqrt() as u32) + 1
+    fn calculate_level(xp: u64, xp_per_level: u64) -> u32 {
+        // Level formula: level = sqrt(xp / xp_per_level) + 1
+        ((xp as f64 / xp_per_level.max(1) as f64).sqrt() as u32) + 1
+    }
+    
+    /// Entries kept on each leaderboard
+    fn leaderboard_size(&self) -> usize {
+        self.config.get().leaderboard_size as usize
+    }
+    
+    /// Record one address's consent to an identity link.
+    ///
+    /// Returns `true` when both addresses have consented and the link is stored.
+    pub async fn record_identity_consent(
+        &mut self,
+        primary: &AccountOwner,
+        secondary: &AccountOwner,
+        consenting: &AccountOwner,
+    ) -> Result<bool, ViewError> {
+        if primary == secondary || (consenting != primary && consenting != secondary) {
+            return Ok(false);
+        }
+        
+        let key = (*primary, *secondary);
+        let mut consents = self.identity_link_consents.get(&key).await?.unwrap_or_default();
+        if !consents.iter().any(|c| c == consenting) {
+            consents.push(*consenting);
+        }
+        
+        let complete = consents.iter().any(|c| c == primary) && consents.iter().any(|c| c == secondary);
+        if complete {
+            self.identity_links.insert(secondary, *primary)?;
+            self.identity_link_consents.remove(&key)?;
+        } else {
+            self.identity_link_consents.insert(&key, consents)?;
+        }
+        Ok(complete)
+    }
+    
+    /// Whether `secondary` may be linked to `primary`. Links are one level
+    /// deep, so an address that is already linked can't be linked again or
+    /// become a primary, and a primary can't be linked to another.
+    pub async fn can_link_identity(
+        &self,
+        primary: &AccountOwner,
+        secondary: &AccountOwner,
+    ) -> Result<bool, ViewError> {
+        if self.identity_links.contains_key(secondary).await?
+            || self.identity_links.contains_key(primary).await?
+        {
+            return Ok(false);
+        }
+        // Links are rare, so primaries aren't indexed
+        let mut secondary_is_primary = false;
+        self.identity_links
+            .for_each_index_value(|_, linked_primary| {
+                secondary_is_primary |= *linked_primary == *secondary;
+                Ok(())
+            })
+            .await?;
+        Ok(!secondary_is_primary)
+    }
+    
+    /// Resolve an address to its primary identity, collapsing linked addresses
+    pub async fn primary_identity(
+        &self,
+        address: &AccountOwner,
+    ) -> Result<AccountOwner, ViewError> {
+        Ok(self.identity_links.get(address).await?.unwrap_or(*address))
+    }
+    
+    /// A player's entry on one of this chain's leaderboards
+    pub async fn leaderboard_entry(
+        &self,
+        game_type: GameType,
+        kind: LeaderboardKind,
+        address: &AccountOwner,
+    ) -> Result<Option<LeaderboardEntry>, ViewError> {
+        match self.leaderboards.try_load_entry(&self.season_key(game_type, kind)).await? {
+            Some(leaderboard) => leaderboard.entries.get(address).await,
+            None => Ok(None),
+        }
+    }
+    
+    /// Merge an entry from some chain into the global `kind` leaderboard
+    /// for `game_type`. Each player keeps their best entry from any chain.
+    pub async fn merge_global_entry(
+        &mut self,
+        game_type: GameType,
+        kind: LeaderboardKind,
+        entry: LeaderboardEntry,
+    ) -> Result<(), ViewError> {
+        let key = (game_type, kind);
+        let mut entries = self.global_leaderboards.get(&key).await?.unwrap_or_default();
+        match entries.iter_mut().find(|e| e.player_address == entry.player_address) {
+            Some(existing) if existing.score <= entry.score => *existing = entry,
+            Some(_) => {}
+            None => entries.push(entry),
+        }
+        entries.sort_by(LeaderboardEntry::cmp_rank);
+        entries.truncate(self.leaderboard_size());
+        self.global_leaderboards.insert(&key, entries)
+    }
+    
+    /// File a report against a player, replacing any earlier report by the same reporter
+    pub async fn file_report(&mut self, player: &AccountOwner, report: PlayerReport) -> Result<(), ViewError> {
+        let mut reports = self.reports.get(player).await?.unwrap_or_default();
+        reports.retain(|r| r.reporter != report.reporter);
+        reports.push(report);
+        self.reports.insert(player, reports)
+    }
+    
+    /// Leaderboard entries with banned players left out
+    pub async fn hide_banned(&self, entries: Vec<LeaderboardEntry>) -> Vec<LeaderboardEntry> {
+        let mut visible = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if !self.banned.contains_key(&entry.player_address).await.unwrap_or(false) {
+                visible.push(entry);
+            }
+        }
+        visible
+    }
+    
+    /// Reset a player at the maximum level back to level 1, raising their
+    /// prestige rank and unlocking its cosmetics. Returns the new rank, or
+    /// `None` if the player is below the maximum level.
+    pub async fn prestige(&mut self, address: &AccountOwner) -> Result<Option<u32>, ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        if profile.level < self.config.get().max_level {
+            return Ok(None);
+        }
+        
+        let before = ProfileStanding::from(&profile);
+        profile.prestige += 1;
+        profile.xp = 0;
+        profile.level = 1;
+        self.player_rankings.update(*address, Some(before), ProfileStanding::from(&profile))?;
+        for cosmetic in ["badge", "frame"] {
+            profile.unlocked_cosmetics.push(format!("prestige-{}-{}", cosmetic, profile.prestige));
+        }
+        let rank = profile.prestige;
+        self.users.insert(address, profile)?;
+        Ok(Some(rank))
+    }
+    
+    /// Buy a shop cosmetic into the player's inventory, burning its price in
+    /// arcade credits. Returns `false` if the player cannot afford it.
+    pub async fn buy_cosmetic(
+        &mut self,
+        address: &AccountOwner,
+        item: &cosmetics::CosmeticItem,
+        now: u64,
+    ) -> Result<bool, ViewError> {
+        if !self.burn_credits(address, item.price, CreditReason::CosmeticPurchase, now).await? {
+            return Ok(false);
+        }
+        let mut profile = self.get_or_create_user(address).await?;
+        profile.unlocked_cosmetics.push(item.item_id.to_string());
+        self.users.insert(address, profile)?;
+        Ok(true)
+    }
+    
+    /// Equip a cosmetic in `slot`, replacing whatever was there, or clear the
+    /// slot. The caller checks ownership and that the item fits the slot.
+    pub async fn equip_cosmetic(
+        &mut self,
+        address: &AccountOwner,
+        slot: CosmeticSlot,
+        item_id: Option<String>,
+    ) -> Result<(), ViewError> {
+        let mut profile = self.get_or_create_user(address).await?;
+        profile.equipped_cosmetics.retain(|equipped| equipped.slot != slot);
+        if let Some(item_id) = item_id {
+            profile.equipped_cosmetics.push(EquippedCosmetic { slot, item_id });
+        }
+        self.users.insert(address, profile)
+    }
+    
+    /// Leaderboard entries of players with at least the given prestige rank
+    pub async fn with_min_prestige(&self, entries: Vec<LeaderboardEntry>, min_prestige: u32) -> Vec<LeaderboardEntry> {
+        let mut filtered = Vec::new();
+        for entry in entries {
+            let prestige = self
+                .users
+                .get(&entry.player_address)
+                .await
+                .ok()
+                .flatten()
+                .map_or(0, |profile| profile.prestige);
+            if prestige >= min_prestige {
+                filtered.push(entry);
+            }
+        }
+        filtered
+    }
+    
+    /// Remove a player from the current season's score leaderboard and the
+    /// global score leaderboard for a game. Returns whether any entry was
+    /// removed.
+    pub async fn remove_leaderboard_entry(
+        &mut self,
+        game_type: GameType,
+        address: &AccountOwner,
+    ) -> Result<bool, ViewError> {
+        let key = self.season_key(game_type, LeaderboardKind::Score);
+        let leaderboard = self.leaderboards.load_entry_mut(&key).await?;
+        let removed_local = leaderboard.remove_entry(address).await?;
+        let global_key = (game_type, LeaderboardKind::Score);
+        let mut global = self.global_leaderboards.get(&global_key).await?.unwrap_or_default();
+        let before = global.len();
+        global.retain(|e| e.player_address != *address);
+        let removed = removed_local || global.len() < before;
+        
+        self.global_leaderboards.insert(&global_key, global)?;
+        Ok(removed)
+    }
+    
+    /// Get the current season's leaderboard entries, hiding players below the minimum games threshold
+    pub async fn get_leaderboard(
+        &self,
+        game_type: GameType,
+        kind: LeaderboardKind,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        let key = self.season_key(game_type, kind);
+        self.get_season_leaderboard(key, LeaderboardSort::Score, limit).await
+    }
+    
+    /// Get a leaderboard as it stands, or stood at the end of, its season,
+    /// in `sort` order
+    pub async fn get_season_leaderboard(
+        &self,
+        key: LeaderboardKey,
+        sort: LeaderboardSort,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        let min_games = self.config.get().min_games_for_leaderboard;
+        let Ok(Some(board)) = self.leaderboards.try_load_entry(&key).await else {
+            return Vec::new();
+        };
+        
+        // Read down the ranking until enough entries are left after filtering
+        let limit = limit as usize;
+        let mut visible = Vec::new();
+        let mut read = 0;
+        while visible.len() < limit {
+            let wanted = limit - visible.len();
+            let players = board.ranked_players(sort, read, wanted).await.unwrap_or_default();
+            if players.is_empty() {
+                break;
+            }
+            read += players.len();
+            let mut entries = Vec::with_capacity(players.len());
+            for player in players {
+                if let Ok(Some(entry)) = board.entries.get(&player).await {
+                    entries.push(entry);
+                }
+            }
+            visible.extend(
+                self.hide_banned(entries).await.into_iter().filter(|e| e.qualifies(min_games)),
+            );
+        }
+        visible
+    }
+    
+    /// Up to `limit` players in `ranking` order, leaving out banned players
+    pub async fn top_players(
+        &self,
+        ranking: PlayerRanking,
+        limit: u32,
+    ) -> Vec<(AccountOwner, UserProfile)> {
+        let limit = limit as usize;
+        let mut players = Vec::new();
+        let mut read = 0;
+        while players.len() < limit {
+            let wanted = limit - players.len();
+            let ranked = self
+                .player_rankings
+                .ranked_players(ranking, read, wanted)
+                .await
+                .unwrap_or_default();
+            if ranked.is_empty() {
+                break;
+            }
+            read += ranked.len();
+            for player in ranked {
+                if self.banned.contains_key(&player).await.unwrap_or(false) {
+                    continue;
+                }
+                if let Ok(Some(profile)) = self.users.get(&player).await {
+                    players.push((player, profile));
+                }
+            }
+        }
+        players
+    }
+    
+    /// A player's position and entry on a leaderboard as it stands, or
+    /// stood at the end of, a given season. Banned players have no rank.
+    pub async fn get_leaderboard_rank(
+        &self,
+        season: u32,
+        game_type: GameType,
+        address: &AccountOwner,
+    ) -> Option<(u32, LeaderboardEntry)> {
+        if self.banned.contains_key(address).await.unwrap_or(false) {
+            return None;
+        }
+        let key = (season, game_type, LeaderboardKind::Score);
+        let board = self.leaderboards.try_load_entry(&key).await.ok().flatten()?;
+        board.position(address).await.ok().flatten()
+    }
+    
+    /// Players in each score bucket of a season leaderboard, lowest first
+    pub async fn get_score_distribution(
+        &self,
+        season: u32,
+        game_type: GameType,
+    ) -> Vec<(u32, u32)> {
+        let key = (season, game_type, LeaderboardKind::Score);
+        match self.leaderboards.try_load_entry(&key).await {
+            Ok(Some(board)) => board.distribution().await.unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+    
+    /// Count tokens staked at `now` towards the day's activity
+    pub async fn record_daily_wager(&mut self, amount: Amount, now: u64) -> Result<(), ViewError> {
+        let activity = self.daily_activity.load_entry_mut(&(now / MICROS_PER_DAY)).await?;
+        let wagered = activity.wagered.get().saturating_add(amount);
+        activity.wagered.set(wagered);
+        Ok(())
+    }
+    
+    /// Games played, players and tokens wagered on each day from
+    /// `first_day` to `last_day`, with zeros for days without any
+    pub async fn get_daily_activity(
+        &self,
+        first_day: u64,
+        last_day: u64,
+    ) -> Vec<(u64, u64, u64, Amount)> {
+        let mut days = Vec::new();
+        for day in first_day..=last_day {
+            let totals = match self.daily_activity.try_load_entry(&day).await {
+                Ok(Some(activity)) => {
+                    (*activity.games.get(), *activity.player_count.get(), *activity.wagered.get())
+                }
+                _ => (0, 0, Amount::ZERO),
+            };
+            days.push((day, totals.0, totals.1, totals.2));
+        }
+        days
+    }
+    
+    /// Whether a player has played enough games to appear on a leaderboard
+    pub async fn is_leaderboard_eligible(
+        &self,
+        game_type: GameType,
+        address: &AccountOwner,
+    ) -> bool {
+        let min_games = self.config.get().min_games_for_leaderboard;
+        let entry = self.leaderboard_entry(game_type, LeaderboardKind::Score, address);
+        let entry = entry.await.ok().flatten();
+        entry.is_some_and(|e| e.qualifies(min_games))
+    }
+}
+
+/// The indexed prefixes of a lowercased username, shortest first
+fn name_prefixes(name: &str) -> Vec<String> {
+    name.char_indices()
+        .map(|(idx, c)| name[..idx + c.len_utf8()].to_string())
+        .take(MAX_SEARCH_PREFIX_LEN)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::{base::CryptoHash, util::BlockingWait, views::KeyValueStore};
+    
+    use super::*;
+    
+    fn player(byte: u8) -> AccountOwner {
+        AccountOwner::Address20([byte; 20])
+    }
+    
+    /// Empty state on an in-memory store
+    fn empty_state() -> GameStationState {
+        let context =
+            ViewStorageContext::new_unchecked(KeyValueStore::mock().to_mut(), Vec::new(), ());
+        GameStationState::load(context).blocking_wait().expect("the mock store is readable")
+    }
+    
+    /// A best-of-3 TicTacToe room between two players, in progress
+    fn best_of_three() -> GameRoom {
+        let chain_id = ChainId(CryptoHash::from([0; 4]));
+        let mut room =
+            GameRoom::new("room".into(), GameType::TicTacToe, player(1), chain_id, 2, 0, 60);
+        room.players.push(player(2));
+        room.series_target = 2;
+        room.start(60);
+        room
+    }
+    
+    #[test]
+    fn best_of_three_ends_two_nil() {
+        let mut room = best_of_three();
+        
+        assert!(!room.record_round(Some(player(1))));
+        assert_eq!(room.status, RoomStatus::InProgress);
+        assert!(room.game_state.is_some());
+        assert_eq!(room.series_winner(), None);
+        
+        assert!(room.record_round(Some(player(1))));
+        assert_eq!(room.status, RoomStatus::Finished);
+        assert_eq!(room.series_winner(), Some(player(1)));
+        assert_eq!(room.round_winners, vec![Some(player(1)), Some(player(1))]);
+    }
+    
+    #[test]
+    fn best_of_three_ends_two_one() {
+        let mut room = best_of_three();
+        
+        assert!(!room.record_round(Some(player(1))));
+        assert!(!room.record_round(Some(player(2))));
+        // A drawn round doesn't count towards the series
+        assert!(!room.record_round(None));
+        assert_eq!(room.series_winner(), None);
+        
+        assert!(room.record_round(Some(player(2))));
+        assert_eq!(room.status, RoomStatus::Finished);
+        assert_eq!(room.series_winner(), Some(player(2)));
+        assert_eq!(room.series_wins, vec![(player(1), 1), (player(2), 2)]);
+    }
+    
+    #[test]
+    fn leaderboard_hides_players_below_the_game_threshold() {
+        let mut state = empty_state();
+        state.config.set(GameStationConfig { min_games_for_leaderboard: 3, ..Default::default() });
+        
+        // The top score belongs to a player who hasn't played enough games yet
+        let key = (0, GameType::Snake, LeaderboardKind::Score);
+        let board = state.leaderboards.load_entry_mut(&key).blocking_wait().unwrap();
+        for (byte, score, games_played) in [(1, 300, 1), (2, 200, 3), (3, 100, 7)] {
+            let entry = LeaderboardEntry {
+                player_name: format!("player{}", byte),
+                player_address: player(byte),
+                score,
+                games_played,
+                win_rate: 0,
+                timestamp: 0,
+            };
+            board.set_entry(entry).blocking_wait().unwrap();
+        }
+        
+        let shown = |state: &GameStationState, limit| -> Vec<AccountOwner> {
+            state
+                .get_leaderboard(GameType::Snake, LeaderboardKind::Score, limit)
+                .blocking_wait()
+                .into_iter()
+                .map(|entry| entry.player_address)
+                .collect()
+        };
+        assert_eq!(shown(&state, 10), vec![player(2), player(3)]);
+        // The hidden leader doesn't take up a place in the limit
+        assert_eq!(shown(&state, 1), vec![player(2)]);
+        
+        // With no threshold everyone is shown
+        state.config.get_mut().min_games_for_leaderboard = 0;
+        assert_eq!(shown(&state, 10), vec![player(1), player(2), player(3)]);
+    }
+    
+    #[test]
+    fn match_history_migrates_to_game_types() {
+        let mut state = empty_state();
+        state.schema_version.set(2);
+        let legacy = state.legacy_match_history.load_entry_mut(&player(1)).blocking_wait().unwrap();
+        for name in ["Chess", "Pinball", "Snake"] {
+            legacy.push_back(LegacyMatchRecord {
+                game_type: name.to_string(),
+                opponent: None,
+                result: MatchResult::Win,
+                score: 0,
+                timestamp: 0,
+            });
+        }
+        
+        state.migrate().blocking_wait().unwrap();
+        
+        // A name no game has is dropped; newest first
+        let history = state.get_match_history(&player(1), 10).blocking_wait();
+        let games: Vec<GameType> = history.iter().map(|record| record.game_type).collect();
+        assert_eq!(games, vec![GameType::Snake, GameType::Chess]);
+        assert!(state.legacy_match_history.indices().blocking_wait().unwrap().is_empty());
+        assert_eq!(*state.schema_version.get(), SCHEMA_VERSION);
     }
     
-    /// Get leaderboard entries
-    pub async fn get_leaderboard(&self, game_type: &str, limit: u32) -> Vec<LeaderboardEntry> {
-        let entries = self.leaderboards.get(game_type).await.ok().flatten().unwrap_or_default();
-        entries.into_iter().take(limit as usize).collect()
+    #[test]
+    fn identity_links_stay_one_level_deep() {
+        let mut state = empty_state();
+        let can_link = |state: &GameStationState, primary, secondary| {
+            state.can_link_identity(&player(primary), &player(secondary)).blocking_wait().unwrap()
+        };
+        assert!(can_link(&state, 1, 2));
+        state.identity_links.insert(&player(2), player(1)).unwrap();
+        
+        // The secondary is taken, and neither end of the link can be chained on
+        assert!(!can_link(&state, 3, 2));
+        assert!(!can_link(&state, 2, 3));
+        assert!(!can_link(&state, 3, 1));
+        // A primary can have several addresses linked to it
+        assert!(can_link(&state, 1, 3));
+        assert_eq!(state.primary_identity(&player(2)).blocking_wait().unwrap(), player(1));
     }
 }
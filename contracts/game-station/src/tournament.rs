@@ -0,0 +1,237 @@
+//! Linera Game Station - Tournaments
+//!
+//! Pairing and ranking for tournaments. Like the game engines these are pure
+//! functions over plain data, so the contract, the service and off-chain
+//! tooling all agree on standings.
+
+use async_graphql::{Enum, SimpleObject};
+use linera_sdk::base::AccountOwner;
+use serde::{Deserialize, Serialize};
+
+/// How a tournament pairs its players
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TournamentFormat {
+    /// A fixed number of rounds, each pairing players on similar scores
+    /// who have not met yet
+    Swiss { rounds: u8 },
+    /// Every player meets every other once, with the fixtures spread evenly
+    /// over a season `window` (microseconds)
+    League { window: u64 },
+}
+
+impl TournamentFormat {
+    /// Whether the format's settings can produce a tournament
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Swiss { rounds } => *rounds > 0,
+            Self::League { window } => *window > 0,
+        }
+    }
+    
+    /// Points awarded for each result
+    pub fn points_table(&self) -> PointsTable {
+        match self {
+            Self::Swiss { .. } => PointsTable { win: 2, draw: 1 },
+            Self::League { .. } => PointsTable { win: 3, draw: 1 },
+        }
+    }
+}
+
+/// Points for a win and for a draw; a loss scores nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointsTable {
+    pub win: u32,
+    pub draw: u32,
+}
+
+/// Lifecycle of a tournament
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum TournamentStatus {
+    Registration,
+    /// Registration has closed and registered players must check in
+    CheckIn,
+    InProgress,
+    Finished,
+    /// Too few players checked in; every entry fee was refunded
+    Cancelled,
+}
+
+/// Two players seated against each other in one round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct Pairing {
+    pub round: u8,
+    pub player_a: AccountOwner,
+    /// `None` for a bye, which counts as a win for `player_a`
+    pub player_b: Option<AccountOwner>,
+    /// Room the game is played in; byes have none
+    pub room_id: Option<String>,
+    pub finished: bool,
+    /// `None` on a draw, once finished
+    pub winner: Option<AccountOwner>,
+    /// End of the fixture's matchday (microseconds), for scheduled formats
+    pub due_by: Option<u64>,
+}
+
+impl Pairing {
+    /// Whether `player` sits on either side of this pairing
+    pub fn involves(&self, player: &AccountOwner) -> bool {
+        self.player_a == *player || self.player_b.as_ref() == Some(player)
+    }
+    
+    /// The other player, if `player` was not given a bye
+    pub fn opponent_of(&self, player: &AccountOwner) -> Option<AccountOwner> {
+        if self.player_a == *player {
+            self.player_b
+        } else if self.player_b.as_ref() == Some(player) {
+            Some(self.player_a)
+        } else {
+            None
+        }
+    }
+    
+    /// Points `player` took from this pairing. A bye scores as a win.
+    pub fn points_for(&self, player: &AccountOwner, table: PointsTable) -> u32 {
+        if !self.finished || !self.involves(player) {
+            return 0;
+        }
+        match self.winner {
+            Some(winner) if winner == *player => table.win,
+            Some(_) => 0,
+            None => table.draw,
+        }
+    }
+}
+
+/// One round of a tournament's bracket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct TournamentRound {
+    pub round: u8,
+    pub pairings: Vec<Pairing>,
+    /// Every game of the round has a result
+    pub complete: bool,
+}
+
+/// Group `pairings` into rounds, in round order
+pub fn rounds(pairings: &[Pairing]) -> Vec<TournamentRound> {
+    let mut rounds: Vec<TournamentRound> = Vec::new();
+    for pairing in pairings {
+        match rounds.iter_mut().find(|round| round.round == pairing.round) {
+            Some(round) => {
+                round.complete &= pairing.finished;
+                round.pairings.push(pairing.clone());
+            }
+            None => rounds.push(TournamentRound {
+                round: pairing.round,
+                pairings: vec![pairing.clone()],
+                complete: pairing.finished,
+            }),
+        }
+    }
+    rounds.sort_by_key(|round| round.round);
+    rounds
+}
+
+/// A player's place in a tournament
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct Standing {
+    pub player: AccountOwner,
+    pub points: u32,
+    /// Sum of the points of every opponent faced, used to break ties
+    pub buchholz: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+/// Rank players by points, then Buchholz. Remaining ties keep registration order.
+pub fn standings(players: &[AccountOwner], pairings: &[Pairing], table: PointsTable) -> Vec<Standing> {
+    let points = |player: &AccountOwner| -> u32 {
+        pairings.iter().map(|pairing| pairing.points_for(player, table)).sum()
+    };
+    
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|player| {
+            let played: Vec<&Pairing> = pairings
+                .iter()
+                .filter(|pairing| pairing.finished && pairing.involves(player))
+                .collect();
+            let count = |wanted: fn(&Pairing, &AccountOwner) -> bool| {
+                played.iter().filter(|pairing| wanted(pairing, player)).count() as u32
+            };
+            Standing {
+                player: *player,
+                points: points(player),
+                buchholz: pairings
+                    .iter()
+                    .filter_map(|pairing| pairing.opponent_of(player))
+                    .map(|opponent| points(&opponent))
+                    .sum(),
+                wins: count(|pairing, player| pairing.winner == Some(*player)),
+                draws: count(|pairing, _| pairing.winner.is_none()),
+                losses: count(|pairing, player| pairing.winner.is_some_and(|w| w != *player)),
+            }
+        })
+        .collect();
+    standings.sort_by(|a, b| b.points.cmp(&a.points).then(b.buchholz.cmp(&a.buchholz)));
+    standings
+}
+
+/// Pair the next Swiss round from the current standings.
+///
+/// With an odd field the lowest-ranked player who has not had a bye sits
+/// out. Everyone else is paired top-down with the highest-ranked player they
+/// have not met yet; a rematch only happens when no fresh opponent is left.
+pub fn swiss_pairings(
+    standings: &[Standing],
+    pairings: &[Pairing],
+) -> Vec<(AccountOwner, Option<AccountOwner>)> {
+    let mut unpaired: Vec<AccountOwner> = standings.iter().map(|s| s.player).collect();
+    let mut round = Vec::new();
+    
+    if unpaired.len() % 2 == 1 {
+        let had_bye = |player: &AccountOwner| {
+            pairings.iter().any(|p| p.player_a == *player && p.player_b.is_none())
+        };
+        let idx = unpaired
+            .iter()
+            .rposition(|player| !had_bye(player))
+            .unwrap_or(unpaired.len() - 1);
+        round.push((unpaired.remove(idx), None));
+    }
+    
+    while !unpaired.is_empty() {
+        let player = unpaired.remove(0);
+        let have_met = |other: &AccountOwner| {
+            pairings.iter().any(|p| p.involves(&player) && p.involves(other))
+        };
+        let idx = unpaired.iter().position(|other| !have_met(other)).unwrap_or(0);
+        round.push((player, Some(unpaired.remove(idx))));
+    }
+    round
+}
+
+/// Every fixture of a single round-robin, as `(round, home, away)` with
+/// rounds numbered from 1.
+///
+/// Uses the circle method: one player stays put while the rest rotate, so
+/// each round everyone plays at most once. With an odd field a different
+/// player sits out each round.
+pub fn round_robin_fixtures(players: &[AccountOwner]) -> Vec<(u8, AccountOwner, AccountOwner)> {
+    let mut seats: Vec<Option<AccountOwner>> = players.iter().copied().map(Some).collect();
+    if seats.len() % 2 == 1 {
+        seats.push(None);
+    }
+    let seat_count = seats.len();
+    
+    let mut fixtures = Vec::new();
+    for round in 1..seat_count {
+        for idx in 0..seat_count / 2 {
+            if let (Some(home), Some(away)) = (seats[idx], seats[seat_count - 1 - idx]) {
+                fixtures.push((round as u8, home, away));
+            }
+        }
+        seats[1..].rotate_right(1);
+    }
+    fixtures
+}